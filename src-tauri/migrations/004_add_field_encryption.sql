@@ -0,0 +1,5 @@
+-- Marca quais linhas já têm as colunas sensíveis cifradas pela aplicação
+-- (além do SQLCipher). 0 = texto puro, 1 = AES-GCM com a chave de campos.
+-- As linhas existentes são cifradas no Rust logo após as migrations.
+ALTER TABLE secrets ADD COLUMN encryption_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE attachments ADD COLUMN encryption_version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Registro de acessos a valores sensíveis (ex: revelar uma senha).
+-- Sem FK para que o histórico sobreviva à exclusão do segredo.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    secret_id INTEGER,
+    field TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_secret_id ON audit_log(secret_id);
@@ -0,0 +1,10 @@
+-- Nome da variável de ambiente usada para cada segredo de um projeto no
+-- `secrets-cli run`. Segredos sem entrada aqui usam o nome derivado do título.
+CREATE TABLE IF NOT EXISTS project_env_vars (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    secret_title TEXT NOT NULL,
+    env_name TEXT NOT NULL,
+    UNIQUE (project_id, secret_title),
+    UNIQUE (project_id, env_name)
+);
@@ -0,0 +1,7 @@
+-- Tipo de cada segredo. Tudo o que existia até aqui é login (usuário e senha).
+ALTER TABLE secrets ADD COLUMN kind TEXT NOT NULL DEFAULT 'login';
+UPDATE secrets SET kind = 'login';
+
+-- Dados estruturados dos tipos que não são login, em JSON cifrado pela
+-- aplicação como password_blob. NULL para logins.
+ALTER TABLE secrets ADD COLUMN payload_blob BLOB;
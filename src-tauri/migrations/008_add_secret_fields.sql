@@ -0,0 +1,15 @@
+-- Campos personalizados dos segredos. Campos ocultos ficam cifrados pela
+-- aplicação em value_blob (como password_blob); os demais em value.
+CREATE TABLE IF NOT EXISTS secret_fields (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    secret_id INTEGER NOT NULL REFERENCES secrets(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    field_type TEXT NOT NULL DEFAULT 'text',
+    value TEXT,
+    value_blob BLOB,
+    position INTEGER NOT NULL DEFAULT 0,
+    encryption_version INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (secret_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_secret_fields_secret_id ON secret_fields(secret_id);
@@ -0,0 +1,19 @@
+-- Histórico de versões dos segredos. Cada linha guarda o estado anterior de
+-- um segredo (título, usuário, senha, payload e campos) em JSON cifrado pela
+-- aplicação, como password_blob.
+CREATE TABLE IF NOT EXISTS secret_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    secret_id INTEGER NOT NULL REFERENCES secrets(id) ON DELETE CASCADE,
+    snapshot_blob BLOB NOT NULL,
+    created_at TEXT NOT NULL,
+    encryption_version INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_secret_versions_secret_id ON secret_versions(secret_id);
+
+-- Configurações que pertencem ao cofre (e não à instalação), como o limite
+-- de versões guardadas por segredo.
+CREATE TABLE IF NOT EXISTS vault_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
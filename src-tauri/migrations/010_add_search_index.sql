@@ -0,0 +1,129 @@
+-- Índices de busca (FTS5). Só entram textos que já ficam em claro no banco:
+-- título, usuário, projeto, nomes de anexos e campos personalizados não
+-- ocultos (URLs e notas). Senhas, payloads e campos ocultos nunca são
+-- indexados. O rowid de cada índice é o id do segredo ou do projeto.
+CREATE VIRTUAL TABLE IF NOT EXISTS secrets_fts USING fts5(
+    title,
+    username,
+    urls,
+    notes,
+    project,
+    attachments,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS projects_fts USING fts5(
+    name,
+    description,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Documento indexado de cada segredo. Os gatilhos abaixo regravam a linha do
+-- índice a partir desta view sempre que algo que ela lê muda, na mesma
+-- transação da alteração.
+CREATE VIEW IF NOT EXISTS secret_search_documents AS
+SELECT
+    s.id,
+    s.title,
+    s.username,
+    (SELECT group_concat(f.value, ' ') FROM secret_fields f
+        WHERE f.secret_id = s.id AND f.field_type = 'url') AS urls,
+    (SELECT group_concat(f.name || ' ' || coalesce(f.value, ''), ' ') FROM secret_fields f
+        WHERE f.secret_id = s.id AND f.field_type NOT IN ('url', 'hidden')) AS notes,
+    (SELECT p.name FROM projects p WHERE p.id = s.project_id) AS project,
+    (SELECT group_concat(a.filename, ' ') FROM attachments a
+        WHERE a.secret_id = s.id) AS attachments
+FROM secrets s;
+
+-- Segredos
+CREATE TRIGGER IF NOT EXISTS secrets_search_insert AFTER INSERT ON secrets BEGIN
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS secrets_search_update
+AFTER UPDATE OF title, username, project_id ON secrets BEGIN
+    DELETE FROM secrets_fts WHERE rowid = OLD.id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS secrets_search_delete AFTER DELETE ON secrets BEGIN
+    DELETE FROM secrets_fts WHERE rowid = OLD.id;
+END;
+
+-- Campos personalizados e anexos regravam o documento do segredo dono
+CREATE TRIGGER IF NOT EXISTS secret_fields_search_insert AFTER INSERT ON secret_fields BEGIN
+    DELETE FROM secrets_fts WHERE rowid = NEW.secret_id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = NEW.secret_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS secret_fields_search_update AFTER UPDATE ON secret_fields BEGIN
+    DELETE FROM secrets_fts WHERE rowid = NEW.secret_id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = NEW.secret_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS secret_fields_search_delete AFTER DELETE ON secret_fields BEGIN
+    DELETE FROM secrets_fts WHERE rowid = OLD.secret_id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = OLD.secret_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS attachments_search_insert AFTER INSERT ON attachments BEGIN
+    DELETE FROM secrets_fts WHERE rowid = NEW.secret_id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = NEW.secret_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS attachments_search_update
+AFTER UPDATE OF filename, secret_id ON attachments BEGIN
+    DELETE FROM secrets_fts WHERE rowid IN (OLD.secret_id, NEW.secret_id);
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id IN (OLD.secret_id, NEW.secret_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS attachments_search_delete AFTER DELETE ON attachments BEGIN
+    DELETE FROM secrets_fts WHERE rowid = OLD.secret_id;
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents WHERE id = OLD.secret_id;
+END;
+
+-- Projetos. Renomear um projeto também regrava os segredos dele.
+CREATE TRIGGER IF NOT EXISTS projects_search_insert AFTER INSERT ON projects BEGIN
+    INSERT INTO projects_fts (rowid, name, description)
+    VALUES (NEW.id, NEW.name, NEW.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_search_update
+AFTER UPDATE OF name, description ON projects BEGIN
+    DELETE FROM projects_fts WHERE rowid = OLD.id;
+    INSERT INTO projects_fts (rowid, name, description)
+    VALUES (NEW.id, NEW.name, NEW.description);
+
+    DELETE FROM secrets_fts WHERE rowid IN (SELECT id FROM secrets WHERE project_id = NEW.id);
+    INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+    SELECT id, title, username, urls, notes, project, attachments
+    FROM secret_search_documents
+    WHERE id IN (SELECT id FROM secrets WHERE project_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS projects_search_delete AFTER DELETE ON projects BEGIN
+    DELETE FROM projects_fts WHERE rowid = OLD.id;
+END;
+
+-- Indexar o que já existe
+INSERT INTO secrets_fts (rowid, title, username, urls, notes, project, attachments)
+SELECT id, title, username, urls, notes, project, attachments FROM secret_search_documents;
+
+INSERT INTO projects_fts (rowid, name, description)
+SELECT id, name, description FROM projects;
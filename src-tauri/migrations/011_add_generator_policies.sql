@@ -0,0 +1,6 @@
+-- Política de geração de senhas de cada projeto: as opções do gerador em
+-- JSON, usadas quando a interface pede uma senha nova para o projeto.
+CREATE TABLE IF NOT EXISTS project_generator_policies (
+    project_id INTEGER PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    options TEXT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Quando a senha mudou pela última vez. NULL enquanto ela for a da criação
+-- (vale `created_at`). Usado pelo relatório de saúde do cofre.
+ALTER TABLE secrets ADD COLUMN password_changed_at TEXT NULL;
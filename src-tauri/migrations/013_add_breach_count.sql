@@ -0,0 +1,4 @@
+-- Resultado da última verificação da senha na base local do HIBP: quantas
+-- vezes ela aparece em vazamentos. NULL enquanto não foi verificada (ou
+-- depois de trocada).
+ALTER TABLE secrets ADD COLUMN breach_count INTEGER NULL;
@@ -0,0 +1,3 @@
+-- Semente TOTP (segundo fator) do segredo, como URI otpauth:// normalizada.
+-- Cifrada pela aplicação, com a mesma proteção de password_blob.
+ALTER TABLE secrets ADD COLUMN totp_blob BLOB NULL;
@@ -0,0 +1,852 @@
+//! Formato de backup portátil.
+//!
+//! Layout do arquivo:
+//!
+//! ```text
+//! "SMBK" | versão (u8) | tamanho do cabeçalho (u32 LE) | cabeçalho JSON | nonce (12) | ciphertext
+//! ```
+//!
+//! O cabeçalho guarda o salt e os parâmetros de KDF do próprio backup, então
+//! basta a senha do backup para abri-lo em qualquer máquina. Tudo antes do
+//! nonce é autenticado como AAD do AES-GCM.
+//!
+//! O conteúdo cifrado é um [`BackupPayload`] em JSON com o cofre completo:
+//! projetos, segredos (inclusive os da lixeira, com seus campos
+//! personalizados e sementes TOTP), anexos e as variáveis de ambiente dos
+//! projetos.
+
+use crate::custom_fields::{insert_fields, load_fields, CustomField};
+use crate::database::{
+    decrypt_column, decrypt_column_string, encrypt_column, ATTACHMENT_CONTENT_COLUMN,
+    SECRET_PASSWORD_COLUMN, SECRET_TOTP_COLUMN,
+};
+use crate::error::{AppError, Result};
+use crate::models::{Project, ProjectEnvVar, Secret};
+use crate::secret_kinds::{decrypt_payload, encrypt_payload, kind_from_row, SecretPayload};
+use crate::security::{
+    decrypt_bytes, encrypt_bytes, generate_salt, KdfParams, MasterKey, FIELD_ENCRYPTION_VERSION,
+};
+use crate::totp::store_totp;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const BACKUP_MAGIC: &[u8; 4] = b"SMBK";
+pub const BACKUP_FORMAT_VERSION: u8 = 1;
+pub const PAYLOAD_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupHeader {
+    kdf: KdfParams,
+    salt: String,
+}
+
+/// Quantas vezes os parâmetros padrão o cabeçalho de um backup pode pedir.
+/// O cabeçalho vem de um arquivo qualquer e é lido antes de a senha ser
+/// conferida; sem teto, um backup forjado faria a importação alocar
+/// gigabytes ou rodar por horas.
+const MAX_KDF_FACTOR: u32 = 8;
+
+impl BackupHeader {
+    fn derive_key(&self, password: &str) -> Result<MasterKey> {
+        let ceiling = KdfParams::default();
+        if self.kdf.memory_cost > ceiling.memory_cost * MAX_KDF_FACTOR
+            || self.kdf.time_cost > ceiling.time_cost * MAX_KDF_FACTOR
+            || self.kdf.parallelism > ceiling.parallelism * MAX_KDF_FACTOR
+        {
+            return Err(AppError::InvalidBackup(format!(
+                "kdf memoryCost={} timeCost={} parallelism={}",
+                self.kdf.memory_cost, self.kdf.time_cost, self.kdf.parallelism
+            )));
+        }
+
+        crate::security::derive_key(password, &self.salt, &self.kdf)
+    }
+}
+
+/// Indica se `data` está no formato com cabeçalho. Backups antigos são apenas
+/// `nonce || ciphertext` cifrados com o salt local do cofre.
+pub fn is_backup_container(data: &[u8]) -> bool {
+    data.starts_with(BACKUP_MAGIC)
+}
+
+/// Cifra `plaintext` num container de backup protegido por `password`.
+pub fn seal(plaintext: &[u8], password: &str) -> Result<Vec<u8>> {
+    let header = BackupHeader {
+        kdf: KdfParams::default(),
+        salt: generate_salt(),
+    };
+    let header_json = serde_json::to_vec(&header).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut container = Vec::with_capacity(9 + header_json.len() + plaintext.len() + 28);
+    container.extend_from_slice(BACKUP_MAGIC);
+    container.push(BACKUP_FORMAT_VERSION);
+    container.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+    container.extend_from_slice(&header_json);
+
+    let key = header.derive_key(password)?;
+    let encrypted = encrypt_bytes(plaintext, &key, &container)?;
+    container.extend(encrypted);
+
+    Ok(container)
+}
+
+/// Abre um container gerado por [`seal`].
+pub fn open(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    if !is_backup_container(data) || data.len() < 9 {
+        return Err(AppError::InvalidBackup("header missing".to_string()));
+    }
+
+    let version = data[4];
+    if version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::UnsupportedVersion(format!(
+            "backup version={}",
+            version
+        )));
+    }
+
+    let header_len = u32::from_le_bytes([data[5], data[6], data[7], data[8]]) as usize;
+    let header_end = 9usize
+        .checked_add(header_len)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| AppError::InvalidBackup(format!("header len={}", header_len)))?;
+
+    let header: BackupHeader = serde_json::from_slice(&data[9..header_end])
+        .map_err(|e| AppError::InvalidBackup(format!("header: {}", e)))?;
+
+    let key = header.derive_key(password)?;
+    decrypt_bytes(&data[header_end..], &key, &data[..header_end])
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupAttachment {
+    pub id: i64,
+    pub secret_id: i64,
+    pub filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    #[serde(with = "hex::serde")]
+    pub content: Vec<u8>,
+    pub created_at: String,
+}
+
+/// Semente TOTP de um segredo, como URI otpauth normalizada.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTotp {
+    pub secret_id: i64,
+    pub uri: String,
+}
+
+/// Cofre completo, como gravado dentro do container.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupPayload {
+    pub version: u32,
+    pub exported_at: String,
+    pub projects: Vec<Project>,
+    pub secrets: Vec<Secret>,
+    pub attachments: Vec<BackupAttachment>,
+    #[serde(default)]
+    pub env_vars: Vec<ProjectEnvVar>,
+    #[serde(default)]
+    pub totp: Vec<BackupTotp>,
+}
+
+impl BackupPayload {
+    /// Lê o JSON de um backup. Backups antigos continham apenas a lista de
+    /// segredos ativos, sem projetos nem anexos.
+    pub fn from_json(json: &str) -> Result<Self> {
+        if json.trim_start().starts_with('[') {
+            let mut secrets: Vec<Secret> =
+                serde_json::from_str(json).map_err(|e| AppError::InvalidBackup(e.to_string()))?;
+
+            // Os projetos não eram exportados, então os vínculos não podem ser refeitos
+            for secret in &mut secrets {
+                secret.project_id = None;
+            }
+
+            return Ok(Self {
+                version: 1,
+                exported_at: String::new(),
+                projects: Vec::new(),
+                secrets,
+                attachments: Vec::new(),
+                env_vars: Vec::new(),
+                totp: Vec::new(),
+            });
+        }
+
+        let payload: Self =
+            serde_json::from_str(json).map_err(|e| AppError::InvalidBackup(e.to_string()))?;
+
+        if payload.version > PAYLOAD_VERSION {
+            return Err(AppError::UnsupportedVersion(format!(
+                "payload version={}",
+                payload.version
+            )));
+        }
+
+        Ok(payload)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub projects_inserted: usize,
+    pub projects_merged: usize,
+    pub secrets_inserted: usize,
+    pub secrets_skipped: usize,
+    pub attachments_inserted: usize,
+}
+
+/// Lê o cofre inteiro, incluindo itens na lixeira, com as colunas protegidas
+/// já decifradas.
+pub fn collect_backup(conn: &Connection, field_key: &MasterKey) -> Result<BackupPayload> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, created_at, deleted_at FROM projects ORDER BY id",
+    )?;
+    let projects = stmt
+        .query_map([], |row| {
+            Ok(Project {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                created_at: row.get(3)?,
+                deleted_at: row.get(4)?,
+                purge_at: None,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, title, username, password_blob, created_at, deleted_at, kind, payload_blob, password_changed_at, breach_count FROM secrets ORDER BY id",
+    )?;
+    let secrets = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let pass_str = decrypt_column_string(row, 4, field_key, SECRET_PASSWORD_COLUMN, id)?;
+            let breach_count: Option<i64> = row.get(10)?;
+
+            Ok(Secret {
+                id,
+                title: row.get(2)?,
+                username: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                password: pass_str,
+                created_at: row.get(5)?,
+                deleted_at: row.get(6)?,
+                project_id: row.get(1)?,
+                kind: kind_from_row(row, 7)?,
+                payload: decrypt_payload(row, 8, field_key, id)?,
+                fields: Vec::new(),
+                password_changed_at: row.get(9)?,
+                breached: breach_count.is_some_and(|count| count > 0),
+                breach_count,
+                purge_at: None,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut fields = load_fields(conn, field_key, None, true)?;
+    let secrets = secrets
+        .into_iter()
+        .map(|secret| Secret {
+            fields: fields.remove(&secret.id).unwrap_or_default(),
+            ..secret
+        })
+        .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, secret_id, filename, mime_type, file_size, content, created_at FROM attachments ORDER BY id",
+    )?;
+    let attachments = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+
+            Ok(BackupAttachment {
+                id,
+                secret_id: row.get(1)?,
+                filename: row.get(2)?,
+                mime_type: row.get(3)?,
+                file_size: row.get(4)?,
+                content: decrypt_column(row, 5, field_key, ATTACHMENT_CONTENT_COLUMN, id)?
+                    .unwrap_or_default(),
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, secret_title, env_name FROM project_env_vars ORDER BY id",
+    )?;
+    let env_vars = stmt
+        .query_map([], |row| {
+            Ok(ProjectEnvVar {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                secret_title: row.get(2)?,
+                env_name: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt =
+        conn.prepare("SELECT id, totp_blob FROM secrets WHERE totp_blob IS NOT NULL ORDER BY id")?;
+    let totp = stmt
+        .query_map([], |row| {
+            let secret_id: i64 = row.get(0)?;
+
+            Ok(BackupTotp {
+                secret_id,
+                uri: decrypt_column_string(row, 1, field_key, SECRET_TOTP_COLUMN, secret_id)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(BackupPayload {
+        version: PAYLOAD_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        projects,
+        secrets,
+        attachments,
+        env_vars,
+        totp,
+    })
+}
+
+/// Segredo que já estava no cofre, para reconhecer os que o backup repete.
+struct ExistingSecret {
+    id: i64,
+    title: String,
+    username: String,
+    password: String,
+    payload: Option<SecretPayload>,
+    project_id: Option<i64>,
+    fields: Vec<CustomField>,
+    totp: Option<Vec<u8>>,
+}
+
+/// Reconstrói o conteúdo do backup dentro do cofre aberto, numa única transação.
+///
+/// Os ids do backup são remapeados para os ids novos. Projetos com o mesmo nome
+/// de um projeto existente são mesclados. Um segredo idêntico a um existente
+/// (título, usuário, senha, payload, projeto, campos e TOTP) não é duplicado:
+/// os anexos do backup que ele ainda não tem são adicionados ao existente.
+pub fn restore_backup(
+    conn: &mut Connection,
+    field_key: &MasterKey,
+    payload: &BackupPayload,
+) -> Result<ImportSummary> {
+    let tx = conn.transaction()?;
+    let mut summary = ImportSummary::default();
+
+    let mut project_ids: HashMap<i64, i64> = HashMap::new();
+    for project in &payload.projects {
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM projects WHERE name = ?1 ORDER BY deleted_at IS NOT NULL, id LIMIT 1",
+                [&project.name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let new_id = match existing {
+            Some(id) => {
+                summary.projects_merged += 1;
+                id
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO projects (name, description, created_at, deleted_at) VALUES (?1, ?2, ?3, ?4)",
+                    (
+                        &project.name,
+                        &project.description,
+                        &project.created_at,
+                        &project.deleted_at,
+                    ),
+                )?;
+                summary.projects_inserted += 1;
+                tx.last_insert_rowid()
+            }
+        };
+
+        project_ids.insert(project.id, new_id);
+    }
+
+    let mut existing_fields = load_fields(&tx, field_key, None, true)?;
+    let mut existing_entries = Vec::new();
+    {
+        let mut stmt = tx.prepare(
+            "SELECT id, title, username, password_blob, payload_blob, project_id, totp_blob FROM secrets",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+
+            Ok(ExistingSecret {
+                id,
+                title: row.get(1)?,
+                username: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                password: decrypt_column_string(row, 3, field_key, SECRET_PASSWORD_COLUMN, id)?,
+                payload: decrypt_payload(row, 4, field_key, id)?,
+                project_id: row.get(5)?,
+                fields: Vec::new(),
+                totp: decrypt_column(row, 6, field_key, SECRET_TOTP_COLUMN, id)?,
+            })
+        })?;
+
+        for r in rows {
+            let mut entry = r?;
+            entry.fields = existing_fields.remove(&entry.id).unwrap_or_default();
+            existing_entries.push(entry);
+        }
+    }
+
+    let backup_totp: HashMap<i64, &str> = payload
+        .totp
+        .iter()
+        .map(|totp| (totp.secret_id, totp.uri.as_str()))
+        .collect();
+
+    let mut secret_ids: HashMap<i64, i64> = HashMap::new();
+    let mut merged_ids: HashSet<i64> = HashSet::new();
+    for secret in &payload.secrets {
+        let project_id = secret
+            .project_id
+            .and_then(|id| project_ids.get(&id).copied());
+
+        let existing = existing_entries.iter().find(|e| {
+            e.title == secret.title
+                && e.username == secret.username
+                && e.password == secret.password
+                && e.payload == secret.payload
+                && e.project_id == project_id
+                && e.fields == secret.fields
+                && e.totp.as_deref() == backup_totp.get(&secret.id).map(|uri| uri.as_bytes())
+        });
+
+        // Anexos e TOTP do segredo repetido passam a apontar para o existente
+        if let Some(existing) = existing {
+            secret_ids.insert(secret.id, existing.id);
+            merged_ids.insert(existing.id);
+            summary.secrets_skipped += 1;
+            continue;
+        }
+
+        let kind = secret
+            .payload
+            .as_ref()
+            .map(SecretPayload::kind)
+            .unwrap_or_default();
+
+        tx.execute(
+            "INSERT INTO secrets (title, username, project_id, created_at, deleted_at, kind, password_changed_at, breach_count, encryption_version) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            (
+                &secret.title,
+                &secret.username,
+                project_id,
+                &secret.created_at,
+                &secret.deleted_at,
+                kind.as_str(),
+                &secret.password_changed_at,
+                secret.breach_count,
+                FIELD_ENCRYPTION_VERSION,
+            ),
+        )?;
+
+        let new_id = tx.last_insert_rowid();
+
+        // Os valores cifrados dependem do id novo da linha
+        let password_blob = encrypt_column(
+            secret.password.as_bytes(),
+            field_key,
+            SECRET_PASSWORD_COLUMN,
+            new_id,
+        )?;
+        let payload_blob = encrypt_payload(secret.payload.as_ref(), field_key, new_id)?;
+        tx.execute(
+            "UPDATE secrets SET password_blob = ?1, payload_blob = ?2 WHERE id = ?3",
+            (password_blob, payload_blob, new_id),
+        )?;
+
+        insert_fields(&tx, field_key, new_id, &secret.fields)?;
+
+        secret_ids.insert(secret.id, new_id);
+        summary.secrets_inserted += 1;
+    }
+
+    for attachment in &payload.attachments {
+        let Some(secret_id) = secret_ids.get(&attachment.secret_id) else {
+            continue;
+        };
+
+        if merged_ids.contains(secret_id) && has_attachment(&tx, field_key, *secret_id, attachment)?
+        {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT INTO attachments (secret_id, filename, mime_type, file_size, content, created_at, encryption_version) VALUES (?1, ?2, ?3, ?4, x'', ?5, ?6)",
+            (
+                secret_id,
+                &attachment.filename,
+                &attachment.mime_type,
+                attachment.file_size,
+                &attachment.created_at,
+                FIELD_ENCRYPTION_VERSION,
+            ),
+        )?;
+
+        let attachment_id = tx.last_insert_rowid();
+        let content = encrypt_column(
+            &attachment.content,
+            field_key,
+            ATTACHMENT_CONTENT_COLUMN,
+            attachment_id,
+        )?;
+        tx.execute(
+            "UPDATE attachments SET content = ?1 WHERE id = ?2",
+            (content, attachment_id),
+        )?;
+        summary.attachments_inserted += 1;
+    }
+
+    for totp in &payload.totp {
+        if let Some(secret_id) = secret_ids
+            .get(&totp.secret_id)
+            .filter(|id| !merged_ids.contains(*id))
+        {
+            store_totp(&tx, field_key, *secret_id, Some(&totp.uri))?;
+        }
+    }
+
+    // Num projeto mesclado, a configuração que já existe prevalece
+    for var in &payload.env_vars {
+        let Some(project_id) = project_ids.get(&var.project_id) else {
+            continue;
+        };
+
+        tx.execute(
+            "INSERT OR IGNORE INTO project_env_vars (project_id, secret_title, env_name) VALUES (?1, ?2, ?3)",
+            (project_id, &var.secret_title, &var.env_name),
+        )?;
+    }
+
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/// Indica se o segredo já tem um anexo com o mesmo nome e conteúdo.
+fn has_attachment(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    attachment: &BackupAttachment,
+) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(
+        "SELECT id, content FROM attachments WHERE secret_id = ?1 AND filename = ?2 AND file_size = ?3",
+    )?;
+    let contents = stmt
+        .query_map(
+            (secret_id, &attachment.filename, attachment.file_size),
+            |row| decrypt_column(row, 1, field_key, ATTACHMENT_CONTENT_COLUMN, row.get(0)?),
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(contents
+        .iter()
+        .any(|content| content.as_deref() == Some(attachment.content.as_slice())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_fields::CustomFieldType;
+    use crate::database::{encrypt_legacy_rows, initialize_database};
+    use crate::security::{derive_field_key, derive_key_from_password, generate_salt};
+    use std::fs;
+
+    fn temp_vault(name: &str) -> (Connection, MasterKey) {
+        let db_path = std::env::temp_dir().join(format!("test_backup_{}.db", name));
+        if db_path.exists() {
+            fs::remove_file(&db_path).unwrap();
+        }
+
+        let key = derive_key_from_password("senha", &generate_salt()).unwrap();
+        let conn = initialize_database(&db_path, &key).unwrap();
+
+        (conn, derive_field_key(&key).unwrap())
+    }
+
+    fn populate(conn: &mut Connection, field_key: &MasterKey) {
+        conn.execute_batch(
+            "INSERT INTO projects (name, description, created_at) VALUES ('API', 'Serviços', '2024-01-01 10:00:00');
+             INSERT INTO projects (name, created_at, deleted_at) VALUES ('Antigo', '2023-05-01 08:00:00', '2024-02-01T00:00:00+00:00');
+             INSERT INTO secrets (title, username, password_blob, project_id, created_at)
+                 VALUES ('GitHub', 'eu', CAST('ghp_123' AS BLOB), 1, '2024-01-02 11:00:00');
+             INSERT INTO secrets (title, username, password_blob, created_at, deleted_at)
+                 VALUES ('Lixo', 'ninguem', CAST('velha' AS BLOB), '2023-01-01 00:00:00', '2024-03-01T00:00:00+00:00');
+             INSERT INTO attachments (secret_id, filename, mime_type, file_size, content, created_at)
+                 VALUES (1, 'id_rsa', 'text/plain', 3, x'010203', '2024-01-03 12:00:00');
+             INSERT INTO project_env_vars (project_id, secret_title, env_name) VALUES (1, 'GitHub', 'GH_TOKEN');",
+        )
+        .unwrap();
+
+        // As linhas acima foram gravadas em texto puro
+        encrypt_legacy_rows(conn, field_key).unwrap();
+    }
+
+    #[test]
+    fn test_full_vault_roundtrip() {
+        let (mut source, source_key) = temp_vault("source");
+        populate(&mut source, &source_key);
+
+        let note = SecretPayload::Note(crate::secret_kinds::NotePayload {
+            content: "recovery codes".to_string(),
+        });
+        source
+            .execute(
+                "INSERT INTO secrets (id, title, username, password_blob, kind, payload_blob, encryption_version) VALUES (3, 'Códigos', '', ?1, 'note', ?2, ?3)",
+                (
+                    encrypt_column(b"", &source_key, SECRET_PASSWORD_COLUMN, 3).unwrap(),
+                    encrypt_payload(Some(&note), &source_key, 3).unwrap(),
+                    FIELD_ENCRYPTION_VERSION,
+                ),
+            )
+            .unwrap();
+        insert_fields(
+            &source,
+            &source_key,
+            1,
+            &[
+                CustomField::new("Recuperação", "abc-def", CustomFieldType::Hidden),
+                CustomField::new("Site", "https://github.com", CustomFieldType::Url),
+            ],
+        )
+        .unwrap();
+        store_totp(
+            &source,
+            &source_key,
+            1,
+            Some("otpauth://totp/GitHub?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA1&digits=6&period=30"),
+        )
+        .unwrap();
+        source
+            .execute(
+                "UPDATE secrets SET password_changed_at = '2024-05-01T00:00:00+00:00', breach_count = 3 WHERE id = 1",
+                [],
+            )
+            .unwrap();
+
+        let exported = collect_backup(&source, &source_key).unwrap();
+        assert_eq!(exported.secrets[0].fields.len(), 2);
+        assert_eq!(
+            exported.secrets[0].password_changed_at.as_deref(),
+            Some("2024-05-01T00:00:00+00:00")
+        );
+        assert_eq!(exported.secrets[0].breach_count, Some(3));
+        assert!(exported.secrets[0].breached);
+        assert_eq!(exported.totp.len(), 1);
+        let json = serde_json::to_string(&exported).unwrap();
+        let container = seal(json.as_bytes(), "senha_backup").unwrap();
+
+        let plaintext = open(&container, "senha_backup").unwrap();
+        let payload = BackupPayload::from_json(&String::from_utf8(plaintext).unwrap()).unwrap();
+
+        let (mut target, target_key) = temp_vault("target");
+        let summary = restore_backup(&mut target, &target_key, &payload).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                projects_inserted: 2,
+                projects_merged: 0,
+                secrets_inserted: 3,
+                secrets_skipped: 0,
+                attachments_inserted: 1,
+            }
+        );
+
+        let restored = collect_backup(&target, &target_key).unwrap();
+        assert_eq!(restored.secrets[2].payload, Some(note));
+        assert_eq!(
+            restored.secrets[0].password_changed_at,
+            exported.secrets[0].password_changed_at
+        );
+        assert_eq!(restored.secrets[0].breach_count, Some(3));
+        assert_eq!(restored.projects, exported.projects);
+        assert_eq!(restored.secrets, exported.secrets);
+        assert_eq!(restored.attachments, exported.attachments);
+        assert_eq!(restored.env_vars, exported.env_vars);
+        assert_eq!(restored.totp, exported.totp);
+    }
+
+    #[test]
+    fn test_restore_remaps_ids_and_skips_duplicates() {
+        let (mut source, source_key) = temp_vault("remap_source");
+        populate(&mut source, &source_key);
+        let payload = collect_backup(&source, &source_key).unwrap();
+
+        // O destino já tem um projeto homônimo e ids deslocados
+        let (mut target, target_key) = temp_vault("remap_target");
+        target
+            .execute_batch(
+                "INSERT INTO projects (name) VALUES ('Outro');
+                 INSERT INTO projects (name) VALUES ('API');
+                 INSERT INTO secrets (title, username, password_blob) VALUES ('Lixo', 'ninguem', CAST('velha' AS BLOB));",
+            )
+            .unwrap();
+        encrypt_legacy_rows(&mut target, &target_key).unwrap();
+
+        let summary = restore_backup(&mut target, &target_key, &payload).unwrap();
+        assert_eq!(summary.projects_merged, 1);
+        assert_eq!(summary.secrets_inserted, 1);
+        assert_eq!(summary.secrets_skipped, 1);
+
+        let (project_id, secret_id): (i64, i64) = target
+            .query_row(
+                "SELECT project_id, id FROM secrets WHERE title = 'GitHub'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            project_id, 2,
+            "Deveria apontar para o projeto 'API' existente"
+        );
+
+        let attachment_secret: i64 = target
+            .query_row("SELECT secret_id FROM attachments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(attachment_secret, secret_id);
+    }
+
+    #[test]
+    fn test_restore_into_vault_with_same_secrets() {
+        let (mut source, source_key) = temp_vault("same_source");
+        populate(&mut source, &source_key);
+        insert_fields(
+            &source,
+            &source_key,
+            1,
+            &[CustomField::new("PIN", "1234", CustomFieldType::Hidden)],
+        )
+        .unwrap();
+        store_totp(
+            &source,
+            &source_key,
+            1,
+            Some("otpauth://totp/GitHub?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA1&digits=6&period=30"),
+        )
+        .unwrap();
+        let payload = collect_backup(&source, &source_key).unwrap();
+
+        let (mut target, target_key) = temp_vault("same_target");
+        restore_backup(&mut target, &target_key, &payload).unwrap();
+        target.execute("DELETE FROM attachments", []).unwrap();
+
+        // Os segredos já existem; o anexo que faltava vai para o segredo existente
+        let summary = restore_backup(&mut target, &target_key, &payload).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                projects_inserted: 0,
+                projects_merged: 2,
+                secrets_inserted: 0,
+                secrets_skipped: 2,
+                attachments_inserted: 1,
+            }
+        );
+
+        let restored = collect_backup(&target, &target_key).unwrap();
+        assert_eq!(restored.secrets, payload.secrets);
+        assert_eq!(restored.attachments[0].secret_id, restored.secrets[0].id);
+        assert_eq!(restored.totp, payload.totp);
+
+        // Importar de novo não duplica nada
+        let summary = restore_backup(&mut target, &target_key, &payload).unwrap();
+        assert_eq!(summary.secrets_skipped, 2);
+        assert_eq!(summary.attachments_inserted, 0);
+        let attachments = collect_backup(&target, &target_key).unwrap().attachments;
+        assert_eq!(attachments.len(), 1);
+
+        // Campos diferentes fazem do segredo do backup um segredo novo
+        target
+            .execute(
+                "DELETE FROM secret_fields WHERE secret_id = ?1",
+                [restored.secrets[0].id],
+            )
+            .unwrap();
+        let summary = restore_backup(&mut target, &target_key, &payload).unwrap();
+        assert_eq!(summary.secrets_inserted, 1);
+        assert_eq!(summary.secrets_skipped, 1);
+    }
+
+    #[test]
+    fn test_legacy_payload_is_accepted() {
+        let json = r#"[{"id":7,"title":"Antigo","username":"eu","projectId":3,"password":"123","createdAt":"2024-01-01 00:00:00","deletedAt":null}]"#;
+
+        let payload = BackupPayload::from_json(json).unwrap();
+        assert_eq!(payload.secrets.len(), 1);
+        assert_eq!(payload.secrets[0].project_id, None);
+        assert!(payload.projects.is_empty());
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let container = seal(b"{\"secrets\":[]}", "senha_backup").unwrap();
+
+        assert!(is_backup_container(&container));
+        assert_eq!(
+            open(&container, "senha_backup").unwrap(),
+            b"{\"secrets\":[]}"
+        );
+    }
+
+    #[test]
+    fn test_backup_wrong_password_fails() {
+        let container = seal(b"dados", "senha_backup").unwrap();
+
+        assert!(open(&container, "outra_senha").is_err());
+    }
+
+    #[test]
+    fn test_backup_tampered_header_fails() {
+        let mut container = seal(b"dados", "senha_backup").unwrap();
+
+        // A versão não entra na derivação da chave, só na AAD
+        container[4] = 0;
+
+        assert!(open(&container, "senha_backup").is_err());
+    }
+
+    #[test]
+    fn test_backup_excessive_kdf_params_rejected() {
+        let header = BackupHeader {
+            kdf: KdfParams {
+                memory_cost: u32::MAX,
+                ..KdfParams::default()
+            },
+            salt: generate_salt(),
+        };
+        let header_json = serde_json::to_vec(&header).unwrap();
+        let mut container = BACKUP_MAGIC.to_vec();
+        container.push(BACKUP_FORMAT_VERSION);
+        container.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+        container.extend_from_slice(&header_json);
+        container.extend_from_slice(&[0u8; 28]);
+
+        let err = open(&container, "senha_backup").unwrap_err();
+        assert_eq!(err.code(), "INVALID_BACKUP");
+    }
+
+    #[test]
+    fn test_backup_truncated_fails() {
+        let container = seal(b"dados", "senha_backup").unwrap();
+
+        assert!(open(&container[..12], "senha_backup").is_err());
+        assert!(open(&container[..container.len() - 1], "senha_backup").is_err());
+    }
+}
@@ -0,0 +1,1277 @@
+//! Cliente de linha de comando. Trabalha sobre os mesmos cofres do app
+//! (registro, `vault.db` e `vault.meta`) através de `secrets_manager_lib::vault`.
+//!
+//! Não há sessão entre execuções: cada comando pede a senha mestre, que pode
+//! vir de `SECRETS_MANAGER_PASSWORD`, da entrada padrão (`--password-stdin`)
+//! ou do terminal.
+//!
+//! `run --project <nome> -- <comando>` executa um programa com os segredos do
+//! projeto em variáveis de ambiente, sem gravar nada em arquivo; os nomes das
+//! variáveis são configurados com `env`.
+
+use clap::{Args, Parser, Subcommand};
+use secrets_manager_lib::breach::BreachCheckSummary;
+use secrets_manager_lib::custom_fields::{CustomField, CustomFieldType};
+use secrets_manager_lib::error::{AppError, Result};
+use secrets_manager_lib::exporters::{ExportFormat, ExportReport};
+use secrets_manager_lib::generator::{
+    self, GeneratedSecret, GeneratorOptions, PassphraseOptions, PasswordOptions, WordlistLanguage,
+};
+use secrets_manager_lib::health::{HealthIssue, HealthReport};
+use secrets_manager_lib::i18n::{t, tf, Msg};
+use secrets_manager_lib::importers::{self, CsvMapping, ExternalImportSummary, ImportFormat};
+use secrets_manager_lib::models::{EnvBinding, SecretField, SecretSummary};
+use secrets_manager_lib::query::{Page, QueryOptions, SortDirection, SortField};
+use secrets_manager_lib::registry::VaultEntry;
+use secrets_manager_lib::search::{SearchFilters, SearchHit, TrashFilter};
+use secrets_manager_lib::secret_history::{SecretVersion, VersionChange};
+use secrets_manager_lib::secret_kinds::{SecretKind, SecretPayload};
+use secrets_manager_lib::settings::apply_saved_locale;
+use secrets_manager_lib::totp::TotpCode;
+use secrets_manager_lib::utils::write_private;
+use secrets_manager_lib::vault::{load_registry, Vault};
+use serde::Serialize;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::process::{self, ExitCode};
+use zeroize::Zeroizing;
+
+#[derive(Parser)]
+#[command(
+    name = "secrets-cli",
+    version,
+    about = "Acesso ao cofre pela linha de comando"
+)]
+struct Cli {
+    /// Cofre a usar (id ou nome). Sem ele, usa o último cofre aberto
+    #[arg(long, global = true, env = "SECRETS_MANAGER_VAULT")]
+    vault: Option<String>,
+
+    /// Imprime a saída em JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Lê as senhas da entrada padrão, uma por linha, em vez do terminal
+    #[arg(long, global = true)]
+    password_stdin: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Confere a senha mestre e prepara o cofre (migrations, recuperação)
+    Unlock,
+    /// Lista os segredos, sem senhas
+    List {
+        /// Lista a lixeira
+        #[arg(long)]
+        deleted: bool,
+        /// Só segredos deste projeto (id ou nome)
+        #[arg(long)]
+        project: Option<String>,
+        /// id, title, username, createdAt ou deletedAt
+        #[arg(long)]
+        sort: Option<SortField>,
+        /// Ordem decrescente
+        #[arg(long)]
+        desc: bool,
+        /// Itens por página
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Continua a partir do cursor impresso na página anterior
+        #[arg(long)]
+        cursor: Option<String>,
+        /// Criados a partir desta data (AAAA-MM-DD ou RFC 3339)
+        #[arg(long)]
+        created_from: Option<String>,
+        /// Criados antes desta data
+        #[arg(long)]
+        created_to: Option<String>,
+    },
+    /// Mostra um segredo
+    Get {
+        id: i64,
+        /// Inclui a senha (a leitura fica registrada no audit_log)
+        #[arg(long)]
+        reveal: bool,
+    },
+    /// Cria um segredo. A senha do segredo é pedida em seguida, exceto com
+    /// --payload-file
+    Add {
+        #[arg(long)]
+        title: String,
+        #[arg(long, default_value = "")]
+        username: String,
+        /// JSON com os dados de um tipo que não é login
+        /// (ex: {"kind":"api_key","key":"..."})
+        #[arg(long)]
+        payload_file: Option<PathBuf>,
+        /// Campo personalizado no formato [TIPO:]NOME=VALOR
+        /// (ex: --field hidden:PIN=1234); pode ser repetido
+        #[arg(long = "field")]
+        fields: Vec<String>,
+    },
+    /// Altera um segredo
+    Edit {
+        id: i64,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        username: Option<String>,
+        /// Pede uma nova senha para o segredo
+        #[arg(long)]
+        change_password: bool,
+        /// Move o segredo para o projeto (id ou nome)
+        #[arg(long, conflicts_with = "no_project")]
+        project: Option<String>,
+        /// Tira o segredo do projeto atual
+        #[arg(long)]
+        no_project: bool,
+        /// Cria ou substitui um campo personalizado ([TIPO:]NOME=VALOR)
+        #[arg(long = "field")]
+        fields: Vec<String>,
+        /// Remove um campo personalizado pelo nome
+        #[arg(long = "remove-field")]
+        remove_fields: Vec<String>,
+    },
+    /// Move um segredo para a lixeira
+    Rm {
+        id: i64,
+        /// Exclui definitivamente em vez de mover para a lixeira
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Restaura um segredo da lixeira
+    Restore { id: i64 },
+    /// Busca segredos e projetos por título, usuário, URLs, notas e anexos
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Só segredos deste projeto (id ou nome)
+        #[arg(long)]
+        project: Option<String>,
+        /// Só segredos deste tipo (login, note, api_key, ...)
+        #[arg(long)]
+        kind: Option<String>,
+        /// active, deleted ou all
+        #[arg(long, default_value = "active")]
+        trash: TrashFilter,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Lista as versões anteriores de um segredo, compara ou restaura uma delas
+    History {
+        id: i64,
+        /// Compara a versão com o estado atual (ou com --to)
+        #[arg(long, value_name = "VERSAO")]
+        diff: Option<i64>,
+        /// Outra versão para comparar no lugar do estado atual
+        #[arg(long, value_name = "VERSAO", requires = "diff")]
+        to: Option<i64>,
+        /// Torna a versão a atual
+        #[arg(long, value_name = "VERSAO", conflicts_with = "diff")]
+        restore: Option<i64>,
+    },
+    /// Relatório de senhas fracas, repetidas e antigas e de logins sem usuário
+    Health {
+        /// Altera a idade máxima das senhas, em dias (0 desliga)
+        #[arg(long, value_name = "DIAS")]
+        max_age: Option<u32>,
+    },
+    /// Confere as senhas numa cópia local do Have I Been Pwned (SHA-1), sem rede
+    BreachCheck {
+        /// Arquivo ordenado por hash ou diretório de arquivos de faixa
+        path: PathBuf,
+    },
+    /// Mostra o código TOTP atual de um segredo, ou associa ou remove a semente
+    Totp {
+        id: i64,
+        /// Associa uma semente (URI otpauth:// ou chave base32), pedida em seguida
+        #[arg(long, conflicts_with = "remove")]
+        set: bool,
+        /// Remove a semente
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Gera uma senha ou frase-senha. Não precisa do cofre, exceto com --project
+    Generate(GenerateArgs),
+    /// Mostra ou altera quantas versões guardar por segredo (0 desliga)
+    HistoryLimit { limit: Option<usize> },
+    /// Mostra ou altera quantos dias um item fica na lixeira (0 guarda para sempre)
+    TrashRetention { days: Option<u32> },
+    /// Exporta o cofre para um arquivo de backup cifrado
+    Export { file: PathBuf },
+    /// Importa um arquivo de backup para o cofre
+    Import { file: PathBuf },
+    /// Exporta em texto puro para outro gerenciador. Pede a senha mestre de novo
+    ExportPlain {
+        /// bitwarden-json, keepass-xml ou csv
+        #[arg(long)]
+        format: ExportFormat,
+        file: PathBuf,
+        /// Confirma que o arquivo terá as senhas sem criptografia
+        #[arg(long)]
+        confirm: bool,
+    },
+    /// Importa a exportação de outro gerenciador de senhas
+    ImportFrom {
+        /// bitwarden-json, keepass-xml, 1password-1pux, 1password-csv ou csv
+        #[arg(long)]
+        format: ImportFormat,
+        file: PathBuf,
+        /// CSV genérico: coluna do título
+        #[arg(long, required_if_eq("format", "csv"))]
+        title_column: Option<String>,
+        /// CSV genérico: coluna do usuário
+        #[arg(long)]
+        username_column: Option<String>,
+        /// CSV genérico: coluna da senha
+        #[arg(long, required_if_eq("format", "csv"))]
+        password_column: Option<String>,
+        /// CSV genérico: coluna do projeto
+        #[arg(long)]
+        project_column: Option<String>,
+        /// CSV genérico: separador
+        #[arg(long)]
+        delimiter: Option<char>,
+    },
+    /// Mostra ou altera os nomes das variáveis de ambiente de um projeto
+    Env {
+        /// Projeto (id ou nome)
+        #[arg(long)]
+        project: String,
+        /// Define a variável de um segredo, pelo título
+        #[arg(long, value_name = "TITULO=VARIAVEL")]
+        set: Vec<String>,
+        /// Volta a usar o nome derivado do título
+        #[arg(long, value_name = "TITULO")]
+        unset: Vec<String>,
+    },
+    /// Importa um arquivo .env para o projeto
+    EnvImport {
+        #[arg(long)]
+        project: String,
+        file: PathBuf,
+    },
+    /// Exporta os segredos do projeto como .env (sem arquivo, na saída padrão)
+    EnvExport {
+        #[arg(long)]
+        project: String,
+        file: Option<PathBuf>,
+    },
+    /// Executa um comando com os segredos do projeto como variáveis de ambiente
+    Run {
+        /// Projeto (id ou nome)
+        #[arg(long)]
+        project: String,
+        #[arg(last = true, required = true, value_name = "COMANDO")]
+        command: Vec<String>,
+    },
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Tamanho da senha
+    #[arg(long)]
+    length: Option<usize>,
+    #[arg(long)]
+    no_lowercase: bool,
+    #[arg(long)]
+    no_uppercase: bool,
+    #[arg(long)]
+    no_digits: bool,
+    #[arg(long)]
+    no_symbols: bool,
+    /// Evita caracteres parecidos (I l 1 | O 0)
+    #[arg(long)]
+    exclude_ambiguous: bool,
+    /// Alterna consoantes e vogais
+    #[arg(long)]
+    pronounceable: bool,
+    /// Gera uma frase-senha em vez de uma senha
+    #[arg(long)]
+    passphrase: bool,
+    /// Palavras da frase-senha
+    #[arg(long, requires = "passphrase")]
+    words: Option<usize>,
+    #[arg(long, requires = "passphrase")]
+    separator: Option<String>,
+    /// Primeira letra de cada palavra em maiúscula
+    #[arg(long, requires = "passphrase")]
+    capitalize: bool,
+    /// Acrescenta um dígito a uma das palavras
+    #[arg(long, requires = "passphrase")]
+    number: bool,
+    /// Lista de palavras: en ou pt. Sem ela, segue o idioma
+    #[arg(long, requires = "passphrase")]
+    language: Option<WordlistLanguage>,
+    /// Sem outras opções, usa a política de geração do projeto (id ou nome)
+    #[arg(long)]
+    project: Option<String>,
+    /// Grava as opções como política de geração do projeto
+    #[arg(long, requires = "project")]
+    save_policy: bool,
+}
+
+impl GenerateArgs {
+    /// Opções pedidas na linha de comando; `None` se nenhuma foi dada.
+    fn options(&self) -> Option<GeneratorOptions> {
+        if self.passphrase {
+            let defaults = PassphraseOptions::default();
+            return Some(GeneratorOptions::Passphrase(PassphraseOptions {
+                words: self.words.unwrap_or(defaults.words),
+                separator: self.separator.clone().unwrap_or(defaults.separator),
+                capitalize: self.capitalize,
+                include_number: self.number,
+                language: self.language,
+            }));
+        }
+
+        let customized = self.length.is_some()
+            || self.no_lowercase
+            || self.no_uppercase
+            || self.no_digits
+            || self.no_symbols
+            || self.exclude_ambiguous
+            || self.pronounceable;
+        if !customized {
+            return None;
+        }
+
+        let defaults = PasswordOptions::default();
+        Some(GeneratorOptions::Password(PasswordOptions {
+            length: self.length.unwrap_or(defaults.length),
+            lowercase: !self.no_lowercase,
+            uppercase: !self.no_uppercase,
+            digits: !self.no_digits,
+            symbols: !self.no_symbols,
+            exclude_ambiguous: self.exclude_ambiguous,
+            pronounceable: self.pronounceable,
+            ..defaults
+        }))
+    }
+}
+
+/// Lê senhas do terminal (sem eco) ou, com `--password-stdin`, uma por linha
+/// da entrada padrão.
+struct Passwords {
+    from_stdin: bool,
+}
+
+impl Passwords {
+    fn read(&self, prompt: Msg) -> Result<String> {
+        if self.from_stdin {
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line)?;
+            return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+        }
+
+        Ok(rpassword::prompt_password(t(prompt))?)
+    }
+
+    fn master(&self) -> Result<String> {
+        match std::env::var("SECRETS_MANAGER_PASSWORD") {
+            Ok(password) => Ok(password),
+            Err(_) => self.read(Msg::PromptMasterPassword),
+        }
+    }
+
+    fn backup(&self) -> Result<String> {
+        match std::env::var("SECRETS_MANAGER_BACKUP_PASSWORD") {
+            Ok(password) => Ok(password),
+            Err(_) => self.read(Msg::PromptBackupPassword),
+        }
+    }
+}
+
+/// Segredo como é exibido pelo `get`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretView {
+    #[serde(flatten)]
+    summary: SecretSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<SecretPayload>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<CustomField>,
+}
+
+#[derive(Serialize)]
+struct MessageView {
+    message: String,
+}
+
+struct Output {
+    json: bool,
+}
+
+impl Output {
+    fn message(&self, message: String) -> Result<()> {
+        if self.json {
+            self.print_json(&MessageView { message })
+        } else {
+            println!("{}", message);
+            Ok(())
+        }
+    }
+
+    fn print_json<T: Serialize>(&self, value: &T) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(value).map_err(|e| AppError::Internal(e.to_string()))?;
+        println!("{}", json);
+        Ok(())
+    }
+
+    fn secrets(&self, page: &Page<SecretSummary>) -> Result<()> {
+        if self.json {
+            return self.print_json(page);
+        }
+
+        let secrets = &page.items;
+
+        if secrets.is_empty() {
+            println!("{}", t(Msg::NoSecrets));
+            return Ok(());
+        }
+
+        let title_width = column_width(t(Msg::LabelTitle), secrets.iter().map(|s| &s.title));
+        let user_width = column_width(t(Msg::LabelUsername), secrets.iter().map(|s| &s.username));
+
+        println!(
+            "{:>6}  {:<title_width$}  {:<user_width$}  {}",
+            "ID",
+            t(Msg::LabelTitle),
+            t(Msg::LabelUsername),
+            t(Msg::LabelCreatedAt),
+        );
+        for secret in secrets {
+            let mut marker = if secret.breached {
+                format!("  [{}]", t(Msg::LabelBreached))
+            } else {
+                String::new()
+            };
+            if let Some(purge_at) = &secret.purge_at {
+                let date = purge_at.get(..10).unwrap_or(purge_at);
+                marker.push_str(&format!("  [{} {}]", t(Msg::LabelPurgeAt), date));
+            }
+            println!(
+                "{:>6}  {:<title_width$}  {:<user_width$}  {}{}",
+                secret.id, secret.title, secret.username, secret.created_at, marker,
+            );
+        }
+        if let Some(cursor) = &page.next_cursor {
+            println!("{}", tf(Msg::NextPage, &[cursor]));
+        }
+
+        Ok(())
+    }
+
+    fn secret(&self, view: &SecretView) -> Result<()> {
+        if self.json {
+            return self.print_json(view);
+        }
+
+        let summary = &view.summary;
+        println!("ID: {}", summary.id);
+        println!("{}: {}", t(Msg::LabelTitle), summary.title);
+        println!("{}: {}", t(Msg::LabelKind), summary.kind.as_str());
+        println!("{}: {}", t(Msg::LabelUsername), summary.username);
+        if let Some(project_id) = summary.project_id {
+            println!("{}: {}", t(Msg::LabelProject), project_id);
+        }
+        println!("{}: {}", t(Msg::LabelCreatedAt), summary.created_at);
+        if let Some(deleted_at) = &summary.deleted_at {
+            println!("{}: {}", t(Msg::LabelDeletedAt), deleted_at);
+        }
+        if let Some(password) = &view.password {
+            println!("{}: {}", t(Msg::LabelPassword), password);
+        }
+        for field in &view.fields {
+            println!(
+                "{}: {}",
+                field.name,
+                field.value.as_deref().unwrap_or("********")
+            );
+        }
+        if let Some(payload) = &view.payload {
+            let json = serde_json::to_string_pretty(payload)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            println!("{}", json);
+        }
+
+        Ok(())
+    }
+
+    fn generated(&self, generated: &GeneratedSecret) -> Result<()> {
+        if self.json {
+            return self.print_json(generated);
+        }
+
+        println!("{}", generated.value);
+        eprintln!(
+            "{}",
+            tf(Msg::Entropy, &[&format!("{:.1}", generated.entropy_bits)])
+        );
+        Ok(())
+    }
+
+    fn search_hits(&self, hits: &[SearchHit]) -> Result<()> {
+        if self.json {
+            return self.print_json(&hits);
+        }
+
+        if hits.is_empty() {
+            println!("{}", t(Msg::NoResults));
+            return Ok(());
+        }
+
+        let kinds: Vec<String> = hits
+            .iter()
+            .map(|hit| match hit.kind {
+                Some(kind) => kind.as_str().to_string(),
+                None => t(Msg::LabelProject).to_lowercase(),
+            })
+            .collect();
+        let kind_width = column_width(t(Msg::LabelKind), kinds.iter());
+        let title_width = column_width(t(Msg::LabelTitle), hits.iter().map(|h| &h.title));
+
+        println!(
+            "{:>6}  {:<kind_width$}  {:<title_width$}  {}",
+            "ID",
+            t(Msg::LabelKind),
+            t(Msg::LabelTitle),
+            t(Msg::LabelUsername),
+        );
+        for (hit, kind) in hits.iter().zip(&kinds) {
+            println!(
+                "{:>6}  {:<kind_width$}  {:<title_width$}  {}",
+                hit.id,
+                kind,
+                hit.title,
+                hit.username.as_deref().unwrap_or_default(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn breach_check(&self, summary: &BreachCheckSummary) -> Result<()> {
+        if self.json {
+            return self.print_json(summary);
+        }
+
+        println!(
+            "{}",
+            tf(
+                Msg::BreachCheckSummary,
+                &[&summary.checked, &summary.breached]
+            )
+        );
+        if !summary.breached_ids.is_empty() {
+            let ids: Vec<String> = summary.breached_ids.iter().map(i64::to_string).collect();
+            println!("ID: {}", ids.join(", "));
+        }
+
+        Ok(())
+    }
+
+    fn totp_code(&self, code: &TotpCode) -> Result<()> {
+        if self.json {
+            return self.print_json(code);
+        }
+
+        println!(
+            "{}",
+            tf(Msg::TotpCode, &[&code.code, &code.seconds_remaining])
+        );
+
+        Ok(())
+    }
+
+    fn health(&self, report: &HealthReport) -> Result<()> {
+        if self.json {
+            return self.print_json(report);
+        }
+
+        println!(
+            "{}",
+            tf(Msg::HealthSummary, &[&report.score, &report.scanned])
+        );
+        if report.secrets.is_empty() {
+            println!("{}", t(Msg::NoHealthIssues));
+            return Ok(());
+        }
+
+        let title_width = column_width(t(Msg::LabelTitle), report.secrets.iter().map(|s| &s.title));
+
+        println!();
+        println!(
+            "{:>6}  {:>5}  {:<title_width$}  {}",
+            "ID",
+            t(Msg::LabelScore),
+            t(Msg::LabelTitle),
+            t(Msg::LabelIssues),
+        );
+        for secret in &report.secrets {
+            let issues: Vec<String> = secret
+                .issues
+                .iter()
+                .map(|issue| match issue {
+                    HealthIssue::Weak { .. } => t(Msg::IssueWeak).to_string(),
+                    HealthIssue::Reused { shared_with } => {
+                        let ids: Vec<String> = shared_with.iter().map(i64::to_string).collect();
+                        tf(Msg::IssueReused, &[&ids.join(", ")])
+                    }
+                    HealthIssue::Old { age_days } => tf(Msg::IssueOld, &[age_days]),
+                    HealthIssue::EmptyUsername => t(Msg::IssueEmptyUsername).to_string(),
+                })
+                .collect();
+
+            println!(
+                "{:>6}  {:>5}  {:<title_width$}  {}",
+                secret.secret_id,
+                secret.score,
+                secret.title,
+                issues.join("; "),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn versions(&self, versions: &[SecretVersion]) -> Result<()> {
+        if self.json {
+            return self.print_json(&versions);
+        }
+
+        if versions.is_empty() {
+            println!("{}", t(Msg::NoVersions));
+            return Ok(());
+        }
+
+        let title_width = column_width(t(Msg::LabelTitle), versions.iter().map(|v| &v.title));
+
+        println!(
+            "{:>6}  {:<title_width$}  {}",
+            t(Msg::LabelVersion),
+            t(Msg::LabelTitle),
+            t(Msg::LabelCreatedAt),
+        );
+        for version in versions {
+            println!(
+                "{:>6}  {:<title_width$}  {}",
+                version.id, version.title, version.created_at,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn changes(&self, changes: &[VersionChange]) -> Result<()> {
+        if self.json {
+            return self.print_json(&changes);
+        }
+
+        if changes.is_empty() {
+            println!("{}", t(Msg::NoChanges));
+            return Ok(());
+        }
+
+        for change in changes {
+            println!(
+                "{}: {} -> {}",
+                change.field,
+                change.before.as_deref().unwrap_or("-"),
+                change.after.as_deref().unwrap_or("-"),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn export_report(&self, report: &ExportReport) -> Result<()> {
+        if self.json {
+            return self.print_json(report);
+        }
+
+        println!(
+            "{}",
+            tf(Msg::PlaintextExported, &[&report.secrets_exported])
+        );
+        for lost in &report.lost {
+            println!("  {}: {} ({})", t(Msg::NotExported), lost.kind, lost.count);
+        }
+
+        Ok(())
+    }
+
+    fn import_summary(&self, summary: &ExternalImportSummary) -> Result<()> {
+        if self.json {
+            return self.print_json(summary);
+        }
+
+        println!(
+            "{}",
+            tf(
+                Msg::ExternalImportSummary,
+                &[
+                    &summary.secrets_inserted,
+                    &summary.secrets_skipped,
+                    &summary.projects_inserted,
+                ],
+            )
+        );
+        for item in &summary.unmapped {
+            let fields = item.fields.join(", ");
+            if item.skipped {
+                println!("  {}: {} ({})", item.title, t(Msg::NotImported), fields);
+            } else {
+                println!("  {}: {}", item.title, fields);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn env_bindings(&self, bindings: &[EnvBinding]) -> Result<()> {
+        if self.json {
+            return self.print_json(&bindings);
+        }
+
+        if bindings.is_empty() {
+            println!("{}", t(Msg::NoEnvBindings));
+            return Ok(());
+        }
+
+        let name_width = column_width(t(Msg::LabelVariable), bindings.iter().map(|b| &b.env_name));
+
+        println!(
+            "{:<name_width$}  {}",
+            t(Msg::LabelVariable),
+            t(Msg::LabelTitle)
+        );
+        for binding in bindings {
+            // Nomes derivados do título são marcados com *
+            let marker = if binding.configured { "" } else { " *" };
+            println!(
+                "{:<name_width$}  {}{}",
+                binding.env_name, binding.secret_title, marker
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn column_width<'a>(header: &str, values: impl Iterator<Item = &'a String>) -> usize {
+    values
+        .map(|value| value.chars().count())
+        .chain(std::iter::once(header.chars().count()))
+        .max()
+        .unwrap_or(0)
+}
+
+fn resolve_entry(vault: Option<&str>) -> Result<VaultEntry> {
+    let (registry, _) = load_registry()?;
+
+    let entry = match vault {
+        Some(id_or_name) => registry.find(id_or_name)?,
+        None => registry.resolve(None)?,
+    };
+
+    Ok(entry.clone())
+}
+
+/// Executa o comando com as variáveis do projeto e devolve o código de saída
+/// dele. As variáveis só existem no ambiente do processo filho.
+fn run_child(command: &[String], env: Vec<(String, Zeroizing<String>)>) -> Result<ExitCode> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| AppError::InvalidInput("command missing".to_string()))?;
+
+    let status = process::Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(name, value)| (name, value.as_str())))
+        .status()?;
+    drop(env);
+
+    if let Some(code) = status.code() {
+        return Ok(ExitCode::from(code as u8));
+    }
+
+    // Encerrado por sinal: segue a convenção dos shells (128 + sinal)
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return Ok(ExitCode::from(128u8.wrapping_add(signal as u8)));
+        }
+    }
+
+    Ok(ExitCode::FAILURE)
+}
+
+/// `[TIPO:]NOME=VALOR`; sem tipo conhecido antes do `:`, o campo é texto.
+fn parse_field(spec: &str) -> Result<CustomField> {
+    let (name, value) = spec
+        .split_once('=')
+        .filter(|(name, _)| !name.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("field value={:?} expected=[TYPE:]NAME=VALUE", spec))
+        })?;
+
+    let (field_type, name) = match name.split_once(':') {
+        Some((prefix, rest)) => match CustomFieldType::parse(prefix) {
+            Ok(field_type) => (field_type, rest),
+            Err(_) => (CustomFieldType::Text, name),
+        },
+        None => (CustomFieldType::Text, name),
+    };
+
+    Ok(CustomField::new(name.trim(), value, field_type))
+}
+
+fn parse_env_assignment(assignment: &str) -> Result<(&str, &str)> {
+    assignment
+        .rsplit_once('=')
+        .filter(|(title, _)| !title.is_empty())
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("env value={:?} expected=TITLE=VAR", assignment))
+        })
+}
+
+fn run(cli: Cli) -> Result<ExitCode> {
+    let output = Output { json: cli.json };
+    let passwords = Passwords {
+        from_stdin: cli.password_stdin,
+    };
+
+    // Sem projeto, gerar não depende do cofre nem da senha mestre
+    if let Command::Generate(args) = &cli.command {
+        if args.project.is_none() {
+            let generated = generator::generate(&args.options().unwrap_or_default())?;
+            return output.generated(&generated).map(|()| ExitCode::SUCCESS);
+        }
+    }
+
+    let entry = resolve_entry(cli.vault.as_deref())?;
+    let mut vault = Vault::open(&entry, &passwords.master()?)?;
+    match vault.take_unlock_purge() {
+        Some(Ok(summary)) if !summary.is_empty() => eprintln!(
+            "{}",
+            tf(Msg::TrashPurged, &[&summary.secrets, &summary.projects])
+        ),
+        Some(Err(e)) => eprintln!("{}", tf(Msg::TrashPurgeFailed, &[&e])),
+        _ => {}
+    }
+
+    let done = match cli.command {
+        Command::Unlock => {
+            let (mut registry, registry_path) = load_registry()?;
+            registry.last_opened = Some(entry.id.clone());
+            registry.save(&registry_path)?;
+
+            output.message(format!(
+                "{} ({}: {})",
+                t(Msg::VaultUnlocked),
+                t(Msg::LabelVault),
+                entry.name
+            ))
+        }
+        Command::List {
+            deleted,
+            project,
+            sort,
+            desc,
+            limit,
+            cursor,
+            created_from,
+            created_to,
+        } => {
+            let options = QueryOptions {
+                limit,
+                cursor,
+                sort,
+                direction: desc.then_some(SortDirection::Desc),
+                project_id: match project {
+                    Some(project) => Some(vault.find_project(&project)?.id),
+                    None => None,
+                },
+                created_from,
+                created_to,
+                ..Default::default()
+            };
+
+            output.secrets(&vault.secret_summaries_page(deleted, &options)?)
+        }
+        Command::Get { id, reveal } => {
+            let summary = vault.secret_summary(id)?;
+            let (password, payload) = match (reveal, summary.kind) {
+                (false, _) => (None, None),
+                (true, SecretKind::Login) => (
+                    Some(vault.reveal_secret_field(id, SecretField::Password)?),
+                    None,
+                ),
+                (true, _) => {
+                    let json = vault.reveal_secret_field(id, SecretField::Payload)?;
+                    let payload = serde_json::from_str(&json)
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    (None, Some(payload))
+                }
+            };
+
+            let mut fields = vault.secret_fields(id)?;
+            if reveal {
+                for field in fields.iter_mut().filter(|f| f.value.is_none()) {
+                    field.value = Some(vault.reveal_custom_field(id, &field.name)?);
+                }
+            }
+
+            output.secret(&SecretView {
+                summary,
+                password,
+                payload,
+                fields,
+            })
+        }
+        Command::Add {
+            title,
+            username,
+            payload_file,
+            fields,
+        } => {
+            let fields = fields
+                .iter()
+                .map(|spec| parse_field(spec))
+                .collect::<Result<Vec<_>>>()?;
+            let (password, payload) = match payload_file {
+                Some(path) => {
+                    let payload: SecretPayload = serde_json::from_slice(&std::fs::read(&path)?)
+                        .map_err(|e| AppError::InvalidInput(format!("payload: {}", e)))?;
+                    (String::new(), Some(payload))
+                }
+                None => (passwords.read(Msg::PromptSecretPassword)?, None),
+            };
+            let secret = vault.create_typed_secret(
+                &title,
+                &username,
+                &password,
+                payload.as_ref(),
+                &fields,
+            )?;
+
+            output.message(tf(Msg::SecretCreated, &[&secret.id]))
+        }
+        Command::Edit {
+            id,
+            title,
+            username,
+            change_password,
+            project,
+            no_project,
+            fields,
+            remove_fields,
+        } => {
+            let current = vault.secret_summary(id)?;
+
+            let new_fields = if fields.is_empty() && remove_fields.is_empty() {
+                None
+            } else {
+                let mut current_fields = vault.secret_fields(id)?;
+                current_fields.retain(|f| {
+                    !remove_fields
+                        .iter()
+                        .any(|name| name.trim().eq_ignore_ascii_case(&f.name))
+                });
+
+                for spec in &fields {
+                    let field = parse_field(spec)?;
+                    match current_fields
+                        .iter_mut()
+                        .find(|f| f.name.eq_ignore_ascii_case(&field.name))
+                    {
+                        Some(existing) => {
+                            *existing = CustomField {
+                                position: existing.position,
+                                ..field
+                            }
+                        }
+                        None => current_fields.push(CustomField {
+                            position: i64::MAX,
+                            ..field
+                        }),
+                    }
+                }
+
+                Some(current_fields)
+            };
+
+            let password = if change_password {
+                Some(passwords.read(Msg::PromptSecretPassword)?)
+            } else {
+                None
+            };
+
+            vault.update_secret(
+                id,
+                title.as_deref().unwrap_or(&current.title),
+                username.as_deref().unwrap_or(&current.username),
+                password.as_deref(),
+                new_fields.as_deref(),
+            )?;
+
+            if let Some(project) = project {
+                let project = vault.find_project(&project)?;
+                vault.set_secret_project(id, Some(project.id))?;
+            } else if no_project {
+                vault.set_secret_project(id, None)?;
+            }
+
+            output.message(t(Msg::SecretUpdated).to_string())
+        }
+        Command::Rm { id, purge } => {
+            if purge {
+                vault.delete_secret(id)?;
+                output.message(t(Msg::SecretDeleted).to_string())
+            } else {
+                vault.soft_delete_secret(id)?;
+                output.message(t(Msg::SecretMovedToTrash).to_string())
+            }
+        }
+        Command::Restore { id } => {
+            vault.restore_secret(id)?;
+            output.message(t(Msg::SecretRestored).to_string())
+        }
+        Command::Search {
+            query,
+            project,
+            kind,
+            trash,
+            limit,
+        } => {
+            let filters = SearchFilters {
+                project_id: match project {
+                    Some(project) => Some(vault.find_project(&project)?.id),
+                    None => None,
+                },
+                kind: kind.as_deref().map(SecretKind::parse).transpose()?,
+                trash,
+                limit,
+            };
+
+            output.search_hits(&vault.search(&query.join(" "), &filters)?)
+        }
+        Command::History {
+            id,
+            diff,
+            to,
+            restore,
+        } => {
+            if let Some(version_id) = restore {
+                vault.restore_secret_version(id, version_id)?;
+                output.message(t(Msg::SecretVersionRestored).to_string())
+            } else if let Some(from) = diff {
+                output.changes(&vault.compare_secret_versions(id, Some(from), to)?)
+            } else {
+                output.versions(&vault.secret_versions(id)?)
+            }
+        }
+        Command::Health { max_age } => {
+            if let Some(days) = max_age {
+                vault.set_password_max_age(days)?;
+            }
+
+            output.health(&vault.health_report()?)
+        }
+        Command::BreachCheck { path } => output.breach_check(&vault.check_breaches(&path)?),
+        Command::Totp { id, set, remove } => {
+            if remove {
+                vault.set_secret_totp(id, None)?;
+                output.message(t(Msg::TotpRemoved).to_string())
+            } else {
+                if set {
+                    let key = Zeroizing::new(passwords.read(Msg::PromptTotpKey)?);
+                    vault.set_secret_totp(id, Some(&key))?;
+                }
+
+                output.totp_code(&vault.secret_totp_code(id)?)
+            }
+        }
+        Command::Generate(args) => {
+            let options = args.options();
+            let generated = match &args.project {
+                Some(project) => {
+                    let project = vault.find_project(project)?;
+                    if args.save_policy {
+                        let policy = options.clone().unwrap_or_default();
+                        vault.set_generator_policy(project.id, Some(&policy))?;
+                        eprintln!("{}", t(Msg::GeneratorPolicySaved));
+                    }
+                    vault.generate_for_project(project.id, options.as_ref())?
+                }
+                None => generator::generate(&options.unwrap_or_default())?,
+            };
+
+            output.generated(&generated)
+        }
+        Command::HistoryLimit { limit } => {
+            if let Some(limit) = limit {
+                vault.set_history_limit(limit)?;
+            }
+
+            output.message(tf(Msg::HistoryLimit, &[&vault.history_limit()?]))
+        }
+        Command::TrashRetention { days } => {
+            if let Some(days) = days {
+                let summary = vault.set_trash_retention(days)?;
+                if !summary.is_empty() {
+                    eprintln!(
+                        "{}",
+                        tf(Msg::TrashPurged, &[&summary.secrets, &summary.projects])
+                    );
+                }
+            }
+
+            output.message(tf(Msg::TrashRetention, &[&vault.trash_retention()?]))
+        }
+        Command::Export { file } => {
+            let data = vault.export_backup(&passwords.backup()?)?;
+            std::fs::write(file, data)?;
+
+            output.message(t(Msg::BackupExported).to_string())
+        }
+        Command::Import { file } => {
+            let data = std::fs::read(file)?;
+            let summary = vault.import_backup(&data, &passwords.backup()?)?;
+
+            output.message(tf(
+                Msg::ImportSummary,
+                &[
+                    &summary.secrets_inserted,
+                    &summary.secrets_skipped,
+                    &summary.projects_inserted,
+                    &summary.attachments_inserted,
+                ],
+            ))
+        }
+        Command::ExportPlain {
+            format,
+            file,
+            confirm,
+        } => {
+            if !confirm {
+                eprintln!("{}", t(Msg::PlaintextExportNeedsConfirm));
+                return Err(AppError::InvalidInput("confirm=false".to_string()));
+            }
+
+            let (content, report) = vault.export_plaintext(format, &passwords.master()?)?;
+            write_private(&file, &content)?;
+
+            output.export_report(&report)
+        }
+        Command::ImportFrom {
+            format,
+            file,
+            title_column,
+            username_column,
+            password_column,
+            project_column,
+            delimiter,
+        } => {
+            let mapping = match (title_column, password_column) {
+                (Some(title), Some(password)) => Some(CsvMapping {
+                    title,
+                    username: username_column,
+                    password,
+                    project: project_column,
+                    delimiter,
+                    ignore: Vec::new(),
+                }),
+                _ => None,
+            };
+
+            let data = std::fs::read(file)?;
+            let parsed = importers::parse(format, &data, mapping.as_ref())?;
+
+            output.import_summary(&vault.import_external(parsed)?)
+        }
+        Command::Env {
+            project,
+            set,
+            unset,
+        } => {
+            let project = vault.find_project(&project)?;
+
+            for title in &unset {
+                vault.remove_env_var(project.id, title)?;
+            }
+            for assignment in &set {
+                let (title, env_name) = parse_env_assignment(assignment)?;
+                vault.set_env_var(project.id, title, env_name)?;
+            }
+
+            output.env_bindings(&vault.env_bindings(project.id)?)
+        }
+        Command::EnvImport { project, file } => {
+            let project = vault.find_project(&project)?;
+            let content = std::fs::read_to_string(file)?;
+            let summary = vault.import_dotenv(project.id, &content)?;
+
+            output.message(tf(
+                Msg::DotenvImportSummary,
+                &[&summary.inserted, &summary.updated, &summary.unchanged],
+            ))
+        }
+        Command::EnvExport { project, file } => {
+            let project = vault.find_project(&project)?;
+            let content = vault.export_dotenv(project.id)?;
+
+            match file {
+                Some(file) => {
+                    write_private(&file, content.as_bytes())?;
+                    output.message(t(Msg::DotenvExported).to_string())
+                }
+                None => {
+                    print!("{}", content);
+                    Ok(())
+                }
+            }
+        }
+        Command::Run { project, command } => {
+            let project = vault.find_project(&project)?;
+            let env = vault.project_environment(project.id)?;
+
+            // O cofre não precisa ficar aberto enquanto o comando roda
+            drop(vault);
+
+            return run_child(&command, env);
+        }
+    };
+
+    done.map(|()| ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    apply_saved_locale();
+
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    match run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            if json {
+                if let Ok(error) = serde_json::to_string(&e) {
+                    eprintln!("{}", error);
+                }
+            } else {
+                eprintln!("{}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Verificação offline de senhas vazadas contra uma cópia local do Have I
+//! Been Pwned (senhas em SHA-1).
+//!
+//! Aceita os dois formatos distribuídos pelo HIBP, ambos ordenados por hash:
+//!
+//! - um arquivo único (`pwned-passwords-sha1-ordered-by-hash-*.txt`), com
+//!   linhas `HASH:CONTAGEM`;
+//! - um diretório de arquivos de faixa, como baixados pelo
+//!   PwnedPasswordsDownloader: um arquivo por prefixo de 5 caracteres
+//!   (`21BD1` ou `21BD1.txt`) com linhas `SUFIXO:CONTAGEM`.
+//!
+//! A busca é binária direto no arquivo (seek), sem carregá-lo em memória, e
+//! nada sai da máquina. O resultado fica em `secrets.breach_count` e aparece
+//! nas listagens como `breached`; trocar a senha apaga o resultado.
+
+use crate::database::{decrypt_column_string, SECRET_PASSWORD_COLUMN};
+use crate::error::{AppError, Result};
+use crate::vault::Vault;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+const PREFIX_LENGTH: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreachSource {
+    /// Arquivo único com os hashes completos
+    File(PathBuf),
+    /// Diretório com um arquivo por prefixo
+    RangeDirectory(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreachCheckSummary {
+    pub checked: usize,
+    pub breached: usize,
+    /// Segredos cuja senha aparece no HIBP
+    pub breached_ids: Vec<i64>,
+}
+
+/// SHA-1 da senha em hexadecimal maiúsculo, como no HIBP.
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+impl BreachSource {
+    /// Um diretório é lido como faixas; um arquivo, como a lista completa.
+    pub fn open(path: &Path) -> Result<Self> {
+        let metadata = std::fs::metadata(path)
+            .map_err(|_| AppError::NotFound(format!("hibp path={:?}", path)))?;
+
+        if metadata.is_dir() {
+            Ok(BreachSource::RangeDirectory(path.to_path_buf()))
+        } else {
+            Ok(BreachSource::File(path.to_path_buf()))
+        }
+    }
+
+    /// Quantas vezes a senha aparece em vazamentos; 0 se não aparece.
+    pub fn lookup(&self, password: &str) -> Result<u64> {
+        let hash = sha1_hex(password);
+
+        let found = match self {
+            BreachSource::File(path) => search_sorted(&mut open_reader(path)?, &hash)?,
+            BreachSource::RangeDirectory(dir) => {
+                let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+                let path = [dir.join(prefix), dir.join(format!("{}.txt", prefix))]
+                    .into_iter()
+                    .find(|path| path.is_file())
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!("hibp_range prefix={} dir={:?}", prefix, dir))
+                    })?;
+
+                search_sorted(&mut open_reader(&path)?, suffix)?
+            }
+        };
+
+        Ok(found.unwrap_or(0))
+    }
+}
+
+fn open_reader(path: &Path) -> Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(path)?))
+}
+
+/// Lê a linha que começa em `position` e devolve o hash, a contagem e onde
+/// começa a próxima linha. `None` no fim do arquivo.
+fn read_entry(reader: &mut BufReader<File>, position: u64) -> Result<Option<(String, u64, u64)>> {
+    reader.seek(SeekFrom::Start(position))?;
+
+    let mut line = String::new();
+    let read = reader.read_line(&mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+
+    let (hash, count) = line
+        .trim_end()
+        .split_once(':')
+        .ok_or_else(|| AppError::InvalidInput(format!("hibp.line value={:?}", line.trim_end())))?;
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| AppError::InvalidInput(format!("hibp.count value={:?}", count)))?;
+
+    Ok(Some((
+        hash.to_ascii_uppercase(),
+        count,
+        position + read as u64,
+    )))
+}
+
+/// Busca binária por `key` num arquivo de linhas `HASH:CONTAGEM` ordenado.
+///
+/// Invariante: se a linha existe, ela começa em `[low, high)`. A cada passo
+/// lê a primeira linha que começa em `mid` ou depois.
+fn search_sorted(reader: &mut BufReader<File>, key: &str) -> Result<Option<u64>> {
+    let (mut low, mut high) = (0u64, reader.get_ref().metadata()?.len());
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        // Pula o resto da linha em que `mid` cai
+        let start = if mid == low {
+            low
+        } else {
+            reader.seek(SeekFrom::Start(mid - 1))?;
+            let mut skipped = Vec::new();
+            mid - 1 + reader.read_until(b'\n', &mut skipped)? as u64
+        };
+        if start >= high {
+            high = mid;
+            continue;
+        }
+
+        let Some((hash, count, next)) = read_entry(reader, start)? else {
+            high = mid;
+            continue;
+        };
+
+        match hash.as_str().cmp(key) {
+            Ordering::Equal => return Ok(Some(count)),
+            Ordering::Less => low = next,
+            Ordering::Greater => high = mid,
+        }
+    }
+
+    Ok(None)
+}
+
+impl Vault {
+    /// Confere a senha de cada segredo (inclusive na lixeira) na base local do
+    /// HIBP em `path` e grava o resultado.
+    pub fn check_breaches(&mut self, path: &Path) -> Result<BreachCheckSummary> {
+        let source = BreachSource::open(path)?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+
+        let passwords: Vec<(i64, Zeroizing<String>)> = {
+            let mut stmt = tx.prepare("SELECT id, password_blob FROM secrets ORDER BY id")?;
+            let rows = stmt.query_map([], |row| {
+                let id: i64 = row.get(0)?;
+
+                Ok((
+                    id,
+                    Zeroizing::new(decrypt_column_string(
+                        row,
+                        1,
+                        field_key,
+                        SECRET_PASSWORD_COLUMN,
+                        id,
+                    )?),
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut summary = BreachCheckSummary::default();
+        for (id, password) in &passwords {
+            if password.is_empty() {
+                continue;
+            }
+
+            let count = source.lookup(password)?;
+            tx.execute(
+                "UPDATE secrets SET breach_count = ?1 WHERE id = ?2",
+                (count as i64, id),
+            )?;
+
+            summary.checked += 1;
+            if count > 0 {
+                summary.breached += 1;
+                summary.breached_ids.push(*id);
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+    use std::fs;
+
+    /// Base com `passwords` e alguns hashes de enchimento, em ordem.
+    fn write_dump(dir: &Path, passwords: &[(&str, u64)]) -> (PathBuf, PathBuf) {
+        let mut lines: Vec<(String, u64)> = passwords
+            .iter()
+            .map(|(password, count)| (sha1_hex(password), *count))
+            .collect();
+        for i in 0..200 {
+            lines.push((sha1_hex(&format!("enchimento{}", i)), i + 1));
+        }
+        lines.sort();
+
+        let file = dir.join("pwned.txt");
+        let content: String = lines
+            .iter()
+            .map(|(hash, count)| format!("{}:{}\r\n", hash, count))
+            .collect();
+        fs::write(&file, content).unwrap();
+
+        let ranges = dir.join("ranges");
+        fs::create_dir_all(&ranges).unwrap();
+        for (hash, count) in &lines {
+            let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+            let path = ranges.join(format!("{}.txt", prefix));
+            let mut content = fs::read_to_string(&path).unwrap_or_default();
+            content.push_str(&format!("{}:{}\n", suffix, count));
+            fs::write(&path, content).unwrap();
+        }
+
+        (file, ranges)
+    }
+
+    #[test]
+    fn test_lookup_in_file_and_ranges() {
+        let dir = std::env::temp_dir().join("test_breach_lookup");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let (file, ranges) = write_dump(&dir, &[("password", 9545824), ("123456", 37359195)]);
+
+        assert_eq!(
+            sha1_hex("password"),
+            "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8"
+        );
+
+        let file = BreachSource::open(&file).unwrap();
+        assert_eq!(file.lookup("password").unwrap(), 9545824);
+        assert_eq!(file.lookup("123456").unwrap(), 37359195);
+        assert_eq!(file.lookup("enchimento0").unwrap(), 1);
+        assert_eq!(file.lookup("enchimento199").unwrap(), 200);
+        assert_eq!(file.lookup("k#9Tq!vZ2@xLm4&w").unwrap(), 0);
+
+        let ranges = BreachSource::open(&ranges).unwrap();
+        assert!(matches!(ranges, BreachSource::RangeDirectory(_)));
+        assert_eq!(ranges.lookup("password").unwrap(), 9545824);
+        // Faixa que não existe no espelho
+        assert!(ranges.lookup("k#9Tq!vZ2@xLm4&w").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_check_breaches_flags_listing() {
+        let dir = std::env::temp_dir().join("test_breach_vault");
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: "test_breach_vault".to_string(),
+            name: "test_breach_vault".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+
+        let leaked = vault
+            .create_typed_secret("Antigo", "eu", "password", None, &[])
+            .unwrap();
+        let safe = vault
+            .create_typed_secret("Novo", "eu", "k#9Tq!vZ2@xLm4&w", None, &[])
+            .unwrap();
+
+        let (file, _) = write_dump(&dir, &[("password", 3)]);
+        let summary = vault.check_breaches(&file).unwrap();
+        assert_eq!(summary.checked, 2);
+        assert_eq!(summary.breached_ids, [leaked.id]);
+
+        let breached = |vault: &Vault| -> Vec<(i64, bool)> {
+            vault
+                .secret_summaries(false)
+                .unwrap()
+                .iter()
+                .map(|s| (s.id, s.breached))
+                .collect()
+        };
+        assert_eq!(breached(&vault), [(leaked.id, true), (safe.id, false)]);
+
+        // Trocar a senha apaga o resultado anterior
+        vault
+            .update_secret(leaked.id, "Antigo", "eu", Some("outra-senha-nova"), None)
+            .unwrap();
+        assert_eq!(breached(&vault), [(leaked.id, false), (safe.id, false)]);
+
+        drop(vault);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
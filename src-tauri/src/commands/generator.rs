@@ -0,0 +1,38 @@
+use crate::error::Result;
+use crate::generator::{self, GeneratedSecret, GeneratorOptions};
+use crate::state::AppState;
+use tauri::State;
+
+/// Gera uma senha ou frase-senha. Sem opções e com `project_id`, usa a
+/// política do projeto; o cofre só é consultado nesse caso.
+#[tauri::command]
+pub fn generate_secret(
+    options: Option<GeneratorOptions>,
+    project_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<GeneratedSecret> {
+    match project_id {
+        Some(project_id) => {
+            state.with_vault(|vault| vault.generate_for_project(project_id, options.as_ref()))
+        }
+        None => generator::generate(&options.unwrap_or_default()),
+    }
+}
+
+#[tauri::command]
+pub fn get_generator_policy(
+    project_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<GeneratorOptions>> {
+    state.with_vault(|vault| vault.generator_policy(project_id))
+}
+
+/// Define a política de geração do projeto; `None` volta ao padrão.
+#[tauri::command]
+pub fn set_generator_policy(
+    project_id: i64,
+    options: Option<GeneratorOptions>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    state.with_vault(|vault| vault.set_generator_policy(project_id, options.as_ref()))
+}
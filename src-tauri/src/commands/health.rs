@@ -0,0 +1,37 @@
+use crate::breach::BreachCheckSummary;
+use crate::error::Result;
+use crate::health::HealthReport;
+use crate::i18n::{t, Msg};
+use crate::state::AppState;
+use std::path::Path;
+use tauri::State;
+
+/// Senhas fracas, repetidas e antigas e logins sem usuário nos segredos fora
+/// da lixeira. Nenhuma senha sai no relatório.
+#[tauri::command]
+pub fn vault_health_report(state: State<'_, AppState>) -> Result<HealthReport> {
+    state.with_vault(|vault| vault.health_report())
+}
+
+#[tauri::command]
+pub fn get_password_max_age(state: State<'_, AppState>) -> Result<u32> {
+    state.with_vault(|vault| vault.password_max_age())
+}
+
+/// Idade máxima das senhas, em dias; 0 desliga a verificação.
+#[tauri::command]
+pub fn set_password_max_age(days: u32, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.set_password_max_age(days))?;
+
+    Ok(t(Msg::PasswordMaxAgeUpdated).to_string())
+}
+
+/// Confere as senhas na base local do HIBP em `path` (arquivo completo ou
+/// diretório de faixas). Nada é enviado pela rede.
+#[tauri::command]
+pub fn check_breached_passwords(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<BreachCheckSummary> {
+    state.with_vault(|vault| vault.check_breaches(Path::new(&path)))
+}
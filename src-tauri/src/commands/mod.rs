@@ -1,6 +1,10 @@
 pub mod attachments;
 pub mod auth;
+pub mod generator;
+pub mod health;
 pub mod projects;
+pub mod search;
 pub mod secrets;
+pub mod settings;
 pub mod trash;
 pub mod vaults;
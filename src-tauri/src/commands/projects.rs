@@ -1,5 +1,11 @@
-use crate::models::Project;
+use crate::error::Result;
+use crate::i18n::{t, tf, Msg};
+use crate::models::{EnvBinding, Project, ProjectEnvVar};
+use crate::query::{Page, QueryOptions};
 use crate::state::AppState;
+use crate::utils::write_private;
+use std::fs;
+use std::path::Path;
 use tauri::State;
 
 #[tauri::command]
@@ -7,100 +13,29 @@ pub fn create_project(
     state: State<'_, AppState>,
     name: String,
     description: Option<String>,
-) -> Result<i64, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    conn.execute(
-        "INSERT INTO projects (name, description) VALUES (?1, ?2)",
-        (name, description),
-    )
-    .map_err(|e| e.to_string())?;
-
-    let id = conn.last_insert_rowid();
-    Ok(id)
+) -> Result<i64> {
+    state.with_vault(|vault| vault.create_project(&name, description.as_deref()))
 }
 
 #[tauri::command]
-pub fn get_all_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    let mut stmt = conn
-        .prepare("SELECT id, name, description, created_at FROM projects WHERE deleted_at IS NULL ORDER BY name ASC")
-        .map_err(|e| e.to_string())?;
-
-    let projects_iter = stmt
-        .query_map([], |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                created_at: row.get(3)?,
-                deleted_at: None,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-
-    let mut projects = Vec::new();
-    for project in projects_iter {
-        projects.push(project.map_err(|e| e.to_string())?);
-    }
-
-    Ok(projects)
+pub fn get_all_projects(
+    state: State<'_, AppState>,
+    options: Option<QueryOptions>,
+) -> Result<Page<Project>> {
+    state.with_vault(|vault| vault.projects_page(false, &options.unwrap_or_default()))
 }
 
 #[tauri::command]
-pub fn get_deleted_projects(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    let mut stmt = conn
-        .prepare("SELECT id, name, description, created_at, deleted_at FROM projects WHERE deleted_at IS NOT NULL ORDER BY name ASC")
-        .map_err(|e| e.to_string())?;
-
-    let projects_iter = stmt
-        .query_map([], |row| {
-            Ok(Project {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                created_at: row.get(3)?,
-                deleted_at: row.get(4)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-
-    let mut projects = Vec::new();
-    for project in projects_iter {
-        projects.push(project.map_err(|e| e.to_string())?);
-    }
-
-    Ok(projects)
+pub fn get_deleted_projects(
+    state: State<'_, AppState>,
+    options: Option<QueryOptions>,
+) -> Result<Page<Project>> {
+    state.with_vault(|vault| vault.projects_page(true, &options.unwrap_or_default()))
 }
 
 #[tauri::command]
-pub fn get_project(state: State<'_, AppState>, id: i64) -> Result<Project, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    let project = conn
-        .query_row(
-            "SELECT id, name, description, created_at FROM projects WHERE id = ?1",
-            [id],
-            |row| {
-                Ok(Project {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    created_at: row.get(3)?,
-                    deleted_at: None,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
-
-    Ok(project)
+pub fn get_project(state: State<'_, AppState>, id: i64) -> Result<Project> {
+    state.with_vault(|vault| vault.project(id))
 }
 
 #[tauri::command]
@@ -109,80 +44,85 @@ pub fn update_project(
     id: i64,
     name: String,
     description: Option<String>,
-) -> Result<(), String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+) -> Result<()> {
+    state.with_vault(|vault| vault.update_project(id, &name, description.as_deref()))
+}
 
-    conn.execute(
-        "UPDATE projects SET name = ?1, description = ?2 WHERE id = ?3",
-        (name, description, id),
-    )
-    .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn soft_delete_project(state: State<'_, AppState>, id: i64) -> Result<()> {
+    state.with_vault(|vault| vault.soft_delete_project(id))
+}
 
-    Ok(())
+#[tauri::command]
+pub fn delete_project(state: State<'_, AppState>, id: i64) -> Result<()> {
+    state.with_vault(|vault| vault.delete_project(id))
 }
 
 #[tauri::command]
-pub fn soft_delete_project(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    let mut lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_mut().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-
-    // Passo 1: "Soltar" os segredos (Setar project_id = NULL)
-    // Isso é redundante se o banco suportar e estiver com PRAGMA foreign_keys = ON,
-    // mas é uma segurança extra essencial em SQLite embutido.
-    tx.execute(
-        "UPDATE secrets SET project_id = NULL WHERE project_id = ?1",
-        [id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Passo 2: Soft deletar o projeto
-    tx.execute(
-        "UPDATE projects SET deleted_at = ?1 WHERE id = ?2",
-        (chrono::Utc::now().to_rfc3339(), id),
-    )
-    .map_err(|e| e.to_string())?;
-
-    tx.commit().map_err(|e| e.to_string())?;
-
-    Ok(())
+pub fn restore_project(state: State<'_, AppState>, id: i64) -> Result<()> {
+    state.with_vault(|vault| vault.restore_project(id))
 }
 
 #[tauri::command]
-pub fn delete_project(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    let mut lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_mut().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn get_project_env_vars(
+    state: State<'_, AppState>,
+    project_id: i64,
+) -> Result<Vec<ProjectEnvVar>> {
+    state.with_vault(|vault| vault.env_vars(project_id))
+}
 
-    // Iniciamos uma transação para garantir atomicidade
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn get_project_env_bindings(
+    state: State<'_, AppState>,
+    project_id: i64,
+) -> Result<Vec<EnvBinding>> {
+    state.with_vault(|vault| vault.env_bindings(project_id))
+}
 
-    // Passo 1: "Soltar" os segredos (Setar project_id = NULL)
-    // Isso é redundante se o banco suportar e estiver com PRAGMA foreign_keys = ON,
-    // mas é uma segurança extra essencial em SQLite embutido.
-    tx.execute(
-        "UPDATE secrets SET project_id = NULL WHERE project_id = ?1",
-        [id],
-    )
-    .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn set_project_env_var(
+    state: State<'_, AppState>,
+    project_id: i64,
+    secret_title: String,
+    env_name: String,
+) -> Result<ProjectEnvVar> {
+    state.with_vault(|vault| vault.set_env_var(project_id, &secret_title, &env_name))
+}
+
+#[tauri::command]
+pub fn remove_project_env_var(
+    state: State<'_, AppState>,
+    project_id: i64,
+    secret_title: String,
+) -> Result<()> {
+    state.with_vault(|vault| vault.remove_env_var(project_id, &secret_title))
+}
 
-    // Passo 2: Deletar o projeto
-    tx.execute("DELETE FROM projects WHERE id = ?1", [id])
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn import_project_dotenv(
+    state: State<'_, AppState>,
+    project_id: i64,
+    file_path: String,
+) -> Result<String> {
+    let content = fs::read_to_string(&file_path)?;
 
-    tx.commit().map_err(|e| e.to_string())?;
+    let summary = state.with_vault(|vault| vault.import_dotenv(project_id, &content))?;
 
-    Ok(())
+    Ok(tf(
+        Msg::DotenvImportSummary,
+        &[&summary.inserted, &summary.updated, &summary.unchanged],
+    ))
 }
 
 #[tauri::command]
-pub fn restore_project(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn export_project_dotenv(
+    state: State<'_, AppState>,
+    project_id: i64,
+    file_path: String,
+) -> Result<String> {
+    let content = state.with_vault(|vault| vault.export_dotenv(project_id))?;
 
-    conn.execute("UPDATE projects SET deleted_at = NULL WHERE id = ?", [id])
-        .map_err(|e| format!("Erro ao restaurar projeto: {}", e))?;
+    write_private(Path::new(&file_path), content.as_bytes())?;
 
-    Ok(())
+    Ok(t(Msg::DotenvExported).to_string())
 }
@@ -0,0 +1,15 @@
+use crate::error::Result;
+use crate::search::{SearchFilters, SearchHit};
+use crate::state::AppState;
+use tauri::State;
+
+/// Busca segredos e projetos por título, usuário, URLs, notas, projeto e
+/// nomes de anexos. Valores protegidos nunca são pesquisados.
+#[tauri::command]
+pub fn search(
+    query: String,
+    filters: Option<SearchFilters>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchHit>> {
+    state.with_vault(|vault| vault.search(&query, &filters.unwrap_or_default()))
+}
@@ -1,156 +1,199 @@
-use crate::models::Secret;
+use crate::custom_fields::CustomField;
+use crate::error::Result;
+use crate::i18n::{t, Msg};
+use crate::models::{Secret, SecretField, SecretSummary};
+use crate::query::{Page, QueryOptions};
+use crate::secret_history::{SecretVersion, VersionChange};
+use crate::secret_kinds::SecretPayload;
 use crate::state::AppState;
+use crate::totp::{TotpCode, TotpInfo};
 use tauri::State;
 
+/// Cria um login ou, com `payload`, um segredo do tipo do payload.
 #[tauri::command]
 pub fn create_secret(
     title: String,
     username: String,
     password: String,
+    payload: Option<SecretPayload>,
+    fields: Option<Vec<CustomField>>,
     state: State<'_, AppState>,
-) -> Result<Secret, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    conn.execute(
-        "INSERT INTO secrets (title, username, password_blob) VALUES (?1, ?2, ?3)",
-        (title.clone(), username.clone(), password.as_bytes()),
-    )
-    .map_err(|e| format!("Erro ao salvar segredo: {}", e))?;
-
-    let id = conn.last_insert_rowid();
-
-    Ok(Secret {
-        id,
-        title,
-        username,
-        password,
-        created_at: chrono::Utc::now().to_rfc3339(),
-        deleted_at: None,
-        project_id: None,
+) -> Result<Secret> {
+    state.with_vault(|vault| {
+        vault.create_typed_secret(
+            &title,
+            &username,
+            &password,
+            payload.as_ref(),
+            &fields.unwrap_or_default(),
+        )
     })
 }
 
+/// Lista os segredos ativos sem nenhum valor sensível. Sem `options`, devolve
+/// todos numa página só. Para ler a senha use `reveal_secret_field`.
 #[tauri::command]
-pub fn get_all_secrets(state: State<'_, AppState>) -> Result<Vec<Secret>, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn list_secrets(
+    options: Option<QueryOptions>,
+    state: State<'_, AppState>,
+) -> Result<Page<SecretSummary>> {
+    state.with_vault(|vault| vault.secret_summaries_page(false, &options.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub fn list_deleted_secrets(
+    options: Option<QueryOptions>,
+    state: State<'_, AppState>,
+) -> Result<Page<SecretSummary>> {
+    state.with_vault(|vault| vault.secret_summaries_page(true, &options.unwrap_or_default()))
+}
 
-    let mut stmt = conn
-        .prepare("SELECT id, project_id, title, username, password_blob, created_at FROM secrets WHERE deleted_at IS NULL")
-        .map_err(|e| format!("Erro ao obter secretos: {}", e))?;
+/// Decifra um único campo de um segredo. Cada chamada é registrada no
+/// `audit_log`; se o registro falhar, o valor não é revelado.
+#[tauri::command]
+pub fn reveal_secret_field(
+    id: i64,
+    field: SecretField,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    state.with_vault(|vault| vault.reveal_secret_field(id, field))
+}
+
+/// Campos personalizados do segredo, sem o valor dos campos ocultos.
+#[tauri::command]
+pub fn get_secret_fields(id: i64, state: State<'_, AppState>) -> Result<Vec<CustomField>> {
+    state.with_vault(|vault| vault.secret_fields(id))
+}
 
-    let secrets_iter = stmt
-        .query_map([], |row| {
-            let pass_blob: Vec<u8> = row.get(4)?;
-            let pass_str = String::from_utf8(pass_blob).unwrap_or_default();
+/// Decifra um campo personalizado; a leitura fica registrada no `audit_log`.
+#[tauri::command]
+pub fn reveal_custom_field(id: i64, name: String, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.reveal_custom_field(id, &name))
+}
 
-            Ok(Secret {
-                id: row.get(0)?,
-                title: row.get(2)?,
-                username: row.get(3)?,
-                password: pass_str,
-                created_at: row.get(5)?,
-                deleted_at: None,
-                project_id: row.get(1)?,
-            })
-        })
-        .map_err(|e| format!("Erro ao obter secretos: {}", e))?;
+#[tauri::command]
+pub fn set_secret_project(
+    id: i64,
+    project_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    state.with_vault(|vault| vault.set_secret_project(id, project_id))
+}
 
-    let mut secrets = Vec::new();
-    for secret in secrets_iter {
-        secrets.push(secret.map_err(|e| e.to_string())?);
-    }
+#[tauri::command]
+pub fn soft_delete_secret(id: i64, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.soft_delete_secret(id))?;
 
-    Ok(secrets)
+    Ok(t(Msg::SecretMovedToTrash).to_string())
 }
 
 #[tauri::command]
-pub fn get_deleted_secrets(state: State<'_, AppState>) -> Result<Vec<Secret>, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn delete_secret(id: i64, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.delete_secret(id))?;
 
-    let mut stmt = conn
-        .prepare("SELECT id, project_id, title, username, password_blob, created_at, deleted_at FROM secrets WHERE deleted_at IS NOT NULL")
-        .map_err(|e| format!("Erro ao obter segredos deletados: {}", e))?;
+    Ok(t(Msg::SecretDeleted).to_string())
+}
 
-    let secrets_iter = stmt
-        .query_map([], |row| {
-            let pass_blob: Vec<u8> = row.get(4)?;
-            let pass_str = String::from_utf8(pass_blob).unwrap_or_default();
+#[tauri::command]
+pub fn restore_secret(id: i64, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.restore_secret(id))?;
 
-            Ok(Secret {
-                id: row.get(0)?,
-                title: row.get(2)?,
-                username: row.get(3)?,
-                password: pass_str,
-                created_at: row.get(5)?,
-                deleted_at: row.get(6)?,
-                project_id: row.get(1)?,
-            })
-        })
-        .map_err(|e| format!("Erro ao obter segredos deletados: {}", e))?;
+    Ok(t(Msg::SecretRestored).to_string())
+}
 
-    let mut secrets = Vec::new();
-    for secret in secrets_iter {
-        secrets.push(secret.map_err(|e| e.to_string())?);
-    }
+/// Atualiza um segredo. Com `fields`, os campos personalizados são
+/// substituídos; campos ocultos sem valor mantêm o valor atual. O estado
+/// anterior fica no histórico de versões.
+#[tauri::command]
+pub fn update_secret(
+    id: i64,
+    title: String,
+    username: String,
+    password: String,
+    fields: Option<Vec<CustomField>>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    state.with_vault(|vault| {
+        vault.update_secret(id, &title, &username, Some(&password), fields.as_deref())
+    })?;
 
-    Ok(secrets)
+    Ok(t(Msg::SecretUpdated).to_string())
 }
 
+/// Troca os dados estruturados (e o tipo) do segredo; sem `payload`, ele
+/// volta a ser um login.
 #[tauri::command]
-pub fn soft_delete_secret(id: i32, state: State<'_, AppState>) -> Result<String, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn update_secret_payload(
+    id: i64,
+    payload: Option<SecretPayload>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    state.with_vault(|vault| vault.update_secret_payload(id, payload.as_ref()))?;
 
-    conn.execute(
-        "UPDATE secrets SET deleted_at = ?1 WHERE id = ?2",
-        (chrono::Utc::now().to_rfc3339(), id),
-    )
-    .map_err(|e| format!("Erro ao deletar segredo: {}", e))?;
+    Ok(t(Msg::SecretUpdated).to_string())
+}
 
-    Ok("Segredo movido para a lixeira com sucesso!".to_string())
+/// Versões anteriores do segredo, da mais recente para a mais antiga.
+#[tauri::command]
+pub fn get_secret_versions(id: i64, state: State<'_, AppState>) -> Result<Vec<SecretVersion>> {
+    state.with_vault(|vault| vault.secret_versions(id))
 }
 
+/// Compara duas versões do segredo (`None` é o estado atual). Senhas, campos
+/// ocultos e partes sensíveis do payload vêm mascarados.
 #[tauri::command]
-pub fn delete_secret(id: i32, state: State<'_, AppState>) -> Result<String, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn compare_secret_versions(
+    id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<VersionChange>> {
+    state.with_vault(|vault| vault.compare_secret_versions(id, from, to))
+}
 
-    conn.execute("DELETE FROM secrets WHERE id = ?", (id,))
-        .map_err(|e| format!("Erro ao deletar segredo: {}", e))?;
+#[tauri::command]
+pub fn restore_secret_version(
+    id: i64,
+    version_id: i64,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    state.with_vault(|vault| vault.restore_secret_version(id, version_id))?;
 
-    Ok("Segredo deletado!".to_string())
+    Ok(t(Msg::SecretVersionRestored).to_string())
 }
 
 #[tauri::command]
-pub fn restore_secret(id: i32, state: State<'_, AppState>) -> Result<String, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn get_history_limit(state: State<'_, AppState>) -> Result<usize> {
+    state.with_vault(|vault| vault.history_limit())
+}
 
-    conn.execute("UPDATE secrets SET deleted_at = NULL WHERE id = ?", (id,))
-        .map_err(|e| format!("Erro ao restaurar segredo: {}", e))?;
+/// Define quantas versões guardar por segredo; 0 desliga o histórico.
+#[tauri::command]
+pub fn set_history_limit(limit: usize, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.set_history_limit(limit))?;
 
-    Ok("Segredo restaurado com sucesso!".to_string())
+    Ok(t(Msg::HistoryLimitUpdated).to_string())
 }
 
+/// Associa ao segredo uma semente TOTP (URI `otpauth://` de QR code ou chave
+/// base32), ou a remove sem `uri`. Devolve os dados de exibição.
 #[tauri::command]
-pub fn update_secret(
-    id: i32,
-    title: String,
-    username: String,
-    password: String,
+pub fn set_secret_totp(
+    id: i64,
+    uri: Option<String>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+) -> Result<Option<TotpInfo>> {
+    state.with_vault(|vault| vault.set_secret_totp(id, uri.as_deref()))
+}
 
-    conn.execute(
-        "UPDATE secrets SET title = ?1, username = ?2, password_blob = ?3 WHERE id = ?4",
-        (title, username, password.as_bytes(), id),
-    )
-    .map_err(|e| format!("Erro ao atualizar segredo: {}", e))?;
+/// Emissor, conta e parâmetros da semente TOTP; a chave nunca sai do cofre.
+#[tauri::command]
+pub fn get_secret_totp(id: i64, state: State<'_, AppState>) -> Result<Option<TotpInfo>> {
+    state.with_vault(|vault| vault.secret_totp(id))
+}
 
-    Ok("Segredo atualizado!".to_string())
+/// Código TOTP atual e os segundos até ele trocar.
+#[tauri::command]
+pub fn get_totp_code(id: i64, state: State<'_, AppState>) -> Result<TotpCode> {
+    state.with_vault(|vault| vault.secret_totp_code(id))
 }
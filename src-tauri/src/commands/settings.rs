@@ -0,0 +1,58 @@
+use crate::error::{AppError, Result};
+use crate::i18n::{self, set_current_locale, Locale, Msg};
+use crate::settings::Settings;
+use crate::utils::get_settings_path;
+use std::{fs, path::PathBuf};
+
+#[tauri::command]
+pub fn get_settings() -> Result<Settings> {
+    Settings::load(&get_settings_path()?)
+}
+
+#[tauri::command]
+pub fn get_locale() -> Result<Locale> {
+    Ok(i18n::current_locale())
+}
+
+#[tauri::command]
+pub fn set_locale(locale: Locale) -> Result<String> {
+    let path = get_settings_path()?;
+
+    let mut settings = Settings::load(&path)?;
+    settings.locale = locale;
+    settings.save(&path)?;
+
+    set_current_locale(locale);
+
+    Ok(i18n::t(Msg::LocaleChanged).to_string())
+}
+
+/// Define a pasta onde novos cofres são criados. `None` volta para a pasta
+/// padrão. Cofres já existentes continuam onde estão.
+#[tauri::command]
+pub fn set_vaults_dir(path: Option<String>) -> Result<String> {
+    let vaults_dir = match path {
+        Some(path) => {
+            let dir = PathBuf::from(path);
+
+            if !dir.is_absolute() {
+                return Err(AppError::InvalidInput(format!(
+                    "vaults_dir path={:?} expected=absolute",
+                    dir
+                )));
+            }
+
+            fs::create_dir_all(&dir)?;
+            Some(dir)
+        }
+        None => None,
+    };
+
+    let settings_path = get_settings_path()?;
+
+    let mut settings = Settings::load(&settings_path)?;
+    settings.vaults_dir = vaults_dir;
+    settings.save(&settings_path)?;
+
+    Ok(i18n::t(Msg::SettingsSaved).to_string())
+}
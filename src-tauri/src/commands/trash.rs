@@ -1,16 +1,51 @@
+use crate::error::Result;
+use crate::i18n::{t, tf, Msg};
 use crate::state::AppState;
-use tauri::State;
+use crate::trash_retention::TrashPurgeSummary;
+use tauri::{AppHandle, Emitter, State};
+
+/// Evento com a mensagem de uma limpeza automática que excluiu itens.
+pub const TRASH_PURGED_EVENT: &str = "trash-purged";
+/// Evento com a mensagem de uma limpeza automática que falhou.
+pub const TRASH_PURGE_FAILED_EVENT: &str = "trash-purge-failed";
+
+/// Avisa a interface do resultado de uma limpeza automática da lixeira
+/// (ao destrancar ou periódica). Limpezas que não excluíram nada não geram
+/// evento.
+pub(crate) fn report_purge(app: &AppHandle, result: Result<TrashPurgeSummary>) {
+    let _ = match result {
+        Ok(summary) if summary.is_empty() => Ok(()),
+        Ok(summary) => app.emit(
+            TRASH_PURGED_EVENT,
+            tf(Msg::TrashPurged, &[&summary.secrets, &summary.projects]),
+        ),
+        Err(e) => app.emit(TRASH_PURGE_FAILED_EVENT, tf(Msg::TrashPurgeFailed, &[&e])),
+    };
+}
 
 #[tauri::command]
-pub fn empty_trash(state: State<'_, AppState>) -> Result<String, String> {
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
+pub fn empty_trash(state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.empty_trash())?;
 
-    conn.execute("DELETE FROM secrets WHERE deleted_at IS NOT NULL", ())
-        .map_err(|e| format!("Erro ao deletar segredos: {}", e))?;
+    Ok(t(Msg::TrashEmptied).to_string())
+}
 
-    conn.execute("DELETE FROM projects WHERE deleted_at IS NOT NULL", ())
-        .map_err(|e| format!("Erro ao deletar projetos: {}", e))?;
+/// Dias que um item fica na lixeira antes de ser excluído; 0 guarda para sempre.
+#[tauri::command]
+pub fn get_trash_retention(state: State<'_, AppState>) -> Result<u32> {
+    state.with_vault(|vault| vault.trash_retention())
+}
 
-    Ok("Lixeira vazia!".to_string())
+/// Troca o prazo da lixeira e já exclui o que venceu com o prazo novo.
+#[tauri::command]
+pub fn set_trash_retention(days: u32, state: State<'_, AppState>) -> Result<String> {
+    state.with_vault(|vault| vault.set_trash_retention(days))?;
+
+    Ok(t(Msg::TrashRetentionUpdated).to_string())
+}
+
+/// Exclui agora os itens vencidos, sem esperar a próxima limpeza automática.
+#[tauri::command]
+pub fn purge_expired_trash(state: State<'_, AppState>) -> Result<TrashPurgeSummary> {
+    state.with_vault(|vault| vault.purge_expired_trash())
 }
@@ -1,166 +1,332 @@
-use crate::commands::secrets::get_all_secrets;
+use crate::commands::trash::report_purge;
 use crate::database::initialize_database;
-use crate::models::Secret;
-use crate::security::{decrypt_data, derive_key_from_password, encrypt_data, generate_salt};
+use crate::error::{AppError, Result};
+use crate::exporters::{ExportFormat, ExportReport};
+use crate::i18n::{t, tf, Msg};
+use crate::importers::{self, CsvMapping, ExternalImportSummary, ImportFormat};
+use crate::models::VaultInfo;
+use crate::registry::{
+    locate_vault_pair, validate_vault_pair, VaultEntry, VaultRegistry, DEFAULT_VAULT_NAME,
+};
 use crate::state::AppState;
-use crate::utils::{get_db_path, get_meta_path};
+use crate::utils::write_private;
+use crate::utils::{get_vaults_dir, read_vault_meta, recover_interrupted_rekey};
+use crate::vault::{self, load_registry, Vault};
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use tauri::State;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+fn vault_info(entry: &VaultEntry, active: Option<&str>) -> VaultInfo {
+    VaultInfo {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        created_at: entry.created_at.clone(),
+        initialized: entry.meta_path.exists(),
+        unlocked: active == Some(entry.id.as_str()),
+        external: entry.external,
+    }
+}
 
-#[tauri::command]
-pub fn check_vault_status() -> Result<bool, String> {
-    let meta_path = get_meta_path()?;
-    Ok(meta_path.exists())
+fn vault_infos(registry: &VaultRegistry, state: &AppState) -> Result<Vec<VaultInfo>> {
+    let lock = state.vault.lock()?;
+    let active = lock.as_ref().map(|vault| vault.id());
+
+    Ok(registry
+        .vaults
+        .iter()
+        .map(|entry| vault_info(entry, active))
+        .collect())
+}
+
+/// Cria os arquivos de um cofre novo, registra e abre.
+fn create_entry(
+    mut registry: VaultRegistry,
+    registry_path: &Path,
+    name: &str,
+    password: &str,
+    state: &AppState,
+) -> Result<VaultInfo> {
+    let entry = registry.new_entry(name, &get_vaults_dir()?)?;
+
+    let vault = Vault::create(&entry, password)?;
+
+    registry.last_opened = Some(entry.id.clone());
+    registry.vaults.push(entry.clone());
+    registry.save(registry_path)?;
+
+    // O cofre anterior, se houver, é fechado junto com sua chave
+    *state.vault.lock()? = Some(vault);
+
+    Ok(vault_info(&entry, Some(&entry.id)))
 }
 
+/// Situação de todos os cofres registrados. Também conclui trocas de senha
+/// interrompidas em qualquer um deles.
 #[tauri::command]
-pub fn setup_vault(password: String, state: State<'_, AppState>) -> Result<String, String> {
-    let meta_path = get_meta_path()?;
+pub fn check_vault_status(state: State<'_, AppState>) -> Result<Vec<VaultInfo>> {
+    let (registry, _) = load_registry()?;
 
-    if meta_path.exists() {
-        return Err("Um cofre já existe neste computador.".to_string());
+    for entry in &registry.vaults {
+        recover_interrupted_rekey(&entry.db_path, &entry.meta_path)?;
     }
 
-    let salt = generate_salt();
+    vault_infos(&registry, &state)
+}
+
+#[tauri::command]
+pub fn list_vaults(state: State<'_, AppState>) -> Result<Vec<VaultInfo>> {
+    let (registry, _) = load_registry()?;
+
+    vault_infos(&registry, &state)
+}
 
-    fs::write(&meta_path, &salt).map_err(|e| format!("Erro ao salvar meta: {}", e))?;
+/// Cria o primeiro cofre. Para cofres adicionais, use `create_vault`.
+#[tauri::command]
+pub fn setup_vault(password: String, state: State<'_, AppState>) -> Result<String> {
+    let (registry, registry_path) = load_registry()?;
 
-    let db_path = get_db_path()?;
-    if db_path.exists() {
-        println!("Limpando banco de dados antigo em {:?}", db_path);
-        fs::remove_file(&db_path).map_err(|e| format!("Erro ao limpar banco: {}", e))?;
+    if !registry.vaults.is_empty() {
+        return Err(AppError::VaultAlreadyExists);
     }
 
-    let key = derive_key_from_password(&password, &salt).map_err(|e| e.to_string())?;
+    create_entry(
+        registry,
+        &registry_path,
+        DEFAULT_VAULT_NAME,
+        &password,
+        &state,
+    )?;
 
-    let conn =
-        initialize_database(&db_path, &key).map_err(|e| format!("Erro ao criar banco: {}", e))?;
+    Ok(t(Msg::VaultCreated).to_string())
+}
 
-    *state.db.lock().map_err(|_| "Falha no Mutex".to_string())? = Some(conn);
+#[tauri::command]
+pub fn create_vault(
+    name: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<VaultInfo> {
+    let (registry, registry_path) = load_registry()?;
 
-    Ok("Cofre criado com sucesso!".to_string())
+    create_entry(registry, &registry_path, &name, &password, &state)
 }
 
+/// Abre um cofre. Sem `vault_id`, abre o último cofre usado.
 #[tauri::command]
-pub fn unlock_vault(password: String, state: State<'_, AppState>) -> Result<String, String> {
-    let meta_path = get_meta_path()?;
+pub fn unlock_vault(
+    password: String,
+    vault_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    let (mut registry, registry_path) = load_registry()?;
+    let entry = registry.resolve(vault_id.as_deref())?.clone();
+
+    // Se a senha estiver errada, o cofre aberto antes continua aberto
+    let mut vault = Vault::open(&entry, &password)?;
+    let purge = vault.take_unlock_purge();
+    *state.vault.lock()? = Some(vault);
+
+    if let Some(result) = purge {
+        report_purge(&app, result);
+    }
 
-    if !meta_path.exists() {
-        return Err("Nenhum cofre encontrado. Crie um primeiro.".to_string());
+    if registry.last_opened.as_deref() != Some(entry.id.as_str()) {
+        registry.last_opened = Some(entry.id);
+        registry.save(&registry_path)?;
     }
 
-    let salt =
-        fs::read_to_string(&meta_path).map_err(|_| "Erro ao ler arquivo de salt".to_string())?;
+    Ok(t(Msg::VaultUnlocked).to_string())
+}
 
-    let key = derive_key_from_password(&password, &salt).map_err(|e| e.to_string())?;
+/// Troca para outro cofre sem reiniciar o aplicativo.
+#[tauri::command]
+pub fn open_vault(
+    vault_id: String,
+    password: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    unlock_vault(password, Some(vault_id), app, state)
+}
 
-    let db_path = get_db_path()?;
-    let conn = initialize_database(&db_path, &key).map_err(|_| "Senha incorreta".to_string())?;
+/// Abre um cofre guardado fora da pasta de cofres (ex: um disco externo) a
+/// partir da pasta, do `vault.db` ou do `vault.meta`, e o registra.
+#[tauri::command]
+pub fn open_vault_at(
+    path: String,
+    password: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<VaultInfo> {
+    let (db_path, meta_path) = locate_vault_pair(Path::new(&path))?;
+
+    recover_interrupted_rekey(&db_path, &meta_path)?;
+    validate_vault_pair(&db_path, &meta_path)?;
+
+    // Caminhos canônicos para reconhecer o mesmo cofre aberto por outro caminho
+    let db_path = fs::canonicalize(db_path)?;
+    let meta_path = fs::canonicalize(meta_path)?;
+
+    let (mut registry, registry_path) = load_registry()?;
+    let entry = registry.register_external(&db_path, &meta_path);
+
+    // O registro só é gravado se a senha abrir o cofre
+    let mut vault = Vault::open(&entry, &password)?;
+    let purge = vault.take_unlock_purge();
+    *state.vault.lock()? = Some(vault);
+
+    if let Some(result) = purge {
+        report_purge(&app, result);
+    }
 
-    *state.db.lock().map_err(|_| "Falha no Mutex".to_string())? = Some(conn);
+    registry.last_opened = Some(entry.id.clone());
+    registry.save(&registry_path)?;
 
-    Ok("Cofre aberto!".to_string())
+    Ok(vault_info(&entry, Some(&entry.id)))
 }
 
 #[tauri::command]
-pub fn lock_vault(state: State<'_, AppState>) -> Result<String, String> {
-    let mut db_guard = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
+pub fn rename_vault(vault_id: String, name: String) -> Result<String> {
+    let (mut registry, registry_path) = load_registry()?;
 
-    *db_guard = None;
+    registry.rename(&vault_id, &name)?;
+    registry.save(&registry_path)?;
 
-    Ok("Cofre trancado.".to_string())
+    Ok(t(Msg::VaultRenamed).to_string())
 }
 
+/// Apaga o cofre e seus arquivos. Exige a senha do próprio cofre. Cofres
+/// externos são apenas removidos do registro.
 #[tauri::command]
-pub fn export_vault(
-    file_path: String,
+pub fn delete_vault(
+    vault_id: String,
     password: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let meta_path = get_meta_path()?;
-    let salt = fs::read_to_string(&meta_path).map_err(|_| "Erro ao ler salt".to_string())?;
+) -> Result<String> {
+    let (mut registry, registry_path) = load_registry()?;
+    let entry = registry.get(&vault_id)?.clone();
+
+    recover_interrupted_rekey(&entry.db_path, &entry.meta_path)?;
+
+    if entry.meta_path.exists() {
+        let (meta, _) = read_vault_meta(&entry.meta_path)?;
+        let key = meta.derive_key(&password)?;
+        drop(initialize_database(&entry.db_path, &key)?);
+    } else if !entry.external && entry.db_path.exists() {
+        // Sem o meta não há como conferir a senha, então o banco não é apagado
+        return Err(AppError::NotFound(format!("vault.meta id={}", entry.id)));
+    }
+
+    {
+        let mut lock = state.vault.lock()?;
+        if lock.as_ref().is_some_and(|vault| vault.id() == entry.id) {
+            *lock = None;
+        }
+    }
 
-    let key = derive_key_from_password(&password, &salt).map_err(|e| e.to_string())?;
+    registry.remove(&entry.id)?;
+    registry.save(&registry_path)?;
 
-    let secrets = get_all_secrets(state)?;
-    let json_data =
-        serde_json::to_string(&secrets).map_err(|e| format!("Erro ao gerar JSON: {}", e))?;
+    entry.remove_files()?;
 
-    let encrypted_bytes = encrypt_data(&json_data, &key).map_err(|e| e.to_string())?;
+    Ok(t(Msg::VaultDeleted).to_string())
+}
 
-    let mut file = File::create(file_path).map_err(|e| e.to_string())?;
-    file.write_all(&encrypted_bytes)
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn lock_vault(state: State<'_, AppState>) -> Result<String> {
+    *state.vault.lock()? = None;
 
-    Ok("Backup exportado com sucesso!".to_string())
+    Ok(t(Msg::VaultLockedOk).to_string())
 }
 
+/// Troca a senha mestre de um cofre. Sem `vault_id`, usa o cofre aberto ou o
+/// último cofre usado.
 #[tauri::command]
-pub fn import_vault(
+pub fn change_master_password(
+    old_password: String,
+    new_password: String,
+    vault_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    // Segura o Mutex durante toda a troca para que nenhuma escrita aconteça
+    // entre a cópia do banco e a substituição dos arquivos.
+    let mut lock = state.vault.lock()?;
+    let active = lock.as_ref().map(|vault| vault.id().to_string());
+
+    let (registry, _) = load_registry()?;
+    let entry = registry.resolve(vault_id.as_deref().or(active.as_deref()))?;
+
+    vault::change_master_password(entry, &old_password, &new_password, &mut lock)?;
+
+    Ok(t(Msg::MasterPasswordChanged).to_string())
+}
+
+#[tauri::command]
+pub fn export_vault(
     file_path: String,
     password: String,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let encrypted_bytes =
-        fs::read(&file_path).map_err(|e| format!("Erro ao ler arquivo: {}", e))?;
-    let meta_path = get_meta_path()?;
-    let salt = fs::read_to_string(&meta_path).map_err(|_| "Erro ao ler salt".to_string())?;
-    let key = derive_key_from_password(&password, &salt).map_err(|e| e.to_string())?;
-
-    let json_string = decrypt_data(&encrypted_bytes, &key).map_err(|e| e.to_string())?;
-    let imported_secrets: Vec<Secret> =
-        serde_json::from_str(&json_string).map_err(|e| format!("Backup inválido: {}", e))?;
-
-    let lock = state.db.lock().map_err(|_| "Falha no Mutex".to_string())?;
-    let conn = lock.as_ref().ok_or("Cofre fechado! Faça login primeiro.")?;
-
-    let mut existing_entries = Vec::new();
-
-    let mut stmt = conn
-        .prepare("SELECT title, username, password_blob FROM secrets")
-        .map_err(|e| e.to_string())?;
-
-    let rows = stmt
-        .query_map([], |row| {
-            let pass_blob: Vec<u8> = row.get(2)?;
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                String::from_utf8(pass_blob).unwrap_or_default(),
-            ))
-        })
-        .map_err(|e| e.to_string())?;
-
-    for r in rows {
-        existing_entries.push(r.map_err(|e| e.to_string())?);
-    }
+) -> Result<String> {
+    let encrypted_bytes = state.with_vault(|vault| vault.export_backup(&password))?;
+
+    let mut file = File::create(file_path)?;
+    file.write_all(&encrypted_bytes)?;
 
-    let mut inserted_count = 0;
-    let mut skipped_count = 0;
+    Ok(t(Msg::BackupExported).to_string())
+}
 
-    for secret in imported_secrets {
-        let already_exists = existing_entries
-            .iter()
-            .any(|(t, u, p)| *t == secret.title && *u == secret.username && *p == secret.password);
+#[tauri::command]
+pub fn import_vault(
+    file_path: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    let encrypted_bytes = fs::read(&file_path)?;
+
+    let summary = state.with_vault(|vault| vault.import_backup(&encrypted_bytes, &password))?;
+
+    Ok(tf(
+        Msg::ImportSummary,
+        &[
+            &summary.secrets_inserted,
+            &summary.secrets_skipped,
+            &summary.projects_inserted,
+            &summary.attachments_inserted,
+        ],
+    ))
+}
 
-        if already_exists {
-            skipped_count += 1;
-            continue;
-        }
+/// Importa a exportação de outro gerenciador de senhas. O resumo inclui o que
+/// não pôde ser importado.
+#[tauri::command]
+pub fn import_external(
+    format: ImportFormat,
+    file_path: String,
+    mapping: Option<CsvMapping>,
+    state: State<'_, AppState>,
+) -> Result<ExternalImportSummary> {
+    let data = fs::read(&file_path)?;
+    let parsed = importers::parse(format, &data, mapping.as_ref())?;
 
-        conn.execute(
-            "INSERT INTO secrets (title, username, password_blob) VALUES (?1, ?2, ?3)",
-            (secret.title, secret.username, secret.password.as_bytes()),
-        )
-        .map_err(|e| e.to_string())?;
+    state.with_vault(|vault| vault.import_external(parsed))
+}
 
-        inserted_count += 1;
-    }
+/// Exporta o cofre em texto puro para outro gerenciador. Pede a senha mestre
+/// de novo e grava o arquivo só com permissão do dono.
+#[tauri::command]
+pub fn export_plaintext(
+    format: ExportFormat,
+    file_path: String,
+    master_password: String,
+    state: State<'_, AppState>,
+) -> Result<ExportReport> {
+    let (content, report) =
+        state.with_vault(|vault| vault.export_plaintext(format, &master_password))?;
 
-    Ok(format!(
-        "Importação concluída: {} novos, {} ignorados",
-        inserted_count, skipped_count
-    ))
+    write_private(Path::new(&file_path), &content)?;
+
+    Ok(report)
 }
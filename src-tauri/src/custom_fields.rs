@@ -0,0 +1,442 @@
+//! Campos personalizados dos segredos.
+//!
+//! Qualquer segredo pode ter campos extras com nome, tipo e ordem. Campos do
+//! tipo `hidden` são cifrados em `secret_fields.value_blob` com a mesma
+//! proteção da senha; os demais ficam em `secret_fields.value`.
+//!
+//! Nas listagens os campos ocultos vêm sem valor (`value: None`). Ao gravar,
+//! um campo oculto sem valor mantém o valor atual do campo de mesmo nome, então
+//! a interface pode reenviar os campos sem revelar os ocultos.
+
+use crate::database::{decrypt_column_string, encrypt_column, SECRET_FIELD_VALUE_COLUMN};
+use crate::error::{AppError, Result};
+use crate::secret_history::{record_version, snapshot};
+use crate::security::{MasterKey, FIELD_ENCRYPTION_VERSION};
+use crate::vault::Vault;
+use rusqlite::{types::Type, Connection, Error, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    #[default]
+    Text,
+    Hidden,
+    Url,
+    Email,
+    Date,
+    Number,
+}
+
+impl CustomFieldType {
+    pub const ALL: [CustomFieldType; 6] = [
+        CustomFieldType::Text,
+        CustomFieldType::Hidden,
+        CustomFieldType::Url,
+        CustomFieldType::Email,
+        CustomFieldType::Date,
+        CustomFieldType::Number,
+    ];
+
+    /// Valor gravado na coluna `secret_fields.field_type`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Hidden => "hidden",
+            CustomFieldType::Url => "url",
+            CustomFieldType::Email => "email",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Number => "number",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == value)
+            .ok_or_else(|| AppError::InvalidInput(format!("field.type value={:?}", value)))
+    }
+
+    /// Confere o formato do valor. Valores vazios são aceitos em todos os tipos.
+    fn accepts(self, value: &str) -> bool {
+        let value = value.trim();
+        if value.is_empty() {
+            return true;
+        }
+
+        match self {
+            CustomFieldType::Text | CustomFieldType::Hidden => true,
+            CustomFieldType::Url => value.starts_with("https://") || value.starts_with("http://"),
+            CustomFieldType::Email => value.split_once('@').is_some_and(|(user, domain)| {
+                !user.is_empty() && domain.contains('.') && !value.contains(char::is_whitespace)
+            }),
+            CustomFieldType::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+            CustomFieldType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    pub name: String,
+    /// `None` em campos ocultos não revelados
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default, rename = "type")]
+    pub field_type: CustomFieldType,
+    /// Ordem de exibição; ao gravar, os campos são renumerados a partir de 0
+    #[serde(default)]
+    pub position: i64,
+}
+
+impl CustomField {
+    pub fn new(name: &str, value: &str, field_type: CustomFieldType) -> Self {
+        Self {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+            field_type,
+            position: 0,
+        }
+    }
+
+    /// Campo vindo de outro gerenciador: se o valor não estiver no formato do
+    /// tipo (ex: URL sem esquema), o campo vira texto em vez de ser recusado.
+    pub fn imported(name: &str, value: &str, field_type: CustomFieldType) -> Self {
+        let field_type = if field_type.accepts(value) {
+            field_type
+        } else {
+            CustomFieldType::Text
+        };
+
+        Self::new(name, value, field_type)
+    }
+}
+
+/// Nomes preenchidos e únicos (sem diferenciar maiúsculas) e valores no
+/// formato do tipo.
+pub fn validate_fields(fields: &[CustomField]) -> Result<()> {
+    for (idx, field) in fields.iter().enumerate() {
+        let name = field.name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("field.name empty".to_string()));
+        }
+
+        if fields[..idx]
+            .iter()
+            .any(|other| other.name.trim().eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::InvalidInput(format!(
+                "field.name value={:?} duplicate",
+                name
+            )));
+        }
+
+        if let Some(value) = &field.value {
+            if !field.field_type.accepts(value) {
+                return Err(AppError::InvalidInput(format!(
+                    "field.value name={:?} type={}",
+                    name,
+                    field.field_type.as_str()
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Grava os campos de um segredo, renumerando as posições na ordem recebida
+/// (estável por `position`). Campos sem valor são gravados vazios.
+pub(crate) fn insert_fields(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    fields: &[CustomField],
+) -> rusqlite::Result<()> {
+    let mut ordered: Vec<&CustomField> = fields.iter().collect();
+    ordered.sort_by_key(|f| f.position);
+
+    for (position, field) in ordered.into_iter().enumerate() {
+        let value = field.value.as_deref().unwrap_or_default();
+        let hidden = field.field_type == CustomFieldType::Hidden;
+
+        conn.execute(
+            "INSERT INTO secret_fields (secret_id, name, field_type, value, position, encryption_version) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (
+                secret_id,
+                field.name.trim(),
+                field.field_type.as_str(),
+                (!hidden).then_some(value),
+                position as i64,
+                FIELD_ENCRYPTION_VERSION,
+            ),
+        )?;
+
+        // O valor oculto é cifrado com o id do campo, conhecido só após o INSERT
+        if hidden {
+            let id = conn.last_insert_rowid();
+            let blob = encrypt_column(value.as_bytes(), field_key, SECRET_FIELD_VALUE_COLUMN, id)?;
+
+            conn.execute(
+                "UPDATE secret_fields SET value_blob = ?1 WHERE id = ?2",
+                (blob, id),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Substitui os campos de um segredo. Campos ocultos sem valor mantêm o
+/// valor atual do campo de mesmo nome.
+pub(crate) fn replace_fields(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    fields: &[CustomField],
+) -> rusqlite::Result<()> {
+    let mut current = load_fields(conn, field_key, Some(secret_id), true)?
+        .remove(&secret_id)
+        .unwrap_or_default();
+
+    let fields: Vec<CustomField> = fields
+        .iter()
+        .map(|field| {
+            let mut field = field.clone();
+            if field.value.is_none() {
+                field.value = current
+                    .iter_mut()
+                    .find(|c| {
+                        c.field_type == CustomFieldType::Hidden
+                            && c.name.eq_ignore_ascii_case(field.name.trim())
+                    })
+                    .and_then(|c| c.value.take());
+            }
+            field
+        })
+        .collect();
+
+    conn.execute(
+        "DELETE FROM secret_fields WHERE secret_id = ?1",
+        [secret_id],
+    )?;
+    insert_fields(conn, field_key, secret_id, &fields)
+}
+
+/// Campos de todos os segredos (ou de um só), por id do segredo. Com
+/// `reveal_hidden` falso, os campos ocultos não são decifrados.
+pub(crate) fn load_fields(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: Option<i64>,
+    reveal_hidden: bool,
+) -> rusqlite::Result<HashMap<i64, Vec<CustomField>>> {
+    let mut stmt = conn.prepare(
+        "SELECT secret_id, name, field_type, value, value_blob, position, id FROM secret_fields WHERE ?1 IS NULL OR secret_id = ?1 ORDER BY secret_id, position",
+    )?;
+
+    let rows = stmt.query_map([secret_id], |row| {
+        let field_type: String = row.get(2)?;
+        let field_type = CustomFieldType::parse(&field_type)
+            .map_err(|e| Error::FromSqlConversionFailure(2, Type::Text, e.to_string().into()))?;
+
+        let value = match field_type {
+            CustomFieldType::Hidden if reveal_hidden => Some(decrypt_column_string(
+                row,
+                4,
+                field_key,
+                SECRET_FIELD_VALUE_COLUMN,
+                row.get(6)?,
+            )?),
+            CustomFieldType::Hidden => None,
+            _ => Some(row.get::<_, Option<String>>(3)?.unwrap_or_default()),
+        };
+
+        Ok((
+            row.get::<_, i64>(0)?,
+            CustomField {
+                name: row.get(1)?,
+                value,
+                field_type,
+                position: row.get(5)?,
+            },
+        ))
+    })?;
+
+    let mut fields: HashMap<i64, Vec<CustomField>> = HashMap::new();
+    for row in rows {
+        let (secret_id, field) = row?;
+        fields.entry(secret_id).or_default().push(field);
+    }
+
+    Ok(fields)
+}
+
+impl Vault {
+    /// Campos do segredo, sem o valor dos campos ocultos. Para lê-los use
+    /// [`Vault::reveal_custom_field`].
+    pub fn secret_fields(&self, secret_id: i64) -> Result<Vec<CustomField>> {
+        self.secret_summary(secret_id)?;
+
+        let mut fields = load_fields(self.conn(), self.field_key(), Some(secret_id), false)?;
+
+        Ok(fields.remove(&secret_id).unwrap_or_default())
+    }
+
+    /// Substitui todos os campos do segredo. Campos ocultos sem valor mantêm
+    /// o valor atual do campo de mesmo nome.
+    pub fn set_secret_fields(&mut self, secret_id: i64, fields: &[CustomField]) -> Result<()> {
+        validate_fields(fields)?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+
+        let before = snapshot(&tx, field_key, secret_id)?;
+        replace_fields(&tx, field_key, secret_id, fields)?;
+        record_version(&tx, field_key, secret_id, &before)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Decifra um campo oculto. Como na senha, a leitura é registrada no
+    /// `audit_log` (campo `field:<nome>`) antes de o valor ser devolvido.
+    pub fn reveal_custom_field(&mut self, secret_id: i64, name: &str) -> Result<String> {
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+
+        let field = tx
+            .query_row(
+                "SELECT name, field_type, value, value_blob, id FROM secret_fields WHERE secret_id = ?1 AND name = ?2 COLLATE NOCASE",
+                (secret_id, name.trim()),
+                |row| {
+                    let value = match row.get::<_, String>(1)?.as_str() {
+                        "hidden" => decrypt_column_string(
+                            row,
+                            3,
+                            field_key,
+                            SECRET_FIELD_VALUE_COLUMN,
+                            row.get(4)?,
+                        )?,
+                        _ => row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    };
+                    Ok((row.get::<_, String>(0)?, value))
+                },
+            )
+            .optional()?;
+
+        let Some((name, value)) = field else {
+            return Err(AppError::NotFound(format!(
+                "field secret={} name={:?}",
+                secret_id, name
+            )));
+        };
+
+        tx.execute(
+            "INSERT INTO audit_log (action, secret_id, field, created_at) VALUES ('reveal', ?1, ?2, ?3)",
+            (
+                secret_id,
+                format!("field:{}", name),
+                chrono::Utc::now().to_rfc3339(),
+            ),
+        )?;
+
+        tx.commit()?;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+
+    #[test]
+    fn test_validate_fields() {
+        let field = CustomField::new;
+
+        assert!(validate_fields(&[
+            field("Site", "https://example.com", CustomFieldType::Url),
+            field("Contato", "eu@example.com", CustomFieldType::Email),
+            field("Validade", "2030-01-31", CustomFieldType::Date),
+            field("Limite", "1500.50", CustomFieldType::Number),
+            field("PIN", "", CustomFieldType::Number),
+        ])
+        .is_ok());
+
+        assert!(validate_fields(&[field("Site", "example.com", CustomFieldType::Url)]).is_err());
+        assert!(validate_fields(&[field("Contato", "eu@", CustomFieldType::Email)]).is_err());
+        assert!(
+            validate_fields(&[field("Validade", "31/01/2030", CustomFieldType::Date)]).is_err()
+        );
+        assert!(validate_fields(&[field("Limite", "mil", CustomFieldType::Number)]).is_err());
+        assert!(validate_fields(&[field(" ", "x", CustomFieldType::Text)]).is_err());
+        assert!(validate_fields(&[
+            field("PIN", "1", CustomFieldType::Text),
+            field("pin", "2", CustomFieldType::Hidden),
+        ])
+        .is_err());
+
+        assert_eq!(
+            CustomField::imported("URL", "ssh://srv", CustomFieldType::Url).field_type,
+            CustomFieldType::Text
+        );
+    }
+
+    #[test]
+    fn test_hidden_fields_are_masked_and_kept() {
+        let dir = std::env::temp_dir().join("test_custom_fields");
+        let _ = std::fs::remove_dir_all(&dir);
+        let entry = VaultEntry {
+            id: "fields".to_string(),
+            name: "fields".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: String::new(),
+            external: false,
+        };
+
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+        let secret = vault.create_secret("Banco", "eu", "123").unwrap();
+
+        vault
+            .set_secret_fields(
+                secret.id,
+                &[
+                    CustomField::new("PIN", "4321", CustomFieldType::Hidden),
+                    CustomField::new("Agência", "0001", CustomFieldType::Text),
+                ],
+            )
+            .unwrap();
+
+        let fields = vault.secret_fields(secret.id).unwrap();
+        assert_eq!(fields[0].value, None);
+        assert_eq!(fields[1].value.as_deref(), Some("0001"));
+
+        // Reenvio sem o valor oculto, trocando a ordem
+        let mut resent = fields.clone();
+        resent[0].position = 1;
+        resent[1].position = 0;
+        vault.set_secret_fields(secret.id, &resent).unwrap();
+
+        let fields = vault.secret_fields(secret.id).unwrap();
+        assert_eq!(fields[0].name, "Agência");
+        assert_eq!(vault.reveal_custom_field(secret.id, "pin").unwrap(), "4321");
+
+        let stored: Option<String> = vault
+            .conn()
+            .query_row(
+                "SELECT value FROM secret_fields WHERE name = 'PIN'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,10 +1,34 @@
-use crate::security::MasterKey;
+use crate::error::{AppError, Result};
+use crate::i18n::{t, tf, Msg};
+use crate::security::{
+    decrypt_field, derive_field_key, encrypt_field, MasterKey, FIELD_ENCRYPTION_VERSION,
+};
 use include_dir::{include_dir, Dir};
-use rusqlite::{Connection, Result};
+use rusqlite::types::{Type, Value};
+use rusqlite::{Connection, DatabaseName, Error, ErrorCode, OptionalExtension, Row};
 use std::path::Path;
 
 static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/migrations");
 
+pub const SECRET_PASSWORD_COLUMN: &str = "secrets.password_blob";
+pub const SECRET_PAYLOAD_COLUMN: &str = "secrets.payload_blob";
+pub const ATTACHMENT_CONTENT_COLUMN: &str = "attachments.content";
+pub const SECRET_FIELD_VALUE_COLUMN: &str = "secret_fields.value_blob";
+pub const SECRET_VERSION_COLUMN: &str = "secret_versions.snapshot_blob";
+pub const SECRET_TOTP_COLUMN: &str = "secrets.totp_blob";
+
+/// Colunas cifradas pela aplicação, junto com a tabela de cada uma. O nome
+/// no formato `tabela.coluna` entra na AAD. Todas as tabelas listadas têm as
+/// colunas `id` (que também entra na AAD) e `encryption_version`.
+const PROTECTED_COLUMNS: &[(&str, &str)] = &[
+    ("secrets", SECRET_PASSWORD_COLUMN),
+    ("secrets", SECRET_PAYLOAD_COLUMN),
+    ("attachments", ATTACHMENT_CONTENT_COLUMN),
+    ("secret_fields", SECRET_FIELD_VALUE_COLUMN),
+    ("secret_versions", SECRET_VERSION_COLUMN),
+    ("secrets", SECRET_TOTP_COLUMN),
+];
+
 struct Migration<'a> {
     version: i32,
     sql: &'a str,
@@ -16,10 +40,19 @@ pub fn initialize_database(path: &Path, key: &MasterKey) -> Result<Connection> {
     let key_hex = hex::encode(key.key);
     conn.pragma_update(None, "key", &format!("x'{}'", key_hex))?;
 
+    // Com a chave errada o SQLCipher só falha na primeira leitura
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| match e.sqlite_error_code() {
+            Some(ErrorCode::NotADatabase) => AppError::WrongPassword,
+            _ => e.into(),
+        })?;
+
     conn.pragma_update(None, "foreign_keys", "ON")?;
 
     run_migrations(&mut conn)?;
 
+    encrypt_legacy_rows(&mut conn, &derive_field_key(key)?)?;
+
     #[cfg(debug_assertions)]
     {
         // run_seed(&mut conn).map_err(|e| e.to_string())?;
@@ -28,6 +61,188 @@ pub fn initialize_database(path: &Path, key: &MasterKey) -> Result<Connection> {
     Ok(conn)
 }
 
+/// Copia todo o conteúdo de `conn` para um novo arquivo cifrado com `new_key`.
+/// O banco original não é alterado.
+pub fn export_rekeyed_copy(conn: &Connection, dest: &Path, new_key: &MasterKey) -> Result<()> {
+    let key_hex = hex::encode(new_key.key);
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+        (dest.to_string_lossy(), format!("x'{}'", key_hex)),
+    )?;
+
+    let result = (|| {
+        conn.query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))?;
+
+        // sqlcipher_export não copia o user_version, usado pelas migrations
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        conn.pragma_update(Some(DatabaseName::Attached("rekeyed")), "user_version", version)
+    })();
+
+    conn.execute("DETACH DATABASE rekeyed", [])?;
+
+    Ok(result?)
+}
+
+/// Lê uma coluna protegida dentro de um `query_map` e devolve o valor
+/// decifrado. `row_id` é o id da linha de `column` de onde o valor veio.
+pub fn decrypt_column(
+    row: &Row,
+    idx: usize,
+    field_key: &MasterKey,
+    column: &str,
+    row_id: i64,
+) -> rusqlite::Result<Option<Vec<u8>>> {
+    let blob: Option<Vec<u8>> = row.get(idx)?;
+
+    blob.map(|data| {
+        decrypt_field(&data, field_key, column, row_id)
+            .map_err(|e| Error::FromSqlConversionFailure(idx, Type::Blob, e.into()))
+    })
+    .transpose()
+}
+
+/// Como [`decrypt_column`], para colunas que guardam texto (`NULL` vira "").
+pub fn decrypt_column_string(
+    row: &Row,
+    idx: usize,
+    field_key: &MasterKey,
+    column: &str,
+    row_id: i64,
+) -> rusqlite::Result<String> {
+    let bytes = decrypt_column(row, idx, field_key, column, row_id)?.unwrap_or_default();
+
+    String::from_utf8(bytes).map_err(|e| Error::FromSqlConversionFailure(idx, Type::Blob, e.into()))
+}
+
+/// Cifra um valor para gravação na coluna protegida da linha `row_id`. Como o
+/// id entra na AAD, inserções gravam a linha primeiro e os valores cifrados
+/// logo depois, com `last_insert_rowid`.
+pub fn encrypt_column(
+    data: &[u8],
+    field_key: &MasterKey,
+    column: &str,
+    row_id: i64,
+) -> rusqlite::Result<Vec<u8>> {
+    encrypt_field(data, field_key, column, row_id)
+        .map_err(|e| Error::ToSqlConversionFailure(e.into()))
+}
+
+/// Lê uma configuração do próprio cofre (tabela `vault_settings`).
+pub(crate) fn vault_setting(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM vault_settings WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+pub(crate) fn set_vault_setting(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO vault_settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        (key, value),
+    )?;
+
+    Ok(())
+}
+
+/// Reescreve, em todas as tabelas protegidas, as linhas com `encryption_version`
+/// igual a `from_version`, aplicando `transform(valor, coluna, id)` em cada
+/// valor não nulo.
+fn rewrite_protected_columns(
+    conn: &mut Connection,
+    from_version: i64,
+    transform: impl Fn(&[u8], &str, i64) -> rusqlite::Result<Vec<u8>>,
+) -> Result<()> {
+    // `encryption_version` vale para a linha inteira, então as colunas
+    // protegidas de uma mesma tabela são reescritas juntas
+    let mut tables: Vec<(&str, Vec<&str>)> = Vec::new();
+    for &(table, column) in PROTECTED_COLUMNS {
+        match tables.iter_mut().find(|(name, _)| *name == table) {
+            Some((_, columns)) => columns.push(column),
+            None => tables.push((table, vec![column])),
+        }
+    }
+
+    let tx = conn.transaction()?;
+
+    for (table, columns) in tables {
+        let names: Vec<&str> = columns.iter().map(|c| &c[table.len() + 1..]).collect();
+
+        let rows = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT id, {} FROM {} WHERE encryption_version = ?1",
+                names.join(", "),
+                table
+            ))?;
+            let rows = stmt
+                .query_map([from_version], |row| {
+                    let values = (1..=names.len())
+                        .map(|idx| row.get::<_, Option<Vec<u8>>>(idx))
+                        .collect::<rusqlite::Result<Vec<_>>>()?;
+                    Ok((row.get::<_, i64>(0)?, values))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            rows
+        };
+
+        let assignments: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| format!("{} = ?{}", name, idx + 1))
+            .collect();
+        let sql = format!(
+            "UPDATE {} SET {}, encryption_version = ?{} WHERE id = ?{}",
+            table,
+            assignments.join(", "),
+            names.len() + 1,
+            names.len() + 2
+        );
+
+        for (id, values) in rows {
+            let mut params = Vec::with_capacity(values.len() + 2);
+            for (column, value) in columns.iter().zip(values) {
+                params.push(match value {
+                    Some(data) => Value::Blob(transform(&data, column, id)?),
+                    None => Value::Null,
+                });
+            }
+            params.push(Value::Integer(FIELD_ENCRYPTION_VERSION));
+            params.push(Value::Integer(id));
+
+            tx.execute(&sql, rusqlite::params_from_iter(params))?;
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Migração das linhas gravadas antes da cifragem de colunas: cifra os valores
+/// em texto puro com a chave de campos.
+pub(crate) fn encrypt_legacy_rows(conn: &mut Connection, field_key: &MasterKey) -> Result<()> {
+    rewrite_protected_columns(conn, 0, |data, column, id| {
+        encrypt_column(data, field_key, column, id)
+    })
+}
+
+/// Troca a chave de campos de todas as colunas protegidas. Usado na troca da
+/// senha mestre, já que a chave de campos é derivada da chave mestra.
+pub fn reencrypt_protected_columns(
+    conn: &mut Connection,
+    old_field_key: &MasterKey,
+    new_field_key: &MasterKey,
+) -> Result<()> {
+    rewrite_protected_columns(conn, FIELD_ENCRYPTION_VERSION, |data, column, id| {
+        let plaintext = decrypt_field(data, old_field_key, column, id)
+            .map_err(|e| Error::FromSqlConversionFailure(0, Type::Blob, e.into()))?;
+
+        encrypt_column(&plaintext, new_field_key, column, id)
+    })
+}
+
 fn run_migrations(conn: &mut Connection) -> Result<()> {
     let mut migrations: Vec<Migration> = MIGRATIONS_DIR
         .files()
@@ -49,14 +264,14 @@ fn run_migrations(conn: &mut Connection) -> Result<()> {
 
     migrations.sort_by_key(|m| m.version);
 
-    let mut current_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
-    println!("Versão atual do banco: {}", current_version);
+    let initial_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let mut current_version = initial_version;
 
     let tx = conn.transaction()?;
 
     for migration in migrations {
         if current_version < migration.version {
-            println!("Aplicando migration versão: {}", migration.version);
+            eprintln!("{}", tf(Msg::ApplyingMigration, &[&migration.version]));
 
             tx.execute_batch(migration.sql)?;
 
@@ -67,10 +282,9 @@ fn run_migrations(conn: &mut Connection) -> Result<()> {
 
     tx.commit()?;
 
-    println!(
-        "Banco de dados atualizado para a versão: {}",
-        current_version
-    );
+    if current_version != initial_version {
+        eprintln!("{}", tf(Msg::DatabaseUpdated, &[&current_version]));
+    }
 
     Ok(())
 }
@@ -78,11 +292,11 @@ fn run_migrations(conn: &mut Connection) -> Result<()> {
 fn run_seed(conn: &mut Connection) -> Result<()> {
     let sql = include_str!("../migrations/seed.sql");
 
-    println!("Executando seed");
+    eprintln!("{}", t(Msg::RunningSeed));
 
     conn.execute_batch(sql)?;
 
-    println!("Seed executado");
+    eprintln!("{}", t(Msg::SeedFinished));
 
     Ok(())
 }
@@ -93,6 +307,52 @@ mod tests {
     use crate::security::{derive_key_from_password, generate_salt};
     use std::fs;
 
+    #[test]
+    fn test_legacy_rows_are_encrypted_on_open() {
+        let db_path = std::env::temp_dir().join("test_field_encryption.db");
+
+        if db_path.exists() {
+            fs::remove_file(&db_path).unwrap();
+        }
+
+        let key = derive_key_from_password("senha", &generate_salt()).unwrap();
+
+        {
+            let conn = initialize_database(&db_path, &key).unwrap();
+            // Simula linhas gravadas antes da migration 004
+            conn.execute_batch(
+                "INSERT INTO secrets (title, username, password_blob) VALUES ('GitHub', 'eu', CAST('ghp_123' AS BLOB));
+                 INSERT INTO attachments (secret_id, filename, mime_type, file_size, content) VALUES (1, 'a.txt', 'text/plain', 2, x'6f69');",
+            )
+            .unwrap();
+        }
+
+        let conn = initialize_database(&db_path, &key).unwrap();
+        let field_key = derive_field_key(&key).unwrap();
+
+        let (raw, password): (Vec<u8>, String) = conn
+            .query_row(
+                "SELECT password_blob, password_blob FROM secrets WHERE encryption_version = ?1",
+                [FIELD_ENCRYPTION_VERSION],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        decrypt_column_string(row, 1, &field_key, SECRET_PASSWORD_COLUMN, 1)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_ne!(raw, b"ghp_123");
+        assert_eq!(password, "ghp_123");
+
+        let content = conn
+            .query_row("SELECT content FROM attachments", [], |row| {
+                decrypt_column(row, 0, &field_key, ATTACHMENT_CONTENT_COLUMN, 1)
+            })
+            .unwrap();
+        assert_eq!(content, Some(b"oi".to_vec()));
+    }
+
     #[test]
     fn test_encypted_db_flow() {
         let db_path = std::env::temp_dir().join("test.db");
@@ -114,8 +374,63 @@ mod tests {
 
         let result = initialize_database(&db_path, &wrong_key);
         assert!(
-            result.is_err(),
+            matches!(result, Err(AppError::WrongPassword)),
             "Deveria falhar ao abrir com a chave errada"
         );
     }
+
+    #[test]
+    fn test_export_rekeyed_copy() {
+        let db_path = std::env::temp_dir().join("test_rekey.db");
+        let new_db_path = std::env::temp_dir().join("test_rekey_new.db");
+
+        for path in [&db_path, &new_db_path] {
+            if path.exists() {
+                fs::remove_file(path).unwrap();
+            }
+        }
+
+        let old_key = derive_key_from_password("senha_antiga", &generate_salt()).unwrap();
+        let new_key = derive_key_from_password("senha_nova", &generate_salt()).unwrap();
+
+        let old_field_key = derive_field_key(&old_key).unwrap();
+        let new_field_key = derive_field_key(&new_key).unwrap();
+
+        {
+            let conn = initialize_database(&db_path, &old_key).unwrap();
+            let password_blob =
+                encrypt_column(b"ghp_123", &old_field_key, SECRET_PASSWORD_COLUMN, 1).unwrap();
+            conn.execute(
+                "INSERT INTO secrets (id, title, username, password_blob, encryption_version) VALUES (1, 'GitHub', 'eu', ?1, ?2)",
+                (password_blob, FIELD_ENCRYPTION_VERSION),
+            )
+            .unwrap();
+
+            export_rekeyed_copy(&conn, &new_db_path, &new_key).unwrap();
+        }
+
+        assert!(
+            initialize_database(&new_db_path, &old_key).is_err(),
+            "A cópia não deveria abrir com a chave antiga"
+        );
+
+        let mut conn = initialize_database(&new_db_path, &new_key).unwrap();
+        reencrypt_protected_columns(&mut conn, &old_field_key, &new_field_key).unwrap();
+
+        let (title, password): (String, String) = conn
+            .query_row("SELECT title, password_blob FROM secrets", [], |row| {
+                Ok((
+                    row.get(0)?,
+                    decrypt_column_string(row, 1, &new_field_key, SECRET_PASSWORD_COLUMN, 1)?,
+                ))
+            })
+            .unwrap();
+        assert_eq!(title, "GitHub");
+        assert_eq!(password, "ghp_123");
+
+        assert!(
+            initialize_database(&db_path, &old_key).is_ok(),
+            "O banco original deveria continuar com a chave antiga"
+        );
+    }
 }
@@ -0,0 +1,395 @@
+//! Importação e exportação de arquivos `.env` por projeto.
+//!
+//! Cada linha `CHAVE=valor` corresponde a um segredo do projeto com título
+//! `CHAVE` e senha `valor`. O parser aceita o prefixo `export`, comentários
+//! (`#` no início da linha ou precedido de espaço num valor sem aspas), aspas
+//! simples (literais) e duplas (com `\n`, `\t`, `\"`, `\\` e `\$`), e valores
+//! entre aspas que ocupam várias linhas. Não há expansão de `${VAR}`.
+
+use crate::database::{decrypt_column_string, encrypt_column, SECRET_PASSWORD_COLUMN};
+use crate::error::{AppError, Result};
+use crate::project_env::{default_env_name, validate_env_name};
+use crate::secret_history::{record_version, snapshot};
+use crate::security::FIELD_ENCRYPTION_VERSION;
+use crate::vault::Vault;
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DotenvImportSummary {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+fn parse_error(line: usize, detail: impl Into<String>) -> AppError {
+    AppError::InvalidInput(format!("dotenv line={} {}", line, detail.into()))
+}
+
+/// Lê o conteúdo de um `.env`. Chaves repetidas ficam com o último valor, na
+/// posição da primeira ocorrência.
+pub fn parse(content: &str) -> Result<Vec<(String, String)>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line_no = i + 1;
+        let line = lines[i].trim_start();
+        i += 1;
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with([' ', '\t']))
+            .map(str::trim_start)
+            .unwrap_or(line);
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| parse_error(line_no, "expected=KEY=value"))?;
+
+        let key = key.trim_end();
+        validate_env_name(key).map_err(|_| parse_error(line_no, format!("name={:?}", key)))?;
+
+        let value = match rest.trim_start().chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut raw = rest.trim_start()[1..].to_string();
+
+                // Valores entre aspas podem continuar nas linhas seguintes
+                let end = loop {
+                    if let Some(end) = find_closing_quote(&raw, quote) {
+                        break end;
+                    }
+                    if i >= lines.len() {
+                        return Err(parse_error(line_no, "quote=unclosed"));
+                    }
+                    raw.push('\n');
+                    raw.push_str(lines[i]);
+                    i += 1;
+                };
+
+                let after = raw[end + 1..].trim();
+                if !after.is_empty() && !after.starts_with('#') {
+                    return Err(parse_error(line_no, "quote=trailing_content"));
+                }
+
+                if quote == '"' {
+                    unescape(&raw[..end])
+                } else {
+                    raw[..end].to_string()
+                }
+            }
+            _ => strip_inline_comment(rest).trim().to_string(),
+        };
+
+        match entries.iter_mut().find(|(existing, _)| existing == key) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((key.to_string(), value)),
+        }
+    }
+
+    Ok(entries)
+}
+
+fn find_closing_quote(raw: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+
+    for (idx, c) in raw.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(idx);
+        }
+    }
+
+    None
+}
+
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c @ ('"' | '\\' | '$')) => out.push(c),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+/// Remove um comentário `# ...` precedido de espaço.
+fn strip_inline_comment(value: &str) -> &str {
+    let mut previous_is_space = false;
+
+    for (idx, c) in value.char_indices() {
+        if c == '#' && previous_is_space {
+            return &value[..idx];
+        }
+        previous_is_space = c.is_whitespace();
+    }
+
+    value
+}
+
+/// Gera o conteúdo de um `.env`. Valores com espaços, aspas, quebras de linha
+/// ou outros caracteres especiais são escritos entre aspas duplas.
+pub fn render<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut out = String::new();
+
+    for (key, value) in entries {
+        out.push_str(key);
+        out.push('=');
+        out.push_str(&quote_value(value));
+        out.push('\n');
+    }
+
+    out
+}
+
+fn quote_value(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:@+,=%~".contains(c));
+    if plain {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+impl Vault {
+    /// Importa um `.env` para o projeto numa única transação. Segredos ativos
+    /// do projeto com o mesmo título têm a senha atualizada, e a anterior vai
+    /// para o histórico de versões.
+    pub fn import_dotenv(&mut self, project_id: i64, content: &str) -> Result<DotenvImportSummary> {
+        self.project(project_id)?;
+        let entries = parse(content)?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+        let mut summary = DotenvImportSummary::default();
+
+        for (key, value) in &entries {
+            let existing: Option<(i64, String)> = tx
+                .query_row(
+                    "SELECT id, password_blob FROM secrets WHERE project_id = ?1 AND title = ?2 AND deleted_at IS NULL ORDER BY id LIMIT 1",
+                    (project_id, key),
+                    |row| {
+                        let id: i64 = row.get(0)?;
+
+                        Ok((
+                            id,
+                            decrypt_column_string(row, 1, field_key, SECRET_PASSWORD_COLUMN, id)?,
+                        ))
+                    },
+                )
+                .optional()?;
+
+            match existing {
+                Some((_, current)) if current == *value => summary.unchanged += 1,
+                Some((id, _)) => {
+                    let before = snapshot(&tx, field_key, id)?;
+                    let password_blob =
+                        encrypt_column(value.as_bytes(), field_key, SECRET_PASSWORD_COLUMN, id)?;
+                    tx.execute(
+                        "UPDATE secrets SET password_blob = ?1, encryption_version = ?2 WHERE id = ?3",
+                        (password_blob, FIELD_ENCRYPTION_VERSION, id),
+                    )?;
+                    record_version(&tx, field_key, id, &before)?;
+                    summary.updated += 1;
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO secrets (title, username, project_id, encryption_version) VALUES (?1, '', ?2, ?3)",
+                        (key, project_id, FIELD_ENCRYPTION_VERSION),
+                    )?;
+                    let id = tx.last_insert_rowid();
+                    let password_blob =
+                        encrypt_column(value.as_bytes(), field_key, SECRET_PASSWORD_COLUMN, id)?;
+                    tx.execute(
+                        "UPDATE secrets SET password_blob = ?1 WHERE id = ?2",
+                        (password_blob, id),
+                    )?;
+                    summary.inserted += 1;
+                }
+            }
+
+            // Chaves que o nome derivado do título não reproduz (ex: minúsculas)
+            // ficam mapeadas para que `run` e a exportação usem o mesmo nome
+            if default_env_name(key) != *key {
+                tx.execute(
+                    "INSERT OR IGNORE INTO project_env_vars (project_id, secret_title, env_name) VALUES (?1, ?2, ?2)",
+                    (project_id, key),
+                )?;
+            }
+        }
+
+        tx.commit()?;
+
+        Ok(summary)
+    }
+
+    /// Gera o `.env` com os segredos ativos do projeto, usando os mesmos nomes
+    /// de variável do `secrets-cli run`. Cada valor exportado é registrado no
+    /// `audit_log` como 'export'.
+    pub fn export_dotenv(&mut self, project_id: i64) -> Result<String> {
+        self.project(project_id)?;
+        let env = self.read_environment(project_id, "export")?;
+
+        Ok(render(
+            env.iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let content = r#"
+# Banco
+export DATABASE_URL = postgres://localhost/app
+API_KEY=abc123 # chave de teste
+HASH=abc#def
+EMPTY=
+SINGLE='literal \n $HOME'
+DOUBLE="linha 1\nlinha \"2\" \$HOME"
+MULTI="primeira
+segunda"
+  PEM='-----BEGIN-----
+abc
+-----END-----' # certificado
+lower_key=1
+API_KEY=substituida
+"#;
+
+        let entries = parse(content).unwrap();
+        let get = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .unwrap()
+        };
+
+        assert_eq!(entries.len(), 9);
+        assert_eq!(entries[1].0, "API_KEY");
+        assert_eq!(get("DATABASE_URL"), "postgres://localhost/app");
+        assert_eq!(get("API_KEY"), "substituida");
+        assert_eq!(get("HASH"), "abc#def");
+        assert_eq!(get("EMPTY"), "");
+        assert_eq!(get("SINGLE"), "literal \\n $HOME");
+        assert_eq!(get("DOUBLE"), "linha 1\nlinha \"2\" $HOME");
+        assert_eq!(get("MULTI"), "primeira\nsegunda");
+        assert_eq!(get("PEM"), "-----BEGIN-----\nabc\n-----END-----");
+        assert_eq!(get("lower_key"), "1");
+    }
+
+    #[test]
+    fn test_parse_dotenv_errors() {
+        assert!(parse("SEM_IGUAL").is_err());
+        assert!(parse("1KEY=x").is_err());
+        assert!(parse("KEY=\"aberta").is_err());
+        assert!(parse("KEY='x' sobra").is_err());
+    }
+
+    #[test]
+    fn test_render_roundtrip() {
+        let values = [
+            ("PLAIN", "abc-123_/x.y:z@w"),
+            ("SPACES", "com espaço"),
+            ("QUOTES", "aspas \" e 'simples'"),
+            ("MULTI", "a\nb\tc\\d $HOME #x"),
+            ("EMPTY", ""),
+        ];
+
+        let rendered = render(values);
+        assert!(rendered.starts_with("PLAIN=abc-123_/x.y:z@w\n"));
+
+        let parsed = parse(&rendered).unwrap();
+        let parsed: Vec<(&str, &str)> = parsed
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(parsed, values);
+    }
+
+    #[test]
+    fn test_import_and_export_project() {
+        let dir = std::env::temp_dir().join("test_dotenv_project");
+        let _ = std::fs::remove_dir_all(&dir);
+        let entry = VaultEntry {
+            id: "dotenv".to_string(),
+            name: "dotenv".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: String::new(),
+            external: false,
+        };
+
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+        let project_id = vault.create_project("API", None).unwrap();
+
+        let summary = vault
+            .import_dotenv(project_id, "TOKEN=a\nlower_key=\"b c\"\n")
+            .unwrap();
+        assert_eq!(summary.inserted, 2);
+
+        let summary = vault
+            .import_dotenv(project_id, "TOKEN=novo\nlower_key=\"b c\"\n")
+            .unwrap();
+        assert_eq!(
+            summary,
+            DotenvImportSummary {
+                inserted: 0,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+
+        let exported = vault.export_dotenv(project_id).unwrap();
+        assert_eq!(exported, "TOKEN=novo\nlower_key=\"b c\"\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
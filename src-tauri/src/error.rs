@@ -0,0 +1,207 @@
+use crate::i18n::{t, Msg};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{fmt, io, sync::PoisonError};
+
+/// Erro único da aplicação. Chega ao frontend como
+/// `{ code, message, details }`, onde `code` é estável e pode ser usado em
+/// comparações, `message` é o texto para o usuário e `details` traz a causa
+/// técnica, quando houver.
+///
+/// `details` não passa pelo catálogo de mensagens, então não leva frases:
+/// apenas o alvo do erro seguido de pares `chave=valor` (ex.: `secret id=42`,
+/// `totp.digits value=9 min=6 max=8`), ou o alvo e a mensagem da biblioteca
+/// de origem (ex.: `vault.meta: <erro do serde>`). Texto para o usuário
+/// vai sempre em `message`.
+#[derive(Debug)]
+pub enum AppError {
+    VaultLocked,
+    VaultNotFound,
+    VaultAlreadyExists,
+    WrongPassword,
+    NotFound(String),
+    Conflict(String),
+    InvalidInput(String),
+    InvalidBackup(String),
+    UnsupportedVersion(String),
+    Crypto(String),
+    Io(io::Error),
+    Db(rusqlite::Error),
+    Internal(String),
+}
+
+pub type Result<T, E = AppError> = std::result::Result<T, E>;
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::VaultLocked => "VAULT_LOCKED",
+            AppError::VaultNotFound => "VAULT_NOT_FOUND",
+            AppError::VaultAlreadyExists => "VAULT_ALREADY_EXISTS",
+            AppError::WrongPassword => "WRONG_PASSWORD",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::InvalidBackup(_) => "INVALID_BACKUP",
+            AppError::UnsupportedVersion(_) => "UNSUPPORTED_VERSION",
+            AppError::Crypto(_) => "CRYPTO",
+            AppError::Io(_) => "IO",
+            AppError::Db(_) => "DB",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// Mensagem para o usuário, no idioma atual.
+    pub fn message(&self) -> &'static str {
+        let msg = match self {
+            AppError::VaultLocked => Msg::ErrVaultLocked,
+            AppError::VaultNotFound => Msg::ErrVaultNotFound,
+            AppError::VaultAlreadyExists => Msg::ErrVaultAlreadyExists,
+            AppError::WrongPassword => Msg::ErrWrongPassword,
+            AppError::NotFound(_) => Msg::ErrNotFound,
+            AppError::Conflict(_) => Msg::ErrConflict,
+            AppError::InvalidInput(_) => Msg::ErrInvalidInput,
+            AppError::InvalidBackup(_) => Msg::ErrInvalidBackup,
+            AppError::UnsupportedVersion(_) => Msg::ErrUnsupportedVersion,
+            AppError::Crypto(_) => Msg::ErrCrypto,
+            AppError::Io(_) => Msg::ErrIo,
+            AppError::Db(_) => Msg::ErrDb,
+            AppError::Internal(_) => Msg::ErrInternal,
+        };
+
+        t(msg)
+    }
+
+    pub fn details(&self) -> Option<String> {
+        match self {
+            AppError::VaultLocked
+            | AppError::VaultNotFound
+            | AppError::VaultAlreadyExists
+            | AppError::WrongPassword => None,
+            AppError::NotFound(details)
+            | AppError::Conflict(details)
+            | AppError::InvalidInput(details)
+            | AppError::InvalidBackup(details)
+            | AppError::UnsupportedVersion(details)
+            | AppError::Crypto(details)
+            | AppError::Internal(details) => Some(details.clone()),
+            AppError::Io(e) => Some(e.to_string()),
+            AppError::Db(e) => Some(e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.details() {
+            Some(details) => write!(f, "{}: {}", self.message(), details),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Io(e) => Some(e),
+            AppError::Db(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        use rusqlite::Error::{
+            FromSqlConversionFailure, QueryReturnedNoRows, SqliteFailure, ToSqlConversionFailure,
+        };
+
+        match e {
+            QueryReturnedNoRows => AppError::NotFound(e.to_string()),
+            // Nome repetido e afins: o registro já existe
+            SqliteFailure(ref err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation
+                    && err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE =>
+            {
+                AppError::Conflict(e.to_string())
+            }
+            // Erros da aplicação levantados dentro de query_map (ex: falha ao
+            // decifrar uma coluna) voltam a ser o AppError original
+            FromSqlConversionFailure(idx, ty, inner) => match inner.downcast::<AppError>() {
+                Ok(app_error) => *app_error,
+                Err(inner) => AppError::Db(FromSqlConversionFailure(idx, ty, inner)),
+            },
+            ToSqlConversionFailure(inner) => match inner.downcast::<AppError>() {
+                Ok(app_error) => *app_error,
+                Err(inner) => AppError::Db(ToSqlConversionFailure(inner)),
+            },
+            e => AppError::Db(e),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
+
+impl<T> From<PoisonError<T>> for AppError {
+    fn from(_: PoisonError<T>) -> Self {
+        AppError::Internal("mutex state=poisoned".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_serializes_with_code_message_and_details() {
+        let json = serde_json::to_value(AppError::NotFound("secret id=42".to_string())).unwrap();
+
+        assert_eq!(json["code"], "NOT_FOUND");
+        assert_eq!(
+            json["message"],
+            Msg::ErrNotFound.text(crate::i18n::current_locale())
+        );
+        assert_eq!(json["details"], "secret id=42");
+
+        let json = serde_json::to_value(AppError::VaultLocked).unwrap();
+        assert_eq!(json["code"], "VAULT_LOCKED");
+        assert!(json["details"].is_null());
+    }
+
+    #[test]
+    fn test_db_errors_are_classified() {
+        assert_eq!(
+            AppError::from(rusqlite::Error::QueryReturnedNoRows).code(),
+            "NOT_FOUND"
+        );
+
+        let wrapped = rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Blob,
+            Box::new(AppError::Crypto("secrets.password_blob".to_string())),
+        );
+        assert_eq!(AppError::from(wrapped).code(), "CRYPTO");
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (name TEXT UNIQUE NOT NULL)")
+            .unwrap();
+        conn.execute("INSERT INTO t VALUES ('a')", []).unwrap();
+        let duplicate = conn.execute("INSERT INTO t VALUES ('a')", []).unwrap_err();
+        assert_eq!(AppError::from(duplicate).code(), "CONFLICT");
+        let missing = conn.execute("INSERT INTO t VALUES (NULL)", []).unwrap_err();
+        assert_eq!(AppError::from(missing).code(), "DB");
+    }
+}
@@ -0,0 +1,757 @@
+//! Exportação em texto puro para formatos de outros gerenciadores.
+//!
+//! Diferente do backup, o arquivo gerado não é cifrado: a exportação exige a
+//! senha mestre novamente e o arquivo é gravado só com permissão do dono.
+//! Apenas segredos ativos são exportados. O relatório lista o que cada formato
+//! não consegue representar, com os identificadores `attachments`, `trash`,
+//! `env-vars`, `totp` (sementes de segundo fator), `project-descriptions`,
+//! `projects` (projetos sem segredos), `secret-kinds` (segredos exportados só
+//! com o valor principal do tipo) e `custom-fields` (segredos cujos campos
+//! personalizados ficaram de fora).
+
+use crate::backup::{collect_backup, BackupPayload};
+use crate::custom_fields::CustomFieldType;
+use crate::error::{AppError, Result};
+use crate::models::{Project, Secret};
+use crate::secret_kinds::{SecretKind, SecretPayload};
+use crate::vault::Vault;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[serde(rename = "bitwarden-json")]
+    BitwardenJson,
+    #[serde(rename = "keepass-xml")]
+    KeepassXml,
+    #[serde(rename = "csv")]
+    Csv,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::BitwardenJson,
+        ExportFormat::KeepassXml,
+        ExportFormat::Csv,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::BitwardenJson => "bitwarden-json",
+            ExportFormat::KeepassXml => "keepass-xml",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|f| f.as_str()).collect();
+                AppError::InvalidInput(format!(
+                    "format value={:?} expected={}",
+                    value,
+                    names.join("|")
+                ))
+            })
+    }
+}
+
+/// Dados do cofre que ficaram fora do arquivo exportado.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LostData {
+    pub kind: &'static str,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub format: ExportFormat,
+    pub secrets_exported: usize,
+    pub lost: Vec<LostData>,
+}
+
+/// Gera o arquivo no formato pedido e o relatório do que não coube nele.
+pub fn render(
+    format: ExportFormat,
+    payload: &BackupPayload,
+) -> Result<(Zeroizing<Vec<u8>>, ExportReport)> {
+    let secrets: Vec<&Secret> = payload
+        .secrets
+        .iter()
+        .filter(|s| s.deleted_at.is_none())
+        .collect();
+    let projects: Vec<&Project> = payload
+        .projects
+        .iter()
+        .filter(|p| p.deleted_at.is_none())
+        .collect();
+
+    let totp: HashMap<i64, &str> = payload
+        .totp
+        .iter()
+        .map(|t| (t.secret_id, t.uri.as_str()))
+        .collect();
+
+    let content = match format {
+        ExportFormat::BitwardenJson => render_bitwarden(&secrets, &projects, &totp)?,
+        ExportFormat::KeepassXml => render_keepass(&secrets, &projects, &totp),
+        ExportFormat::Csv => render_csv(&secrets, &projects)?,
+    };
+
+    let exported_ids: Vec<i64> = secrets.iter().map(|s| s.id).collect();
+    let mut lost = vec![
+        LostData {
+            kind: "attachments",
+            count: payload
+                .attachments
+                .iter()
+                .filter(|a| exported_ids.contains(&a.secret_id))
+                .count(),
+        },
+        LostData {
+            kind: "trash",
+            count: payload.secrets.len() - secrets.len(),
+        },
+        LostData {
+            kind: "env-vars",
+            count: payload.env_vars.len(),
+        },
+        // O Bitwarden só guarda TOTP em logins e o CSV não tem coluna para ele
+        LostData {
+            kind: "totp",
+            count: secrets
+                .iter()
+                .filter(|s| totp.contains_key(&s.id))
+                .filter(|s| match format {
+                    ExportFormat::BitwardenJson => matches!(
+                        s.payload,
+                        Some(SecretPayload::Note(_) | SecretPayload::Card(_))
+                    ),
+                    ExportFormat::KeepassXml => false,
+                    ExportFormat::Csv => true,
+                })
+                .count(),
+        },
+    ];
+
+    // O Bitwarden tem notas seguras e cartões; os outros tipos viram logins
+    lost.push(LostData {
+        kind: "secret-kinds",
+        count: secrets
+            .iter()
+            .filter(|s| {
+                !matches!(
+                    (format, s.kind),
+                    (_, SecretKind::Login)
+                        | (
+                            ExportFormat::BitwardenJson,
+                            SecretKind::Note | SecretKind::Card
+                        )
+                )
+            })
+            .count(),
+    });
+
+    // O CSV tem colunas fixas
+    if format == ExportFormat::Csv {
+        lost.push(LostData {
+            kind: "custom-fields",
+            count: secrets.iter().filter(|s| !s.fields.is_empty()).count(),
+        });
+    }
+
+    // Grupos do KeePass guardam notas; os demais formatos perdem a descrição
+    if format != ExportFormat::KeepassXml {
+        lost.push(LostData {
+            kind: "project-descriptions",
+            count: projects
+                .iter()
+                .filter(|p| p.description.as_deref().is_some_and(|d| !d.is_empty()))
+                .count(),
+        });
+    }
+
+    // No CSV um projeto só existe como coluna de algum segredo
+    if format == ExportFormat::Csv {
+        lost.push(LostData {
+            kind: "projects",
+            count: projects
+                .iter()
+                .filter(|p| !secrets.iter().any(|s| s.project_id == Some(p.id)))
+                .count(),
+        });
+    }
+
+    lost.retain(|l| l.count > 0);
+
+    Ok((
+        content,
+        ExportReport {
+            format,
+            secrets_exported: secrets.len(),
+            lost,
+        },
+    ))
+}
+
+/// Senha do login ou valor principal dos outros tipos.
+fn secret_value(secret: &Secret) -> String {
+    match &secret.payload {
+        Some(payload) => payload.primary_value(),
+        None => secret.password.clone(),
+    }
+}
+
+fn project_name<'a>(projects: &[&'a Project], id: Option<i64>) -> Option<&'a str> {
+    id.and_then(|id| projects.iter().find(|p| p.id == id))
+        .map(|p| p.name.as_str())
+}
+
+/// Os formatos externos usam UUIDs; estes são derivados do id local.
+fn uuid(kind: u8, id: i64) -> String {
+    format!("00000000-0000-4000-8{:03x}-{:012x}", kind, id)
+}
+
+fn render_bitwarden(
+    secrets: &[&Secret],
+    projects: &[&Project],
+    totp: &HashMap<i64, &str>,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let folders: Vec<_> = projects
+        .iter()
+        .map(|p| json!({ "id": uuid(1, p.id), "name": p.name }))
+        .collect();
+
+    let items: Vec<_> = secrets
+        .iter()
+        .map(|s| {
+            let folder_id = s
+                .project_id
+                .filter(|id| projects.iter().any(|p| p.id == *id))
+                .map(|id| uuid(1, id));
+
+            let mut item = json!({
+                "id": uuid(2, s.id),
+                "organizationId": null,
+                "folderId": folder_id,
+                "reprompt": 0,
+                "name": s.title,
+                "notes": null,
+                "favorite": false,
+                "collectionIds": null,
+                "creationDate": rfc3339(&s.created_at),
+            });
+
+            // Tipos de campo do Bitwarden: 0 = texto, 1 = oculto
+            if !s.fields.is_empty() {
+                let fields: Vec<_> = s
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        json!({
+                            "name": f.name,
+                            "value": f.value,
+                            "type": u8::from(f.field_type == CustomFieldType::Hidden),
+                            "linkedId": null,
+                        })
+                    })
+                    .collect();
+                item["fields"] = json!(fields);
+            }
+
+            match &s.payload {
+                Some(SecretPayload::Note(note)) => {
+                    item["type"] = json!(2);
+                    item["notes"] = json!(note.content);
+                    item["secureNote"] = json!({ "type": 0 });
+                }
+                Some(SecretPayload::Card(card)) => {
+                    item["type"] = json!(3);
+                    item["card"] = json!({
+                        "cardholderName": card.cardholder,
+                        "brand": null,
+                        "number": card.number,
+                        "expMonth": card.expiry_month.to_string(),
+                        "expYear": card.expiry_year.to_string(),
+                        "code": card.cvv,
+                    });
+                }
+                _ => {
+                    item["type"] = json!(1);
+                    item["login"] = json!({
+                        "uris": [],
+                        "username": s.username,
+                        "password": secret_value(s),
+                        "totp": totp.get(&s.id),
+                    });
+                }
+            }
+
+            item
+        })
+        .collect();
+
+    let export = json!({ "encrypted": false, "folders": folders, "items": items });
+    let json = serde_json::to_vec_pretty(&export).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Zeroizing::new(json))
+}
+
+fn render_keepass(
+    secrets: &[&Secret],
+    projects: &[&Project],
+    totp: &HashMap<i64, &str>,
+) -> Zeroizing<Vec<u8>> {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n<KeePassFile>\n\t<Meta>\n\t\t<Generator>Secrets Manager</Generator>\n\t</Meta>\n\t<Root>\n\t\t<Group>\n",
+    );
+    xml.push_str(&format!(
+        "\t\t\t<UUID>{}</UUID>\n\t\t\t<Name>Secrets Manager</Name>\n",
+        keepass_uuid(0, 0)
+    ));
+
+    let write_entries = |xml: &mut String, project_id: Option<i64>, indent: &str| {
+        for secret in secrets.iter().filter(|s| {
+            // Segredos de projetos na lixeira ficam no grupo raiz
+            let in_group = s
+                .project_id
+                .filter(|id| projects.iter().any(|p| p.id == *id));
+            in_group == project_id
+        }) {
+            xml.push_str(&format!("{indent}<Entry>\n"));
+            xml.push_str(&format!(
+                "{indent}\t<UUID>{}</UUID>\n",
+                keepass_uuid(2, secret.id)
+            ));
+            if let Some(created) = rfc3339(&secret.created_at) {
+                xml.push_str(&format!(
+                    "{indent}\t<Times><CreationTime>{}</CreationTime><LastModificationTime>{}</LastModificationTime></Times>\n",
+                    created, created
+                ));
+            }
+            let password = secret_value(secret);
+            let mut strings = vec![
+                ("Title".to_string(), secret.title.as_str(), false),
+                ("UserName".to_string(), secret.username.as_str(), false),
+                ("Password".to_string(), password.as_str(), true),
+            ];
+            // Chave usada pelo KeePassXC
+            if let Some(uri) = totp.get(&secret.id) {
+                strings.push(("otp".to_string(), uri, true));
+            }
+            for field in &secret.fields {
+                // As chaves de uma entrada são únicas; campos com o nome de
+                // uma chave padrão ganham um sufixo
+                let mut key = field.name.clone();
+                while strings.iter().any(|(k, _, _)| k.eq_ignore_ascii_case(&key)) {
+                    key.push_str(" 2");
+                }
+                strings.push((
+                    key,
+                    field.value.as_deref().unwrap_or_default(),
+                    field.field_type == CustomFieldType::Hidden,
+                ));
+            }
+            for (key, value, protected) in strings {
+                let attr = if protected {
+                    " ProtectInMemory=\"True\""
+                } else {
+                    ""
+                };
+                xml.push_str(&format!(
+                    "{indent}\t<String><Key>{}</Key><Value{}>{}</Value></String>\n",
+                    key,
+                    attr,
+                    escape_xml(value)
+                ));
+            }
+            xml.push_str(&format!("{indent}</Entry>\n"));
+        }
+    };
+
+    write_entries(&mut xml, None, "\t\t\t");
+
+    for project in projects {
+        xml.push_str("\t\t\t<Group>\n");
+        xml.push_str(&format!(
+            "\t\t\t\t<UUID>{}</UUID>\n\t\t\t\t<Name>{}</Name>\n",
+            keepass_uuid(1, project.id),
+            escape_xml(&project.name)
+        ));
+        if let Some(description) = project.description.as_deref().filter(|d| !d.is_empty()) {
+            xml.push_str(&format!(
+                "\t\t\t\t<Notes>{}</Notes>\n",
+                escape_xml(description)
+            ));
+        }
+        write_entries(&mut xml, Some(project.id), "\t\t\t\t");
+        xml.push_str("\t\t\t</Group>\n");
+    }
+
+    xml.push_str("\t\t</Group>\n\t</Root>\n</KeePassFile>\n");
+
+    Zeroizing::new(xml.into_bytes())
+}
+
+fn render_csv(secrets: &[&Secret], projects: &[&Project]) -> Result<Zeroizing<Vec<u8>>> {
+    let invalid = |e: csv::Error| AppError::Internal(e.to_string());
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer
+        .write_record(["title", "username", "password", "project", "created_at"])
+        .map_err(invalid)?;
+    for secret in secrets {
+        let password = secret_value(secret);
+        writer
+            .write_record([
+                secret.title.as_str(),
+                secret.username.as_str(),
+                password.as_str(),
+                project_name(projects, secret.project_id).unwrap_or_default(),
+                secret.created_at.as_str(),
+            ])
+            .map_err(invalid)?;
+    }
+
+    let data = writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Zeroizing::new(data))
+}
+
+/// Datas do banco em RFC 3339 (UTC). Há registros gravados pelo SQLite
+/// (`2024-01-01 10:00:00`) e pelo chrono.
+fn rfc3339(value: &str) -> Option<String> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|d| d.and_utc())
+        })
+        .ok()?;
+
+    Some(parsed.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// UUID do KeePass: 16 bytes em base64.
+fn keepass_uuid(kind: u8, id: i64) -> String {
+    let mut bytes = [0u8; 16];
+    bytes[0] = 0x53; // 'S'
+    bytes[1] = kind;
+    bytes[8..].copy_from_slice(&id.to_be_bytes());
+
+    base64(&bytes)
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+impl Vault {
+    /// Exporta o cofre em texto puro depois de conferir a senha mestre. A
+    /// exportação é registrada no `audit_log` com o formato no campo `field`.
+    pub fn export_plaintext(
+        &mut self,
+        format: ExportFormat,
+        master_password: &str,
+    ) -> Result<(Zeroizing<Vec<u8>>, ExportReport)> {
+        self.verify_password(master_password)?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let payload = collect_backup(conn, field_key)?;
+        let exported = render(format, &payload)?;
+
+        conn.execute(
+            "INSERT INTO audit_log (action, field, created_at) VALUES ('export-plaintext', ?1, ?2)",
+            (format.as_str(), chrono::Utc::now().to_rfc3339()),
+        )?;
+
+        Ok(exported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{BackupAttachment, BackupTotp};
+    use crate::custom_fields::CustomField;
+    use crate::importers::{self, CsvMapping, ImportFormat};
+    use crate::models::ProjectEnvVar;
+    use crate::secret_kinds::{ApiKeyPayload, CardPayload};
+
+    fn payload() -> BackupPayload {
+        let project = |id: i64, name: &str, description: Option<&str>| Project {
+            id,
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            created_at: "2024-01-01 10:00:00".to_string(),
+            deleted_at: None,
+            purge_at: None,
+        };
+        let secret = |id: i64, title: &str, password: &str, project_id: Option<i64>| Secret {
+            id,
+            title: title.to_string(),
+            username: "eu".to_string(),
+            password: password.to_string(),
+            created_at: "2024-01-02T11:00:00+00:00".to_string(),
+            deleted_at: None,
+            project_id,
+            kind: SecretKind::Login,
+            payload: None,
+            fields: Vec::new(),
+            password_changed_at: None,
+            breached: false,
+            breach_count: None,
+            purge_at: None,
+        };
+        let typed = |id: i64, title: &str, payload: SecretPayload| Secret {
+            kind: payload.kind(),
+            payload: Some(payload),
+            username: String::new(),
+            ..secret(id, title, "", None)
+        };
+
+        BackupPayload {
+            version: 2,
+            exported_at: String::new(),
+            projects: vec![
+                project(1, "API & <Infra>", Some("Serviços")),
+                project(2, "Vazio", None),
+            ],
+            secrets: vec![
+                Secret {
+                    fields: vec![
+                        CustomField::new("Site", "https://github.com", CustomFieldType::Url),
+                        CustomField {
+                            position: 1,
+                            ..CustomField::new("Title", "2FA", CustomFieldType::Hidden)
+                        },
+                    ],
+                    ..secret(1, "GitHub", "a\"b,c\nd", Some(1))
+                },
+                secret(2, "Wi-Fi", "wifi", None),
+                Secret {
+                    deleted_at: Some("2024-03-01T00:00:00+00:00".to_string()),
+                    ..secret(3, "Velho", "x", None)
+                },
+                typed(
+                    4,
+                    "Visa",
+                    SecretPayload::Card(CardPayload {
+                        cardholder: "Fulano".to_string(),
+                        number: "4111 1111 1111 1111".to_string(),
+                        expiry_month: 7,
+                        expiry_year: 2030,
+                        cvv: None,
+                    }),
+                ),
+                typed(
+                    5,
+                    "Stripe",
+                    SecretPayload::ApiKey(ApiKeyPayload {
+                        key: "sk_live".to_string(),
+                        endpoint: None,
+                    }),
+                ),
+            ],
+            attachments: vec![BackupAttachment {
+                id: 1,
+                secret_id: 1,
+                filename: "id_rsa".to_string(),
+                mime_type: "text/plain".to_string(),
+                file_size: 1,
+                content: vec![1],
+                created_at: String::new(),
+            }],
+            env_vars: vec![ProjectEnvVar {
+                id: 1,
+                project_id: 1,
+                secret_title: "GitHub".to_string(),
+                env_name: "GH_TOKEN".to_string(),
+            }],
+            totp: [1, 4]
+                .into_iter()
+                .map(|secret_id| BackupTotp {
+                    secret_id,
+                    uri:
+                        "otpauth://totp/?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA1&digits=6&period=30"
+                            .to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn field_summary(fields: &[CustomField]) -> Vec<(&str, &str, CustomFieldType)> {
+        fields
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.value.as_deref().unwrap_or_default(),
+                    f.field_type,
+                )
+            })
+            .collect()
+    }
+
+    fn lost(report: &ExportReport) -> Vec<(&str, usize)> {
+        report.lost.iter().map(|l| (l.kind, l.count)).collect()
+    }
+
+    #[test]
+    fn test_exports_read_back_by_importers() {
+        let payload = payload();
+
+        let (json, report) = render(ExportFormat::BitwardenJson, &payload).unwrap();
+        assert_eq!(report.secrets_exported, 4);
+        assert_eq!(
+            lost(&report),
+            [
+                ("attachments", 1),
+                ("trash", 1),
+                ("env-vars", 1),
+                ("totp", 1),
+                ("secret-kinds", 1),
+                ("project-descriptions", 1)
+            ]
+        );
+        let parsed = importers::parse(ImportFormat::BitwardenJson, &json, None).unwrap();
+        assert_eq!(parsed.items[0].password, "a\"b,c\nd");
+        assert_eq!(parsed.items[0].project.as_deref(), Some("API & <Infra>"));
+        assert_eq!(
+            field_summary(&parsed.items[0].fields),
+            [
+                ("Site", "https://github.com", CustomFieldType::Text),
+                ("Title", "2FA", CustomFieldType::Hidden)
+            ]
+        );
+        assert_eq!(
+            parsed.items[0].totp.as_deref(),
+            Some(payload.totp[0].uri.as_str())
+        );
+        assert_eq!(parsed.items[1].project, None);
+        assert_eq!(parsed.items[2].payload, payload.secrets[3].payload);
+        assert_eq!(parsed.items[3].password, "sk_live");
+
+        let (xml, report) = render(ExportFormat::KeepassXml, &payload).unwrap();
+        assert_eq!(
+            lost(&report),
+            [
+                ("attachments", 1),
+                ("trash", 1),
+                ("env-vars", 1),
+                ("secret-kinds", 2)
+            ]
+        );
+        let parsed = importers::parse(ImportFormat::KeepassXml, &xml, None).unwrap();
+        assert_eq!(parsed.items[0].title, "Wi-Fi");
+        assert_eq!(parsed.items[2].password, "sk_live");
+        assert_eq!(parsed.items[3].password, "a\"b,c\nd");
+        assert_eq!(parsed.items[3].project.as_deref(), Some("API & <Infra>"));
+        assert_eq!(
+            parsed.items[3].totp.as_deref(),
+            Some(payload.totp[0].uri.as_str())
+        );
+        assert_eq!(
+            parsed.items[1].totp.as_deref(),
+            Some(payload.totp[1].uri.as_str())
+        );
+        assert_eq!(
+            field_summary(&parsed.items[3].fields),
+            [
+                ("Site", "https://github.com", CustomFieldType::Text),
+                ("Title 2", "2FA", CustomFieldType::Hidden)
+            ]
+        );
+
+        let (csv, report) = render(ExportFormat::Csv, &payload).unwrap();
+        assert!(report.lost.contains(&LostData {
+            kind: "projects",
+            count: 1
+        }));
+        assert!(report.lost.contains(&LostData {
+            kind: "custom-fields",
+            count: 1
+        }));
+        assert!(report.lost.contains(&LostData {
+            kind: "totp",
+            count: 2
+        }));
+        let mapping = CsvMapping {
+            title: "title".to_string(),
+            username: Some("username".to_string()),
+            password: "password".to_string(),
+            project: Some("project".to_string()),
+            delimiter: None,
+            ignore: vec!["created_at".to_string()],
+        };
+        let parsed = importers::parse(ImportFormat::Csv, &csv, Some(&mapping)).unwrap();
+        assert_eq!(parsed.items.len(), 4);
+        assert_eq!(parsed.items[0].password, "a\"b,c\nd");
+        assert_eq!(parsed.items[2].password, "4111111111111111");
+        assert!(parsed.unmapped.is_empty());
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(&[0xff; 16]).len(), 24);
+        assert_eq!(
+            rfc3339("2024-01-01 10:00:00").as_deref(),
+            Some("2024-01-01T10:00:00Z")
+        );
+        assert_eq!(
+            rfc3339("2024-01-01T10:00:00-03:00").as_deref(),
+            Some("2024-01-01T13:00:00Z")
+        );
+    }
+}
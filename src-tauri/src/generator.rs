@@ -0,0 +1,550 @@
+//! Gerador de senhas e frases-senha.
+//!
+//! Toda a aleatoriedade vem do CSPRNG do sistema (`OsRng`), sempre com
+//! sorteio uniforme (`gen_range`), sem viés de módulo. A entropia informada é
+//! a do processo de geração, não uma estimativa sobre o resultado:
+//!
+//! - Senhas aleatórias com todas as classes obrigatórias são sorteadas por
+//!   rejeição, o que mantém a distribuição uniforme entre as senhas válidas;
+//!   a entropia é o log2 de quantas existem (inclusão-exclusão).
+//! - Senhas pronunciáveis alternam consoantes e vogais; dígito e símbolo,
+//!   quando pedidos, vão no final.
+//! - Frases-senha sorteiam palavras das listas BIP-39 embutidas (2048
+//!   palavras, 11 bits cada), em inglês ou português.
+//!
+//! Cada projeto pode ter uma política própria, usada quando a geração é pedida
+//! para o projeto sem opções explícitas.
+
+use crate::error::{AppError, Result};
+use crate::i18n::{current_locale, Locale};
+use crate::vault::Vault;
+use rand::{rngs::OsRng, Rng};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+// Sem aspas, crase, barra invertida e espaço, que atrapalham em shells e .env
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>?/~";
+const CONSONANTS: &str = "bcdfghjklmnpqrstvwxz";
+const VOWELS: &str = "aeiou";
+
+/// Caracteres fáceis de confundir entre si.
+const AMBIGUOUS: &str = "Il1|O0";
+
+const WORDLIST_EN: &str = include_str!("../wordlists/en.txt");
+const WORDLIST_PT: &str = include_str!("../wordlists/pt.txt");
+
+pub const MIN_PASSWORD_LENGTH: usize = 4;
+pub const MAX_PASSWORD_LENGTH: usize = 256;
+pub const MIN_PASSPHRASE_WORDS: usize = 3;
+pub const MAX_PASSPHRASE_WORDS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordOptions {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    /// Remove caracteres parecidos (`I l 1 | O 0`)
+    pub exclude_ambiguous: bool,
+    /// Garante ao menos um caractere de cada classe ativa
+    pub require_each_class: bool,
+    /// Alterna consoantes e vogais em vez de sortear cada caractere
+    pub pronounceable: bool,
+}
+
+impl Default for PasswordOptions {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+            require_each_class: true,
+            pronounceable: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordlistLanguage {
+    En,
+    Pt,
+}
+
+impl FromStr for WordlistLanguage {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "en" => Ok(WordlistLanguage::En),
+            "pt" | "pt-BR" => Ok(WordlistLanguage::Pt),
+            _ => Err(AppError::InvalidInput(format!(
+                "wordlist value={:?} expected=en|pt",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PassphraseOptions {
+    pub words: usize,
+    pub separator: String,
+    /// Primeira letra de cada palavra em maiúscula
+    pub capitalize: bool,
+    /// Acrescenta um dígito a uma das palavras
+    pub include_number: bool,
+    /// Sem idioma, usa o idioma atual das mensagens.
+    pub language: Option<WordlistLanguage>,
+}
+
+impl Default for PassphraseOptions {
+    fn default() -> Self {
+        Self {
+            words: 6,
+            separator: "-".to_string(),
+            capitalize: false,
+            include_number: false,
+            language: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum GeneratorOptions {
+    Password(PasswordOptions),
+    Passphrase(PassphraseOptions),
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions::Password(PasswordOptions::default())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedSecret {
+    pub value: String,
+    /// Entropia do processo de geração, em bits
+    pub entropy_bits: f64,
+}
+
+impl GeneratorOptions {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            GeneratorOptions::Password(options) => {
+                if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&options.length) {
+                    return Err(AppError::InvalidInput(format!(
+                        "length value={} min={} max={}",
+                        options.length, MIN_PASSWORD_LENGTH, MAX_PASSWORD_LENGTH
+                    )));
+                }
+                if options.pronounceable && !options.lowercase && !options.uppercase {
+                    return Err(AppError::InvalidInput(
+                        "pronounceable lowercase=false uppercase=false".to_string(),
+                    ));
+                }
+
+                let classes = options.classes();
+                if classes.is_empty() {
+                    return Err(AppError::InvalidInput("classes count=0".to_string()));
+                }
+                if options.require_each_class && options.length < classes.len() {
+                    return Err(AppError::InvalidInput(format!(
+                        "length value={} required_classes={}",
+                        options.length,
+                        classes.len()
+                    )));
+                }
+            }
+            GeneratorOptions::Passphrase(options) => {
+                if !(MIN_PASSPHRASE_WORDS..=MAX_PASSPHRASE_WORDS).contains(&options.words) {
+                    return Err(AppError::InvalidInput(format!(
+                        "words value={} min={} max={}",
+                        options.words, MIN_PASSPHRASE_WORDS, MAX_PASSPHRASE_WORDS
+                    )));
+                }
+                if options.separator.chars().count() > 3 {
+                    return Err(AppError::InvalidInput(format!(
+                        "separator value={:?} max=3",
+                        options.separator
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PasswordOptions {
+    /// Conjuntos de caracteres das classes ativas, já sem os ambíguos.
+    fn classes(&self) -> Vec<Vec<char>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, chars)| self.filter(chars))
+        .collect()
+    }
+
+    fn filter(&self, chars: &str) -> Vec<char> {
+        chars
+            .chars()
+            .filter(|c| !self.exclude_ambiguous || !AMBIGUOUS.contains(*c))
+            .collect()
+    }
+}
+
+pub fn generate(options: &GeneratorOptions) -> Result<GeneratedSecret> {
+    options.validate()?;
+
+    Ok(match options {
+        GeneratorOptions::Password(options) if options.pronounceable => pronounceable(options),
+        GeneratorOptions::Password(options) => random_password(options),
+        GeneratorOptions::Passphrase(options) => passphrase(options),
+    })
+}
+
+fn pick<T: Copy>(items: &[T]) -> T {
+    items[OsRng.gen_range(0..items.len())]
+}
+
+fn random_password(options: &PasswordOptions) -> GeneratedSecret {
+    let classes = options.classes();
+    let pool: Vec<char> = classes.iter().flatten().copied().collect();
+
+    let value = loop {
+        let candidate: Vec<char> = (0..options.length).map(|_| pick(&pool)).collect();
+
+        let complete = !options.require_each_class
+            || classes
+                .iter()
+                .all(|class| candidate.iter().any(|c| class.contains(c)));
+        if complete {
+            break candidate.into_iter().collect();
+        }
+    };
+
+    let sizes: Vec<usize> = classes.iter().map(Vec::len).collect();
+    let entropy_bits = if options.require_each_class {
+        required_classes_entropy(options.length, &sizes)
+    } else {
+        options.length as f64 * (pool.len() as f64).log2()
+    };
+
+    GeneratedSecret {
+        value,
+        entropy_bits,
+    }
+}
+
+/// log2 do número de senhas de `length` caracteres com ao menos um caractere
+/// de cada classe. Por inclusão-exclusão, a fração válida entre as
+/// `pool^length` senhas é `Σ (-1)^|S| (1 - |S|/pool)^length` sobre os
+/// subconjuntos `S` de classes ausentes.
+fn required_classes_entropy(length: usize, sizes: &[usize]) -> f64 {
+    let pool: usize = sizes.iter().sum();
+
+    let mut fraction = 0.0;
+    for mask in 0u32..(1 << sizes.len()) {
+        let missing: usize = sizes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1 << i) != 0)
+            .map(|(_, size)| size)
+            .sum();
+        let sign = if mask.count_ones() % 2 == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+
+        fraction += sign * (1.0 - missing as f64 / pool as f64).powi(length as i32);
+    }
+
+    length as f64 * (pool as f64).log2() + fraction.log2()
+}
+
+fn pronounceable(options: &PasswordOptions) -> GeneratedSecret {
+    // Os ambíguos dependem da caixa (`l` e `I`, por exemplo), então cada
+    // conjunto é filtrado já na caixa em que será usado
+    let lower = [options.filter(CONSONANTS), options.filter(VOWELS)];
+    let upper = [
+        options.filter(&CONSONANTS.to_ascii_uppercase()),
+        options.filter(&VOWELS.to_ascii_uppercase()),
+    ];
+    let digits = options.filter(DIGITS);
+    let symbols = options.filter(SYMBOLS);
+
+    let letters = options.length - usize::from(options.digits) - usize::from(options.symbols);
+
+    // Começar por consoante ou vogal também é sorteado (1 bit)
+    let mut vowel_turn = OsRng.gen_bool(0.5);
+    let mut entropy_bits = 1.0;
+
+    let uppercase_position = match (options.lowercase, options.uppercase) {
+        (true, true) if letters > 0 => {
+            // Uma letra maiúscula em posição sorteada
+            entropy_bits += (letters as f64).log2();
+            Some(OsRng.gen_range(0..letters))
+        }
+        _ => None,
+    };
+
+    let mut value: Vec<char> = Vec::with_capacity(options.length);
+    for position in 0..letters {
+        let sets = if !options.lowercase || uppercase_position == Some(position) {
+            &upper
+        } else {
+            &lower
+        };
+        let set = &sets[usize::from(vowel_turn)];
+        value.push(pick(set));
+        entropy_bits += (set.len() as f64).log2();
+        vowel_turn = !vowel_turn;
+    }
+
+    for (enabled, set) in [(options.digits, &digits), (options.symbols, &symbols)] {
+        if enabled {
+            value.push(pick(set));
+            entropy_bits += (set.len() as f64).log2();
+        }
+    }
+
+    GeneratedSecret {
+        value: value.into_iter().collect(),
+        entropy_bits,
+    }
+}
+
+pub(crate) fn wordlist(language: WordlistLanguage) -> Vec<&'static str> {
+    let words = match language {
+        WordlistLanguage::En => WORDLIST_EN,
+        WordlistLanguage::Pt => WORDLIST_PT,
+    };
+
+    words.lines().filter(|word| !word.is_empty()).collect()
+}
+
+fn passphrase(options: &PassphraseOptions) -> GeneratedSecret {
+    let language = options.language.unwrap_or(match current_locale() {
+        Locale::PtBr => WordlistLanguage::Pt,
+        Locale::En => WordlistLanguage::En,
+    });
+    let words = wordlist(language);
+
+    let mut chosen: Vec<String> = (0..options.words)
+        .map(|_| {
+            let word = pick(&words);
+            if options.capitalize {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    let mut entropy_bits = options.words as f64 * (words.len() as f64).log2();
+
+    if options.include_number {
+        let position = OsRng.gen_range(0..chosen.len());
+        chosen[position].push(pick(&DIGITS.chars().collect::<Vec<_>>()));
+        entropy_bits += (options.words as f64).log2() + 10f64.log2();
+    }
+
+    GeneratedSecret {
+        value: chosen.join(&options.separator),
+        entropy_bits,
+    }
+}
+
+impl Vault {
+    /// Política de geração do projeto, se houver.
+    pub fn generator_policy(&self, project_id: i64) -> Result<Option<GeneratorOptions>> {
+        self.project(project_id)?;
+
+        let json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT options FROM project_generator_policies WHERE project_id = ?1",
+                [project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        json.map(|json| serde_json::from_str(&json).map_err(|e| AppError::Internal(e.to_string())))
+            .transpose()
+    }
+
+    /// Define (ou, com `None`, remove) a política de geração do projeto.
+    pub fn set_generator_policy(
+        &self,
+        project_id: i64,
+        options: Option<&GeneratorOptions>,
+    ) -> Result<()> {
+        self.project(project_id)?;
+
+        match options {
+            Some(options) => {
+                options.validate()?;
+                let json = serde_json::to_string(options)
+                    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+                self.conn().execute(
+                    "INSERT INTO project_generator_policies (project_id, options) VALUES (?1, ?2) ON CONFLICT(project_id) DO UPDATE SET options = excluded.options",
+                    (project_id, json),
+                )?;
+            }
+            None => {
+                self.conn().execute(
+                    "DELETE FROM project_generator_policies WHERE project_id = ?1",
+                    [project_id],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gera com as opções dadas ou, sem elas, com a política do projeto (ou o
+    /// padrão).
+    pub fn generate_for_project(
+        &self,
+        project_id: i64,
+        options: Option<&GeneratorOptions>,
+    ) -> Result<GeneratedSecret> {
+        match options {
+            Some(options) => generate(options),
+            None => generate(&self.generator_policy(project_id)?.unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_password_respects_options() {
+        let options = PasswordOptions {
+            length: 12,
+            symbols: false,
+            exclude_ambiguous: true,
+            ..Default::default()
+        };
+        let classes = options.classes();
+
+        for _ in 0..200 {
+            let generated = random_password(&options);
+            let chars: Vec<char> = generated.value.chars().collect();
+
+            assert_eq!(chars.len(), 12);
+            assert!(chars.iter().all(|c| c.is_ascii_alphanumeric()));
+            assert!(!chars.iter().any(|c| AMBIGUOUS.contains(*c)));
+            for class in &classes {
+                assert!(chars.iter().any(|c| class.contains(c)));
+            }
+        }
+
+        // Com classes obrigatórias a entropia é um pouco menor que a ingênua
+        let naive = 12.0 * ((25 + 24 + 8) as f64).log2();
+        let entropy = random_password(&options).entropy_bits;
+        assert!(entropy < naive && entropy > naive - 1.0, "{}", entropy);
+
+        // Uma classe só: as duas contas coincidem
+        assert!((required_classes_entropy(10, &[10]) - 10.0 * 10f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pronounceable_and_passphrase() {
+        let generated = generate(&GeneratorOptions::Password(PasswordOptions {
+            length: 10,
+            uppercase: false,
+            pronounceable: true,
+            ..Default::default()
+        }))
+        .unwrap();
+        let chars: Vec<char> = generated.value.chars().collect();
+        assert_eq!(chars.len(), 10);
+        assert!(chars[..8].iter().all(|c| c.is_ascii_lowercase()));
+        assert!(chars[8].is_ascii_digit() && SYMBOLS.contains(chars[9]));
+        assert!(chars[..8]
+            .windows(2)
+            .all(|pair| VOWELS.contains(pair[0]) != VOWELS.contains(pair[1])));
+
+        let generated = generate(&GeneratorOptions::Passphrase(PassphraseOptions {
+            words: 5,
+            separator: " ".into(),
+            language: Some(WordlistLanguage::Pt),
+            ..Default::default()
+        }))
+        .unwrap();
+        let words = wordlist(WordlistLanguage::Pt);
+        assert_eq!(words.len(), 2048);
+        assert!(generated.value.split(' ').all(|word| words.contains(&word)));
+        assert_eq!(generated.entropy_bits, 55.0);
+
+        assert!(generate(&GeneratorOptions::Password(PasswordOptions {
+            length: 3,
+            ..Default::default()
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_pronounceable_excludes_ambiguous() {
+        for (lowercase, uppercase) in [(true, true), (false, true), (true, false)] {
+            let options = PasswordOptions {
+                length: 12,
+                lowercase,
+                uppercase,
+                exclude_ambiguous: true,
+                pronounceable: true,
+                ..Default::default()
+            };
+
+            for _ in 0..500 {
+                let generated = pronounceable(&options);
+                assert!(
+                    !generated.value.chars().any(|c| AMBIGUOUS.contains(c)),
+                    "{}",
+                    generated.value
+                );
+            }
+        }
+
+        // Só maiúsculas: vogais sem I e O, consoantes completas (L não é ambígua)
+        let generated = pronounceable(&PasswordOptions {
+            length: 4,
+            lowercase: false,
+            digits: false,
+            symbols: false,
+            exclude_ambiguous: true,
+            pronounceable: true,
+            ..Default::default()
+        });
+        let expected = 1.0 + 2.0 * 20f64.log2() + 2.0 * 3f64.log2();
+        assert!((generated.entropy_bits - expected).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,357 @@
+//! Relatório de saúde do cofre.
+//!
+//! Percorre os segredos fora da lixeira procurando senhas fracas (estimativa
+//! de `strength`), senhas repetidas entre segredos, senhas mais velhas que o
+//! limite do cofre e logins sem usuário. As senhas são decifradas só em
+//! memória: o relatório traz notas, idades e ids, nunca os valores.
+//!
+//! A idade conta a partir da última troca de senha (`password_changed_at`) ou,
+//! se ela nunca mudou, da criação do segredo.
+
+use crate::database::{
+    decrypt_column_string, set_vault_setting, vault_setting, SECRET_PASSWORD_COLUMN,
+};
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{kind_from_row, SecretKind};
+use crate::strength::{self, PatternKind};
+use crate::vault::Vault;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+/// Idade máxima de uma senha, em dias, quando o cofre não define outra.
+pub const DEFAULT_PASSWORD_MAX_AGE_DAYS: u32 = 365;
+
+const PASSWORD_MAX_AGE_SETTING: &str = "password_max_age_days";
+
+/// Notas abaixo desta (de 0 a 4) contam como senha fraca.
+const WEAK_SCORE_THRESHOLD: u8 = 3;
+
+/// Quanto cada problema tira dos 100 pontos de um segredo.
+const WEAK_PENALTY: u32 = 40;
+const REUSED_PENALTY: u32 = 30;
+const OLD_PENALTY: u32 = 20;
+const EMPTY_USERNAME_PENALTY: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HealthIssue {
+    #[serde(rename_all = "camelCase")]
+    Weak {
+        /// De 0 a 4
+        score: u8,
+        guesses_log10: f64,
+        patterns: Vec<PatternKind>,
+    },
+    /// A mesma senha aparece nos segredos listados
+    #[serde(rename_all = "camelCase")]
+    Reused {
+        shared_with: Vec<i64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Old {
+        age_days: i64,
+    },
+    EmptyUsername,
+}
+
+impl HealthIssue {
+    fn penalty(&self) -> u32 {
+        match self {
+            HealthIssue::Weak { .. } => WEAK_PENALTY,
+            HealthIssue::Reused { .. } => REUSED_PENALTY,
+            HealthIssue::Old { .. } => OLD_PENALTY,
+            HealthIssue::EmptyUsername => EMPTY_USERNAME_PENALTY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretHealth {
+    pub secret_id: i64,
+    pub title: String,
+    pub username: String,
+    pub kind: SecretKind,
+    pub project_id: Option<i64>,
+    /// Nota da senha (0 a 4); `None` para segredos sem senha
+    pub strength: Option<u8>,
+    pub password_age_days: i64,
+    /// De 0 a 100
+    pub score: u8,
+    pub issues: Vec<HealthIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Média das notas dos segredos, de 0 a 100
+    pub score: u8,
+    pub scanned: usize,
+    pub weak: usize,
+    pub reused: usize,
+    pub old: usize,
+    pub empty_username: usize,
+    pub password_max_age_days: u32,
+    /// Só os segredos com algum problema, do pior para o melhor
+    pub secrets: Vec<SecretHealth>,
+}
+
+struct ScannedSecret {
+    health: SecretHealth,
+    /// SHA-256 da senha, só para achar repetições
+    digest: Option<[u8; 32]>,
+}
+
+impl Vault {
+    /// Analisa os segredos fora da lixeira.
+    pub fn health_report(&self) -> Result<HealthReport> {
+        let max_age = self.password_max_age()?;
+
+        let mut stmt = self.conn().prepare(
+            "SELECT id, title, username, kind, project_id, password_blob,
+                    CAST(julianday('now') - julianday(coalesce(password_changed_at, created_at)) AS INTEGER)
+             FROM secrets WHERE deleted_at IS NULL ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let password = Zeroizing::new(decrypt_column_string(
+                row,
+                5,
+                self.field_key(),
+                SECRET_PASSWORD_COLUMN,
+                id,
+            )?);
+            let title: String = row.get(1)?;
+            let username: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+            let kind = kind_from_row(row, 3)?;
+            let age_days: i64 = row.get::<_, Option<i64>>(6)?.unwrap_or(0);
+
+            let mut issues = Vec::new();
+
+            // Outros tipos guardam os dados no payload; a senha costuma ficar vazia
+            let has_password = kind == SecretKind::Login || !password.is_empty();
+            let estimate =
+                has_password.then(|| strength::estimate(&password, &[&title, &username]));
+            if let Some(estimate) = &estimate {
+                if estimate.score < WEAK_SCORE_THRESHOLD {
+                    issues.push(HealthIssue::Weak {
+                        score: estimate.score,
+                        guesses_log10: estimate.guesses_log10,
+                        patterns: estimate.patterns.clone(),
+                    });
+                }
+            }
+            if has_password && max_age > 0 && age_days > max_age as i64 {
+                issues.push(HealthIssue::Old { age_days });
+            }
+            if kind == SecretKind::Login && username.trim().is_empty() {
+                issues.push(HealthIssue::EmptyUsername);
+            }
+
+            let digest = (!password.is_empty()).then(|| Sha256::digest(password.as_bytes()).into());
+
+            Ok(ScannedSecret {
+                health: SecretHealth {
+                    secret_id: id,
+                    title,
+                    username,
+                    kind,
+                    project_id: row.get(4)?,
+                    strength: estimate.map(|estimate| estimate.score),
+                    password_age_days: age_days,
+                    score: 0,
+                    issues,
+                },
+                digest,
+            })
+        })?;
+        let mut scanned = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut by_digest: HashMap<[u8; 32], Vec<i64>> = HashMap::new();
+        for secret in &scanned {
+            if let Some(digest) = secret.digest {
+                by_digest
+                    .entry(digest)
+                    .or_default()
+                    .push(secret.health.secret_id);
+            }
+        }
+        for secret in &mut scanned {
+            let Some(ids) = secret.digest.and_then(|digest| by_digest.get(&digest)) else {
+                continue;
+            };
+            if ids.len() > 1 {
+                let shared_with = ids
+                    .iter()
+                    .copied()
+                    .filter(|id| *id != secret.health.secret_id)
+                    .collect();
+                secret
+                    .health
+                    .issues
+                    .push(HealthIssue::Reused { shared_with });
+            }
+        }
+
+        let count = |matches: fn(&HealthIssue) -> bool| {
+            scanned
+                .iter()
+                .filter(|secret| secret.health.issues.iter().any(matches))
+                .count()
+        };
+        let weak = count(|issue| matches!(issue, HealthIssue::Weak { .. }));
+        let reused = count(|issue| matches!(issue, HealthIssue::Reused { .. }));
+        let old = count(|issue| matches!(issue, HealthIssue::Old { .. }));
+        let empty_username = count(|issue| matches!(issue, HealthIssue::EmptyUsername));
+
+        let mut total = 0u32;
+        for secret in &mut scanned {
+            let penalty: u32 = secret.health.issues.iter().map(HealthIssue::penalty).sum();
+            secret.health.score = 100u32.saturating_sub(penalty) as u8;
+            total += secret.health.score as u32;
+        }
+        let score = match scanned.len() {
+            0 => 100,
+            n => (total as f64 / n as f64).round() as u8,
+        };
+
+        let scanned_count = scanned.len();
+        let mut secrets: Vec<SecretHealth> = scanned
+            .into_iter()
+            .map(|secret| secret.health)
+            .filter(|health| !health.issues.is_empty())
+            .collect();
+        secrets.sort_by_key(|health| (health.score, health.secret_id));
+
+        Ok(HealthReport {
+            score,
+            scanned: scanned_count,
+            weak,
+            reused,
+            old,
+            empty_username,
+            password_max_age_days: max_age,
+            secrets,
+        })
+    }
+
+    /// Idade máxima das senhas, em dias. Zero desliga a verificação.
+    pub fn password_max_age(&self) -> Result<u32> {
+        match vault_setting(self.conn(), PASSWORD_MAX_AGE_SETTING)? {
+            Some(value) => value.parse().map_err(|_| {
+                AppError::Internal(format!("password_max_age_days value={:?}", value))
+            }),
+            None => Ok(DEFAULT_PASSWORD_MAX_AGE_DAYS),
+        }
+    }
+
+    pub fn set_password_max_age(&self, days: u32) -> Result<()> {
+        set_vault_setting(self.conn(), PASSWORD_MAX_AGE_SETTING, &days.to_string())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+    use crate::secret_kinds::{NotePayload, SecretPayload};
+    use std::fs;
+
+    fn temp_vault(name: &str) -> (Vault, VaultEntry) {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+
+        (Vault::create(&entry, "senha").unwrap(), entry)
+    }
+
+    fn issues(report: &HealthReport, id: i64) -> Vec<HealthIssue> {
+        report
+            .secrets
+            .iter()
+            .find(|health| health.secret_id == id)
+            .map(|health| health.issues.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_health_report_flags_issues_without_values() {
+        let (mut vault, entry) = temp_vault("test_health_report");
+
+        let strong = "k#9Tq!vZ2@xLm4&w";
+        let ok = vault
+            .create_typed_secret("GitHub", "octocat", strong, None, &[])
+            .unwrap();
+        let weak = vault
+            .create_typed_secret("Banco", "", "senha123", None, &[])
+            .unwrap();
+        let copy = vault
+            .create_typed_secret("GitLab", "octocat", strong, None, &[])
+            .unwrap();
+        let note = vault
+            .create_typed_secret(
+                "Nota",
+                "",
+                "",
+                Some(&SecretPayload::Note(NotePayload {
+                    content: "x".into(),
+                })),
+                &[],
+            )
+            .unwrap();
+
+        // Senha antiga, e depois trocada
+        vault
+            .conn()
+            .execute(
+                "UPDATE secrets SET created_at = '2020-01-01 00:00:00' WHERE id IN (?1, ?2)",
+                [ok.id, weak.id],
+            )
+            .unwrap();
+        vault
+            .update_secret(weak.id, "Banco", "", Some("outra123"), None)
+            .unwrap();
+
+        let report = vault.health_report().unwrap();
+        assert_eq!(report.scanned, 4);
+        assert_eq!((report.weak, report.reused, report.old), (1, 2, 1));
+        assert_eq!(report.empty_username, 1);
+        assert!(report.score < 100);
+
+        let ok_issues = issues(&report, ok.id);
+        assert!(matches!(ok_issues[0], HealthIssue::Old { age_days } if age_days > 365));
+        assert_eq!(
+            ok_issues[1],
+            HealthIssue::Reused {
+                shared_with: vec![copy.id]
+            }
+        );
+        let weak_issues = issues(&report, weak.id);
+        assert!(matches!(weak_issues[0], HealthIssue::Weak { .. }));
+        assert_eq!(weak_issues[1], HealthIssue::EmptyUsername);
+        assert!(issues(&report, note.id).is_empty());
+
+        // Nenhuma senha no relatório
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains(strong) && !json.contains("outra123"));
+
+        // Sem limite de idade, nada é velho
+        vault.set_password_max_age(0).unwrap();
+        assert_eq!(vault.health_report().unwrap().old, 0);
+
+        drop(vault);
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+}
@@ -0,0 +1,487 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Idiomas suportados pelas mensagens do backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Locale {
+    fn to_u8(self) -> u8 {
+        match self {
+            Locale::PtBr => 0,
+            Locale::En => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Locale::En,
+            _ => Locale::PtBr,
+        }
+    }
+}
+
+// O idioma é global ao processo: é lido de settings.json na inicialização e
+// trocado pelo comando set_locale.
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn current_locale() -> Locale {
+    Locale::from_u8(CURRENT_LOCALE.load(Ordering::Relaxed))
+}
+
+pub fn set_current_locale(locale: Locale) {
+    CURRENT_LOCALE.store(locale.to_u8(), Ordering::Relaxed);
+}
+
+/// Catálogo de mensagens exibidas ao usuário. Mensagens com parâmetros usam
+/// `{}` e são preenchidas em ordem por `tf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Cofre
+    VaultCreated,
+    VaultUnlocked,
+    VaultLockedOk,
+    VaultRenamed,
+    VaultDeleted,
+    MasterPasswordChanged,
+    BackupExported,
+    ImportSummary,
+    CleaningOldDatabase,
+    DotenvImportSummary,
+    DotenvExported,
+    ExternalImportSummary,
+    NotImported,
+    PlaintextExported,
+    PlaintextExportNeedsConfirm,
+    NotExported,
+
+    // Segredos e lixeira
+    SecretMovedToTrash,
+    SecretDeleted,
+    SecretRestored,
+    SecretUpdated,
+    SecretVersionRestored,
+    HistoryLimitUpdated,
+    GeneratorPolicySaved,
+    PasswordMaxAgeUpdated,
+    TotpRemoved,
+    TrashEmptied,
+    TrashRetentionUpdated,
+    TrashPurged,
+    TrashPurgeFailed,
+
+    // Linha de comando
+    PromptMasterPassword,
+    PromptSecretPassword,
+    PromptBackupPassword,
+    PromptTotpKey,
+    SecretCreated,
+    LabelVault,
+    LabelTitle,
+    LabelUsername,
+    LabelPassword,
+    LabelKind,
+    LabelProject,
+    LabelCreatedAt,
+    LabelDeletedAt,
+    LabelVariable,
+    LabelVersion,
+    NoSecrets,
+    NoEnvBindings,
+    NoVersions,
+    NoResults,
+    NextPage,
+    NoChanges,
+    HistoryLimit,
+    Entropy,
+    HealthSummary,
+    LabelScore,
+    LabelIssues,
+    IssueWeak,
+    IssueReused,
+    IssueOld,
+    IssueEmptyUsername,
+    NoHealthIssues,
+    BreachCheckSummary,
+    LabelBreached,
+    TotpCode,
+    TrashRetention,
+    LabelPurgeAt,
+
+    // Configurações
+    LocaleChanged,
+    SettingsSaved,
+
+    // Banco de dados
+    ApplyingMigration,
+    DatabaseUpdated,
+    RunningSeed,
+    SeedFinished,
+
+    // Erros (ver AppError::message)
+    ErrVaultLocked,
+    ErrVaultNotFound,
+    ErrVaultAlreadyExists,
+    ErrWrongPassword,
+    ErrNotFound,
+    ErrConflict,
+    ErrInvalidInput,
+    ErrInvalidBackup,
+    ErrUnsupportedVersion,
+    ErrCrypto,
+    ErrIo,
+    ErrDb,
+    ErrInternal,
+}
+
+impl Msg {
+    pub const ALL: [Msg; 84] = [
+        Msg::VaultCreated,
+        Msg::VaultUnlocked,
+        Msg::VaultLockedOk,
+        Msg::VaultRenamed,
+        Msg::VaultDeleted,
+        Msg::MasterPasswordChanged,
+        Msg::BackupExported,
+        Msg::ImportSummary,
+        Msg::CleaningOldDatabase,
+        Msg::DotenvImportSummary,
+        Msg::DotenvExported,
+        Msg::ExternalImportSummary,
+        Msg::NotImported,
+        Msg::PlaintextExported,
+        Msg::PlaintextExportNeedsConfirm,
+        Msg::NotExported,
+        Msg::SecretMovedToTrash,
+        Msg::SecretDeleted,
+        Msg::SecretRestored,
+        Msg::SecretUpdated,
+        Msg::SecretVersionRestored,
+        Msg::HistoryLimitUpdated,
+        Msg::GeneratorPolicySaved,
+        Msg::PasswordMaxAgeUpdated,
+        Msg::TotpRemoved,
+        Msg::TrashEmptied,
+        Msg::TrashRetentionUpdated,
+        Msg::TrashPurged,
+        Msg::TrashPurgeFailed,
+        Msg::PromptMasterPassword,
+        Msg::PromptSecretPassword,
+        Msg::PromptBackupPassword,
+        Msg::PromptTotpKey,
+        Msg::SecretCreated,
+        Msg::LabelVault,
+        Msg::LabelTitle,
+        Msg::LabelUsername,
+        Msg::LabelPassword,
+        Msg::LabelKind,
+        Msg::LabelProject,
+        Msg::LabelCreatedAt,
+        Msg::LabelDeletedAt,
+        Msg::LabelVariable,
+        Msg::LabelVersion,
+        Msg::NoSecrets,
+        Msg::NoEnvBindings,
+        Msg::NoVersions,
+        Msg::NoResults,
+        Msg::NextPage,
+        Msg::NoChanges,
+        Msg::HistoryLimit,
+        Msg::Entropy,
+        Msg::HealthSummary,
+        Msg::LabelScore,
+        Msg::LabelIssues,
+        Msg::IssueWeak,
+        Msg::IssueReused,
+        Msg::IssueOld,
+        Msg::IssueEmptyUsername,
+        Msg::NoHealthIssues,
+        Msg::BreachCheckSummary,
+        Msg::LabelBreached,
+        Msg::TotpCode,
+        Msg::TrashRetention,
+        Msg::LabelPurgeAt,
+        Msg::LocaleChanged,
+        Msg::SettingsSaved,
+        Msg::ApplyingMigration,
+        Msg::DatabaseUpdated,
+        Msg::RunningSeed,
+        Msg::SeedFinished,
+        Msg::ErrVaultLocked,
+        Msg::ErrVaultNotFound,
+        Msg::ErrVaultAlreadyExists,
+        Msg::ErrWrongPassword,
+        Msg::ErrNotFound,
+        Msg::ErrConflict,
+        Msg::ErrInvalidInput,
+        Msg::ErrInvalidBackup,
+        Msg::ErrUnsupportedVersion,
+        Msg::ErrCrypto,
+        Msg::ErrIo,
+        Msg::ErrDb,
+        Msg::ErrInternal,
+    ];
+
+    pub fn text(self, locale: Locale) -> &'static str {
+        let (pt_br, en) = match self {
+            Msg::VaultCreated => ("Cofre criado com sucesso!", "Vault created successfully!"),
+            Msg::VaultUnlocked => ("Cofre aberto!", "Vault unlocked!"),
+            Msg::VaultLockedOk => ("Cofre trancado.", "Vault locked."),
+            Msg::VaultRenamed => ("Cofre renomeado!", "Vault renamed!"),
+            Msg::VaultDeleted => ("Cofre excluído!", "Vault deleted!"),
+            Msg::MasterPasswordChanged => (
+                "Senha mestre alterada com sucesso!",
+                "Master password changed successfully!",
+            ),
+            Msg::BackupExported => (
+                "Backup exportado com sucesso!",
+                "Backup exported successfully!",
+            ),
+            Msg::ImportSummary => (
+                "Importação concluída: {} novos, {} ignorados ({} projetos, {} anexos)",
+                "Import finished: {} new, {} skipped ({} projects, {} attachments)",
+            ),
+            Msg::CleaningOldDatabase => (
+                "Limpando banco de dados antigo em {}",
+                "Removing old database at {}",
+            ),
+            Msg::DotenvImportSummary => (
+                "Importação do .env concluída: {} novos, {} atualizados, {} sem alteração",
+                ".env import finished: {} new, {} updated, {} unchanged",
+            ),
+            Msg::DotenvExported => (".env exportado com sucesso!", ".env exported successfully!"),
+            Msg::ExternalImportSummary => (
+                "Importação concluída: {} novos, {} ignorados ({} projetos novos)",
+                "Import finished: {} new, {} skipped ({} new projects)",
+            ),
+            Msg::NotImported => ("Não importado", "Not imported"),
+            Msg::PlaintextExported => (
+                "{} segredos exportados em texto puro. Guarde o arquivo com cuidado.",
+                "{} secrets exported as plaintext. Keep the file safe.",
+            ),
+            Msg::PlaintextExportNeedsConfirm => (
+                "A exportação grava as senhas em texto puro; repita com --confirm",
+                "The export writes passwords as plaintext; run again with --confirm",
+            ),
+            Msg::NotExported => ("Não exportado", "Not exported"),
+
+            Msg::SecretMovedToTrash => (
+                "Segredo movido para a lixeira com sucesso!",
+                "Secret moved to trash!",
+            ),
+            Msg::SecretDeleted => ("Segredo deletado!", "Secret deleted!"),
+            Msg::SecretRestored => (
+                "Segredo restaurado com sucesso!",
+                "Secret restored successfully!",
+            ),
+            Msg::SecretUpdated => ("Segredo atualizado!", "Secret updated!"),
+            Msg::SecretVersionRestored => {
+                ("Versão anterior restaurada!", "Previous version restored!")
+            }
+            Msg::HistoryLimitUpdated => {
+                ("Limite do histórico atualizado!", "History limit updated!")
+            }
+            Msg::GeneratorPolicySaved => (
+                "Política de geração do projeto salva!",
+                "Project generator policy saved!",
+            ),
+            Msg::PasswordMaxAgeUpdated => (
+                "Idade máxima das senhas atualizada!",
+                "Password max age updated!",
+            ),
+            Msg::TotpRemoved => ("Semente TOTP removida!", "TOTP seed removed!"),
+            Msg::TrashEmptied => ("Lixeira vazia!", "Trash emptied!"),
+            Msg::TrashRetentionUpdated => {
+                ("Prazo da lixeira atualizado!", "Trash retention updated!")
+            }
+            Msg::TrashPurged => (
+                "Lixeira: {} segredos e {} projetos vencidos excluídos",
+                "Trash: {} expired secrets and {} projects purged",
+            ),
+            Msg::TrashPurgeFailed => (
+                "Falha ao limpar a lixeira: {}",
+                "Failed to purge the trash: {}",
+            ),
+
+            Msg::PromptMasterPassword => ("Senha mestre: ", "Master password: "),
+            Msg::PromptSecretPassword => ("Senha do segredo: ", "Secret password: "),
+            Msg::PromptBackupPassword => ("Senha do backup: ", "Backup password: "),
+            Msg::PromptTotpKey => (
+                "URI otpauth:// ou chave base32: ",
+                "otpauth:// URI or base32 key: ",
+            ),
+            Msg::SecretCreated => ("Segredo criado com id {}", "Secret created with id {}"),
+            Msg::LabelVault => ("Cofre", "Vault"),
+            Msg::LabelTitle => ("Título", "Title"),
+            Msg::LabelUsername => ("Usuário", "Username"),
+            Msg::LabelPassword => ("Senha", "Password"),
+            Msg::LabelKind => ("Tipo", "Kind"),
+            Msg::LabelProject => ("Projeto", "Project"),
+            Msg::LabelCreatedAt => ("Criado em", "Created at"),
+            Msg::LabelDeletedAt => ("Excluído em", "Deleted at"),
+            Msg::LabelVariable => ("Variável", "Variable"),
+            Msg::LabelVersion => ("Versão", "Version"),
+            Msg::NoSecrets => ("Nenhum segredo encontrado.", "No secrets found."),
+            Msg::NoEnvBindings => (
+                "Nenhum segredo ativo neste projeto.",
+                "No active secrets in this project.",
+            ),
+            Msg::NoVersions => (
+                "Nenhuma versão anterior deste segredo.",
+                "No previous versions of this secret.",
+            ),
+            Msg::NoResults => ("Nenhum resultado.", "No results."),
+            Msg::Entropy => ("Entropia: {} bits", "Entropy: {} bits"),
+            Msg::HealthSummary => (
+                "Saúde do cofre: {}/100 ({} segredos analisados)",
+                "Vault health: {}/100 ({} secrets scanned)",
+            ),
+            Msg::LabelScore => ("Nota", "Score"),
+            Msg::LabelIssues => ("Problemas", "Issues"),
+            Msg::IssueWeak => ("senha fraca", "weak password"),
+            Msg::IssueReused => ("repetida em {}", "reused in {}"),
+            Msg::IssueOld => ("senha com {} dias", "password {} days old"),
+            Msg::IssueEmptyUsername => ("sem usuário", "no username"),
+            Msg::NoHealthIssues => ("Nenhum problema encontrado.", "No issues found."),
+            Msg::BreachCheckSummary => (
+                "{} senhas verificadas, {} encontradas em vazamentos",
+                "{} passwords checked, {} found in breaches",
+            ),
+            Msg::LabelBreached => ("vazada", "breached"),
+            Msg::TotpCode => ("{} (vale por mais {} s)", "{} (valid for {} more s)"),
+            Msg::TrashRetention => (
+                "Dias na lixeira antes da exclusão: {}",
+                "Days in the trash before purging: {}",
+            ),
+            Msg::LabelPurgeAt => ("sai da lixeira em", "purged on"),
+            Msg::NextPage => ("Próxima página: --cursor {}", "Next page: --cursor {}"),
+            Msg::NoChanges => ("Nenhuma diferença.", "No differences."),
+            Msg::HistoryLimit => (
+                "Versões guardadas por segredo: {}",
+                "Versions kept per secret: {}",
+            ),
+
+            Msg::LocaleChanged => ("Idioma alterado!", "Language changed!"),
+            Msg::SettingsSaved => ("Configurações salvas!", "Settings saved!"),
+
+            Msg::ApplyingMigration => (
+                "Aplicando migration versão: {}",
+                "Applying migration version: {}",
+            ),
+            Msg::DatabaseUpdated => (
+                "Banco de dados atualizado para a versão: {}",
+                "Database updated to version: {}",
+            ),
+            Msg::RunningSeed => ("Executando seed", "Running seed"),
+            Msg::SeedFinished => ("Seed executado", "Seed finished"),
+
+            Msg::ErrVaultLocked => (
+                "Cofre fechado! Faça login primeiro.",
+                "Vault is locked! Unlock it first.",
+            ),
+            Msg::ErrVaultNotFound => (
+                "Nenhum cofre encontrado. Crie um primeiro.",
+                "No vault found. Create one first.",
+            ),
+            Msg::ErrVaultAlreadyExists => (
+                "Um cofre já existe neste computador.",
+                "A vault already exists on this computer.",
+            ),
+            Msg::ErrWrongPassword => ("Senha incorreta", "Wrong password"),
+            Msg::ErrNotFound => ("Registro não encontrado", "Record not found"),
+            Msg::ErrConflict => (
+                "Operação em conflito com os dados existentes",
+                "Operation conflicts with existing data",
+            ),
+            Msg::ErrInvalidInput => ("Dados inválidos", "Invalid input"),
+            Msg::ErrInvalidBackup => (
+                "Arquivo de backup inválido ou corrompido",
+                "Invalid or corrupted backup file",
+            ),
+            Msg::ErrUnsupportedVersion => (
+                "Arquivo criado por uma versão mais nova do aplicativo",
+                "File was created by a newer version of the app",
+            ),
+            Msg::ErrCrypto => ("Falha de criptografia", "Encryption failure"),
+            Msg::ErrIo => ("Erro ao acessar arquivo", "Error accessing file"),
+            Msg::ErrDb => ("Erro no banco de dados", "Database error"),
+            Msg::ErrInternal => ("Erro interno", "Internal error"),
+        };
+
+        match locale {
+            Locale::PtBr => pt_br,
+            Locale::En => en,
+        }
+    }
+}
+
+/// Texto da mensagem no idioma atual.
+pub fn t(msg: Msg) -> &'static str {
+    msg.text(current_locale())
+}
+
+/// Texto da mensagem no idioma atual com os `{}` preenchidos em ordem.
+pub fn tf(msg: Msg, args: &[&dyn Display]) -> String {
+    fill(t(msg), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_match_between_locales() {
+        for msg in Msg::ALL {
+            assert_eq!(
+                msg.text(Locale::PtBr).matches("{}").count(),
+                msg.text(Locale::En).matches("{}").count(),
+                "{:?}",
+                msg
+            );
+        }
+    }
+
+    #[test]
+    fn test_locale_serializes_as_tag() {
+        assert_eq!(serde_json::to_string(&Locale::PtBr).unwrap(), "\"pt-BR\"");
+        assert_eq!(
+            serde_json::from_str::<Locale>("\"en\"").unwrap(),
+            Locale::En
+        );
+    }
+
+    #[test]
+    fn test_tf_fills_in_order() {
+        let filled = fill(Msg::ImportSummary.text(Locale::En), &[&3, &1, &0, &2]);
+
+        assert_eq!(
+            filled,
+            "Import finished: 3 new, 1 skipped (0 projects, 2 attachments)"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Exportação JSON do Bitwarden (sem criptografia).
+//!
+//! Logins, notas seguras e cartões são importados, com URIs e campos
+//! personalizados. A pasta do item define o projeto; sem pasta, é usada a
+//! primeira coleção da organização.
+
+use super::{is_blank, text, ImportedItem, ParsedImport};
+use crate::custom_fields::CustomFieldType;
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{CardPayload, NotePayload, SecretPayload};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const LOGIN: u8 = 1;
+const SECURE_NOTE: u8 = 2;
+const CARD: u8 = 3;
+
+const FIELD_HIDDEN: u8 = 1;
+const FIELD_LINKED: u8 = 3;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Vec<Named>,
+    #[serde(default)]
+    collections: Vec<Named>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default)]
+    name: String,
+    folder_id: Option<String>,
+    collection_ids: Option<Vec<String>>,
+    notes: Option<String>,
+    fields: Option<Vec<Field>>,
+    login: Option<Login>,
+    card: Option<Card>,
+    attachments: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: u8,
+}
+
+#[derive(Deserialize)]
+struct Login {
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+    uris: Option<Vec<Uri>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    cardholder_name: Option<String>,
+    number: Option<String>,
+    exp_month: Option<String>,
+    exp_year: Option<String>,
+    code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Uri {
+    uri: Option<String>,
+}
+
+/// Cartão com número e validade válidos; os demais ficam de fora.
+fn card_payload(card: &Card) -> Option<SecretPayload> {
+    let payload = SecretPayload::Card(CardPayload {
+        cardholder: card.cardholder_name.clone().unwrap_or_default(),
+        number: card.number.clone()?,
+        expiry_month: card.exp_month.as_deref()?.trim().parse().ok()?,
+        expiry_year: card.exp_year.as_deref()?.trim().parse().ok()?,
+        cvv: card.code.clone().filter(|c| !c.is_empty()),
+    });
+
+    payload.validate().ok().map(|()| payload)
+}
+
+fn kind_name(kind: u8) -> String {
+    match kind {
+        2 => "note".to_string(),
+        3 => "card".to_string(),
+        4 => "identity".to_string(),
+        5 => "ssh-key".to_string(),
+        other => other.to_string(),
+    }
+}
+
+pub fn parse(data: &[u8]) -> Result<ParsedImport> {
+    let export: Export = serde_json::from_str(text(data)?)
+        .map_err(|e| AppError::InvalidInput(format!("bitwarden: {}", e)))?;
+
+    if export.encrypted {
+        return Err(AppError::InvalidInput(
+            "bitwarden encrypted=true".to_string(),
+        ));
+    }
+
+    let folders: HashMap<&str, &str> = export
+        .folders
+        .iter()
+        .map(|f| (f.id.as_str(), f.name.as_str()))
+        .collect();
+    let collections: HashMap<&str, &str> = export
+        .collections
+        .iter()
+        .map(|c| (c.id.as_str(), c.name.as_str()))
+        .collect();
+
+    let mut parsed = ParsedImport::default();
+
+    for item in &export.items {
+        let mut unmapped = Vec::new();
+
+        let (login, payload) = match (item.kind, &item.login, &item.card) {
+            (LOGIN, Some(login), _) => (Some(login), None),
+            (SECURE_NOTE, _, _) if !is_blank(item.notes.as_deref()) => {
+                let content = item.notes.clone().unwrap_or_default();
+                (None, Some(SecretPayload::Note(NotePayload { content })))
+            }
+            (CARD, _, Some(card)) => match card_payload(card) {
+                Some(payload) => (None, Some(payload)),
+                None => {
+                    parsed.skip(&item.name, "invalid:card".to_string());
+                    continue;
+                }
+            },
+            _ => {
+                parsed.skip(&item.name, format!("type:{}", kind_name(item.kind)));
+                continue;
+            }
+        };
+
+        let project = item
+            .folder_id
+            .as_deref()
+            .and_then(|id| folders.get(id))
+            .or_else(|| {
+                item.collection_ids
+                    .iter()
+                    .flatten()
+                    .find_map(|id| collections.get(id.as_str()))
+            })
+            .map(|name| name.to_string());
+
+        let mut imported = ImportedItem {
+            title: item.name.clone(),
+            username: login.and_then(|l| l.username.clone()).unwrap_or_default(),
+            password: login.and_then(|l| l.password.clone()).unwrap_or_default(),
+            project,
+            payload,
+            fields: Vec::new(),
+            totp: None,
+        };
+
+        for uri in login.and_then(|l| l.uris.as_ref()).into_iter().flatten() {
+            imported.add_field(
+                "URL",
+                uri.uri.as_deref().unwrap_or_default(),
+                CustomFieldType::Url,
+            );
+        }
+        for field in item.fields.iter().flatten() {
+            // Campos "linked" apenas apontam para outro campo do item
+            if field.kind == FIELD_LINKED {
+                continue;
+            }
+
+            let field_type = if field.kind == FIELD_HIDDEN {
+                CustomFieldType::Hidden
+            } else {
+                CustomFieldType::Text
+            };
+            imported.add_field(
+                field.name.as_deref().unwrap_or_default(),
+                field.value.as_deref().unwrap_or_default(),
+                field_type,
+            );
+        }
+
+        // Numa nota segura as notas são o próprio conteúdo
+        if item.kind != SECURE_NOTE && !is_blank(item.notes.as_deref()) {
+            unmapped.push("notes".to_string());
+        }
+        if let Some(totp) = login
+            .and_then(|l| l.totp.as_deref())
+            .filter(|t| !t.trim().is_empty())
+        {
+            imported.set_totp(totp, &mut unmapped);
+        }
+        if item.attachments.as_ref().is_some_and(|a| !a.is_empty()) {
+            unmapped.push("attachments".to_string());
+        }
+
+        parsed.push(imported, unmapped);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_fields::CustomField;
+
+    #[test]
+    fn test_parse_bitwarden_export() {
+        let json = r#"{
+            "encrypted": false,
+            "folders": [{ "id": "f1", "name": "Trabalho" }],
+            "collections": [{ "id": "c1", "organizationId": "o1", "name": "Infra" }],
+            "items": [
+                {
+                    "id": "1", "type": 1, "name": "GitHub", "folderId": "f1",
+                    "notes": "conta principal", "favorite": false,
+                    "fields": [{ "name": "PIN", "value": "1234", "type": 1 }],
+                    "login": {
+                        "username": "eu", "password": "ghp",
+                        "totp": null, "uris": [{ "match": null, "uri": "https://github.com" }]
+                    }
+                },
+                {
+                    "id": "2", "type": 1, "name": "AWS", "folderId": null,
+                    "collectionIds": ["c1"],
+                    "login": { "username": "root", "password": "aws", "totp": "JBSWY3DP" }
+                },
+                { "id": "3", "type": 3, "name": "Cartão", "card": { "number": "4111" } },
+                {
+                    "id": "4", "type": 3, "name": "Visa",
+                    "card": {
+                        "cardholderName": "Fulano", "number": "4111111111111111",
+                        "expMonth": "7", "expYear": "2030", "code": "123"
+                    }
+                },
+                {
+                    "id": "5", "type": 2, "name": "Recuperação", "notes": "códigos",
+                    "secureNote": { "type": 0 }
+                },
+                { "id": "6", "type": 4, "name": "Eu", "identity": {} }
+            ]
+        }"#;
+
+        let parsed = parse(json.as_bytes()).unwrap();
+
+        assert_eq!(parsed.items.len(), 4);
+        assert_eq!(
+            parsed.items[..2],
+            [
+                ImportedItem {
+                    title: "GitHub".to_string(),
+                    username: "eu".to_string(),
+                    password: "ghp".to_string(),
+                    project: Some("Trabalho".to_string()),
+                    payload: None,
+                    fields: vec![
+                        CustomField::new("URL", "https://github.com", CustomFieldType::Url),
+                        CustomField {
+                            position: 1,
+                            ..CustomField::new("PIN", "1234", CustomFieldType::Hidden)
+                        },
+                    ],
+                    totp: None,
+                },
+                ImportedItem {
+                    title: "AWS".to_string(),
+                    username: "root".to_string(),
+                    password: "aws".to_string(),
+                    project: Some("Infra".to_string()),
+                    payload: None,
+                    fields: Vec::new(),
+                    totp: Some(
+                        "otpauth://totp/?secret=JBSWY3DP&algorithm=SHA1&digits=6&period=30"
+                            .to_string()
+                    ),
+                },
+            ]
+        );
+
+        assert!(matches!(
+            &parsed.items[2].payload,
+            Some(SecretPayload::Card(card)) if card.expiry_month == 7 && card.cvv.as_deref() == Some("123")
+        ));
+        assert_eq!(
+            parsed.items[3].payload,
+            Some(SecretPayload::Note(NotePayload {
+                content: "códigos".to_string()
+            }))
+        );
+
+        assert_eq!(parsed.unmapped.len(), 3);
+        assert_eq!(parsed.unmapped[0].fields, ["notes"]);
+        assert!(parsed.unmapped[1].skipped);
+        assert_eq!(parsed.unmapped[1].fields, ["invalid:card"]);
+        assert_eq!(parsed.unmapped[2].fields, ["type:identity"]);
+    }
+
+    #[test]
+    fn test_encrypted_export_is_rejected() {
+        let json = r#"{ "encrypted": true, "passwordProtected": true, "data": "..." }"#;
+
+        assert!(matches!(
+            parse(json.as_bytes()),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+//! CSV com mapeamento de colunas pelo nome do cabeçalho.
+//!
+//! Colunas que não estão no mapeamento (nem em `ignore`) e têm valor são
+//! listadas como `field:<coluna>` no relatório.
+
+use super::{text, ImportedItem, ParsedImport};
+use crate::error::{AppError, Result};
+use serde::Deserialize;
+
+/// Nomes das colunas no cabeçalho, sem diferenciar maiúsculas.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvMapping {
+    pub title: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    pub password: String,
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Separador; o padrão é vírgula
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Colunas que não devem aparecer no relatório
+    #[serde(default)]
+    pub ignore: Vec<String>,
+}
+
+impl CsvMapping {
+    /// Colunas do CSV exportado pelo 1Password 8.
+    pub fn one_password() -> Self {
+        Self {
+            title: "Title".to_string(),
+            username: Some("Username".to_string()),
+            password: "Password".to_string(),
+            project: None,
+            delimiter: None,
+            ignore: vec!["Favorite".to_string(), "Archived".to_string()],
+        }
+    }
+}
+
+fn invalid(e: impl std::fmt::Display) -> AppError {
+    AppError::InvalidInput(format!("csv: {}", e))
+}
+
+pub fn parse(data: &[u8], mapping: &CsvMapping) -> Result<ParsedImport> {
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err(AppError::InvalidInput(format!(
+            "csv.delimiter value={:?}",
+            delimiter
+        )));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .from_reader(text(data)?.as_bytes());
+
+    let headers = reader.headers().map_err(invalid)?.clone();
+    let column = |name: &str| -> Result<usize> {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| AppError::InvalidInput(format!("csv.column name={:?} missing", name)))
+    };
+
+    let title = column(&mapping.title)?;
+    let password = column(&mapping.password)?;
+    let username = mapping.username.as_deref().map(column).transpose()?;
+    let project = mapping.project.as_deref().map(column).transpose()?;
+
+    let mapped = [Some(title), Some(password), username, project];
+    let reported: Vec<(usize, &str)> = headers
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !mapped.contains(&Some(*idx)))
+        .filter(|(_, h)| {
+            !mapping
+                .ignore
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(h.trim()))
+        })
+        .collect();
+
+    let mut parsed = ParsedImport::default();
+
+    for record in reader.records() {
+        let record = record.map_err(invalid)?;
+        let get = |idx: Option<usize>| {
+            idx.and_then(|i| record.get(i))
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+
+        let item = ImportedItem {
+            title: get(Some(title)).trim().to_string(),
+            username: get(username),
+            password: get(Some(password)),
+            project: Some(get(project).trim().to_string()).filter(|p| !p.is_empty()),
+            payload: None,
+            fields: Vec::new(),
+            totp: None,
+        };
+
+        if item.title.is_empty() {
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            parsed.skip(&format!("#{}", line), "missing:title".to_string());
+            continue;
+        }
+
+        let unmapped = reported
+            .iter()
+            .filter(|(idx, _)| record.get(*idx).is_some_and(|v| !v.trim().is_empty()))
+            .map(|(_, header)| format!("field:{}", header.trim()))
+            .collect();
+
+        parsed.push(item, unmapped);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mapped_csv() {
+        let csv = "\u{feff}nome;login;senha;pasta;site\n\
+                   GitHub;eu;\"a;b\"\"c\";Trabalho;https://github.com\n\
+                   ;;;;\n\
+                   ;sem;titulo;;\n\
+                   Wi-Fi;;wifi;;\n";
+        let mapping = CsvMapping {
+            title: "Nome".to_string(),
+            username: Some("login".to_string()),
+            password: "senha".to_string(),
+            project: Some("pasta".to_string()),
+            delimiter: Some(';'),
+            ignore: Vec::new(),
+        };
+
+        let parsed = parse(csv.as_bytes(), &mapping).unwrap();
+
+        assert_eq!(parsed.items.len(), 2);
+        assert_eq!(parsed.items[0].password, "a;b\"c");
+        assert_eq!(parsed.items[0].project.as_deref(), Some("Trabalho"));
+        assert_eq!(parsed.items[1].project, None);
+
+        assert_eq!(parsed.unmapped[0].fields, ["field:site"]);
+        assert!(parsed.unmapped[1].skipped);
+        assert_eq!(parsed.unmapped[1].fields, ["missing:title"]);
+    }
+
+    #[test]
+    fn test_one_password_csv() {
+        let csv = "Title,Url,Username,Password,OTPAuth,Favorite,Archived,Tags,Notes\n\
+                   AWS,https://aws.amazon.com,root,aws,,false,false,,\n";
+
+        let parsed = parse(csv.as_bytes(), &CsvMapping::one_password()).unwrap();
+
+        assert_eq!(parsed.items[0].username, "root");
+        assert_eq!(parsed.unmapped[0].fields, ["field:Url"]);
+
+        let missing = CsvMapping {
+            title: "Nome".to_string(),
+            ..CsvMapping::one_password()
+        };
+        assert!(parse(csv.as_bytes(), &missing).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! XML exportado pelo KeePass 2.x ("KeePass XML (2.x)").
+//!
+//! Cada grupo vira um projeto com o caminho completo (`Trabalho/Servidores`);
+//! entradas no grupo raiz ficam sem projeto. A lixeira e o histórico das
+//! entradas são ignorados. A URL e as strings extras viram campos
+//! personalizados; strings protegidas na memória viram campos ocultos. A
+//! semente TOTP vem da string `otp` (KeePassXC) ou das `TimeOtp-*` (KeePass).
+
+use super::{is_blank, text, ImportedItem, ParsedImport};
+use crate::custom_fields::CustomFieldType;
+use crate::error::{AppError, Result};
+use roxmltree::{Document, Node};
+
+pub fn parse(data: &[u8]) -> Result<ParsedImport> {
+    let doc = Document::parse(text(data)?)
+        .map_err(|e| AppError::InvalidInput(format!("keepass: {}", e)))?;
+
+    let root = doc.root_element();
+    if !root.has_tag_name("KeePassFile") {
+        return Err(AppError::InvalidInput(format!(
+            "keepass root={:?} expected=KeePassFile",
+            root.tag_name().name()
+        )));
+    }
+
+    let recycle_bin = child(root, "Meta")
+        .and_then(|meta| child_text(meta, "RecycleBinUUID"))
+        .map(str::to_string);
+
+    let mut parsed = ParsedImport::default();
+
+    if let Some(group) = child(root, "Root").and_then(|r| child(r, "Group")) {
+        walk(group, None, recycle_bin.as_deref(), &mut parsed);
+    }
+
+    Ok(parsed)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|n| n.text())
+}
+
+fn walk(group: Node, project: Option<&str>, recycle_bin: Option<&str>, parsed: &mut ParsedImport) {
+    for node in group.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "Entry" => entry(node, project, parsed),
+            "Group" => {
+                if recycle_bin.is_some() && child_text(node, "UUID") == recycle_bin {
+                    continue;
+                }
+
+                let name = child_text(node, "Name").unwrap_or_default();
+                let path = match project {
+                    Some(parent) => format!("{}/{}", parent, name),
+                    None => name.to_string(),
+                };
+
+                walk(node, Some(&path), recycle_bin, parsed);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn entry(node: Node, project: Option<&str>, parsed: &mut ParsedImport) {
+    let mut item = ImportedItem {
+        title: String::new(),
+        username: String::new(),
+        password: String::new(),
+        project: project.map(str::to_string),
+        payload: None,
+        fields: Vec::new(),
+        totp: None,
+    };
+    let mut unmapped = Vec::new();
+    let mut otp = None;
+    let mut time_otp = Vec::new();
+
+    for string in node.children().filter(|n| n.has_tag_name("String")) {
+        let key = child_text(string, "Key").unwrap_or_default();
+        let value = child_text(string, "Value");
+        let protected = child(string, "Value")
+            .and_then(|v| v.attribute("ProtectInMemory"))
+            .is_some_and(|p| p.eq_ignore_ascii_case("true"));
+
+        match key {
+            "Title" => item.title = value.unwrap_or_default().to_string(),
+            "UserName" => item.username = value.unwrap_or_default().to_string(),
+            "Password" => item.password = value.unwrap_or_default().to_string(),
+            _ if is_blank(value) => {}
+            "URL" => item.add_field("URL", value.unwrap_or_default(), CustomFieldType::Url),
+            "Notes" => unmapped.push("notes".to_string()),
+            "otp" => otp = value,
+            "TimeOtp-Secret-Base32" => time_otp.push(("secret", value.unwrap_or_default())),
+            "TimeOtp-Algorithm" => {
+                let algorithm = value.unwrap_or_default();
+                time_otp.push(("algorithm", algorithm.trim_start_matches("HMAC-")));
+            }
+            "TimeOtp-Length" => time_otp.push(("digits", value.unwrap_or_default())),
+            "TimeOtp-Period" => time_otp.push(("period", value.unwrap_or_default())),
+            other => {
+                let field_type = if protected {
+                    CustomFieldType::Hidden
+                } else {
+                    CustomFieldType::Text
+                };
+                item.add_field(other, value.unwrap_or_default(), field_type);
+            }
+        }
+    }
+
+    // Os parâmetros das TimeOtp-* viram uma URI, lida como as demais
+    let time_otp_uri = time_otp.iter().any(|(name, _)| *name == "secret").then(|| {
+        let query: Vec<String> = time_otp
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value.trim()))
+            .collect();
+        format!("otpauth://totp/?{}", query.join("&"))
+    });
+    if let Some(totp) = otp.map(str::to_string).or(time_otp_uri) {
+        item.set_totp(&totp, &mut unmapped);
+    }
+
+    if node.children().any(|n| n.has_tag_name("Binary")) {
+        unmapped.push("attachments".to_string());
+    }
+
+    parsed.push(item, unmapped);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keepass_xml() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" standalone="yes"?>
+<KeePassFile>
+    <Meta>
+        <RecycleBinUUID>bGl4ZWlyYQ==</RecycleBinUUID>
+    </Meta>
+    <Root>
+        <Group>
+            <UUID>cmFpeg==</UUID>
+            <Name>Banco</Name>
+            <Entry>
+                <String><Key>Title</Key><Value>Wi-Fi</Value></String>
+                <String><Key>UserName</Key><Value /></String>
+                <String><Key>Password</Key><Value ProtectMemory="True">wifi &amp; casa</Value></String>
+                <String><Key>otp</Key><Value>otpauth://totp/roteador</Value></String>
+            </Entry>
+            <Group>
+                <UUID>dHJhYmFsaG8=</UUID>
+                <Name>Trabalho</Name>
+                <Group>
+                    <UUID>c2Vydg==</UUID>
+                    <Name>Servidores</Name>
+                    <Entry>
+                        <String><Key>Title</Key><Value>SSH</Value></String>
+                        <String><Key>UserName</Key><Value>root</Value></String>
+                        <String><Key>Password</Key><Value>toor</Value></String>
+                        <String><Key>URL</Key><Value>ssh://srv</Value></String>
+                        <String><Key>Porta</Key><Value>22</Value></String>
+                        <String><Key>Chave</Key><Value ProtectInMemory="True">abc</Value></String>
+                        <String><Key>TimeOtp-Secret-Base32</Key><Value>GEZDGNBVGY3TQOJQ</Value></String>
+                        <String><Key>TimeOtp-Algorithm</Key><Value>HMAC-SHA-256</Value></String>
+                        <String><Key>TimeOtp-Period</Key><Value>60</Value></String>
+                        <Binary><Key>id_rsa</Key><Value Ref="0" /></Binary>
+                        <History>
+                            <Entry>
+                                <String><Key>Title</Key><Value>SSH antigo</Value></String>
+                            </Entry>
+                        </History>
+                    </Entry>
+                </Group>
+            </Group>
+            <Group>
+                <UUID>bGl4ZWlyYQ==</UUID>
+                <Name>Lixeira</Name>
+                <Entry>
+                    <String><Key>Title</Key><Value>Apagado</Value></String>
+                </Entry>
+            </Group>
+        </Group>
+    </Root>
+</KeePassFile>"#;
+
+        let parsed = parse(xml.as_bytes()).unwrap();
+
+        assert_eq!(parsed.items.len(), 2);
+        assert_eq!(parsed.items[0].title, "Wi-Fi");
+        assert_eq!(parsed.items[0].password, "wifi & casa");
+        assert_eq!(parsed.items[0].project, None);
+        assert_eq!(parsed.items[1].title, "SSH");
+        assert_eq!(
+            parsed.items[1].project.as_deref(),
+            Some("Trabalho/Servidores")
+        );
+
+        let fields: Vec<(&str, CustomFieldType)> = parsed.items[1]
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("URL", CustomFieldType::Text),
+                ("Porta", CustomFieldType::Text),
+                ("Chave", CustomFieldType::Hidden)
+            ]
+        );
+
+        assert_eq!(parsed.items[0].totp, None);
+        assert_eq!(
+            parsed.items[1].totp.as_deref(),
+            Some("otpauth://totp/?secret=GEZDGNBVGY3TQOJQ&algorithm=SHA256&digits=6&period=60")
+        );
+
+        assert_eq!(parsed.unmapped.len(), 2);
+        assert_eq!(parsed.unmapped[0].fields, ["totp"]);
+        assert_eq!(parsed.unmapped[1].fields, ["attachments"]);
+    }
+}
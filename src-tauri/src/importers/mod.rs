@@ -0,0 +1,446 @@
+//! Importação de exportações de outros gerenciadores de senha.
+//!
+//! Cada formato é convertido em [`ImportedItem`]s com título, usuário, senha e
+//! projeto, ou com o payload dos tipos que têm equivalente (notas seguras e
+//! cartões). URLs e campos extras viram campos personalizados e sementes TOTP
+//! válidas vão para o segredo. Pastas, coleções, grupos e cofres de origem
+//! viram projetos. O que não tem onde ser guardado (notas, TOTP inválido,
+//! anexos e itens de outros tipos) volta em [`UnmappedItem`] para ser mostrado
+//! ao usuário.
+//!
+//! Os campos não mapeados usam identificadores estáveis, traduzidos pela
+//! interface: `url`, `notes`, `totp`, `attachments`, `field:<nome>` e, para
+//! itens inteiros não importados, `type:<tipo>`, `invalid:<tipo>` ou
+//! `missing:<campo>`.
+
+pub mod bitwarden;
+pub mod generic_csv;
+pub mod keepass;
+pub mod onepassword;
+
+pub use generic_csv::CsvMapping;
+
+use crate::custom_fields::{insert_fields, CustomField, CustomFieldType};
+use crate::database::{decrypt_column_string, encrypt_column, SECRET_PASSWORD_COLUMN};
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{decrypt_payload, encrypt_payload, SecretPayload};
+use crate::security::FIELD_ENCRYPTION_VERSION;
+use crate::totp::{store_totp, TotpKey};
+use crate::vault::Vault;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportFormat {
+    #[serde(rename = "bitwarden-json")]
+    BitwardenJson,
+    #[serde(rename = "keepass-xml")]
+    KeepassXml,
+    #[serde(rename = "1password-1pux")]
+    OnePassword1pux,
+    #[serde(rename = "1password-csv")]
+    OnePasswordCsv,
+    #[serde(rename = "csv")]
+    Csv,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 5] = [
+        ImportFormat::BitwardenJson,
+        ImportFormat::KeepassXml,
+        ImportFormat::OnePassword1pux,
+        ImportFormat::OnePasswordCsv,
+        ImportFormat::Csv,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormat::BitwardenJson => "bitwarden-json",
+            ImportFormat::KeepassXml => "keepass-xml",
+            ImportFormat::OnePassword1pux => "1password-1pux",
+            ImportFormat::OnePasswordCsv => "1password-csv",
+            ImportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|f| f.as_str()).collect();
+                AppError::InvalidInput(format!(
+                    "format value={:?} expected={}",
+                    value,
+                    names.join("|")
+                ))
+            })
+    }
+}
+
+/// Item pronto para virar um segredo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedItem {
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    pub project: Option<String>,
+    /// Dados do tipo, para itens que não são logins
+    pub payload: Option<SecretPayload>,
+    pub fields: Vec<CustomField>,
+    /// URI `otpauth://` normalizada
+    pub totp: Option<String>,
+}
+
+impl ImportedItem {
+    /// Acrescenta um campo personalizado, ignorando valores vazios. Nomes
+    /// repetidos ganham um sufixo numérico (`URL`, `URL 2`, ...).
+    fn add_field(&mut self, name: &str, value: &str, field_type: CustomFieldType) {
+        if value.trim().is_empty() {
+            return;
+        }
+
+        let base = match name.trim() {
+            "" => "Campo",
+            name => name,
+        };
+        let taken = |name: &str| {
+            self.fields
+                .iter()
+                .any(|f| f.name.eq_ignore_ascii_case(name))
+        };
+
+        let mut name = base.to_string();
+        let mut n = 2;
+        while taken(&name) {
+            name = format!("{} {}", base, n);
+            n += 1;
+        }
+
+        let mut field = CustomField::imported(&name, value, field_type);
+        field.position = self.fields.len() as i64;
+        self.fields.push(field);
+    }
+
+    /// Guarda a semente TOTP (URI `otpauth://` ou base32). Sementes que não
+    /// são lidas ficam de fora e o item é reportado com `totp`.
+    fn set_totp(&mut self, value: &str, unmapped: &mut Vec<String>) {
+        match TotpKey::parse(value) {
+            Ok(key) => self.totp = Some(key.to_uri().to_string()),
+            Err(_) => unmapped.push("totp".to_string()),
+        }
+    }
+}
+
+/// O que não foi importado de um item. Com `skipped`, o item inteiro ficou de
+/// fora e `fields` explica o motivo.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedItem {
+    pub title: String,
+    pub fields: Vec<String>,
+    pub skipped: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub items: Vec<ImportedItem>,
+    pub unmapped: Vec<UnmappedItem>,
+}
+
+impl ParsedImport {
+    fn push(&mut self, item: ImportedItem, unmapped_fields: Vec<String>) {
+        if !unmapped_fields.is_empty() {
+            self.unmapped.push(UnmappedItem {
+                title: item.title.clone(),
+                fields: unmapped_fields,
+                skipped: false,
+            });
+        }
+        self.items.push(item);
+    }
+
+    fn skip(&mut self, title: &str, reason: String) {
+        self.unmapped.push(UnmappedItem {
+            title: title.to_string(),
+            fields: vec![reason],
+            skipped: true,
+        });
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportSummary {
+    pub projects_inserted: usize,
+    pub projects_merged: usize,
+    pub secrets_inserted: usize,
+    pub secrets_skipped: usize,
+    pub unmapped: Vec<UnmappedItem>,
+}
+
+/// Lê o arquivo exportado. O CSV genérico exige o mapeamento das colunas.
+pub fn parse(
+    format: ImportFormat,
+    data: &[u8],
+    mapping: Option<&CsvMapping>,
+) -> Result<ParsedImport> {
+    match format {
+        ImportFormat::BitwardenJson => bitwarden::parse(data),
+        ImportFormat::KeepassXml => keepass::parse(data),
+        ImportFormat::OnePassword1pux => onepassword::parse_1pux(data),
+        ImportFormat::OnePasswordCsv => generic_csv::parse(data, &CsvMapping::one_password()),
+        ImportFormat::Csv => {
+            let mapping =
+                mapping.ok_or_else(|| AppError::InvalidInput("csv.mapping missing".to_string()))?;
+            generic_csv::parse(data, mapping)
+        }
+    }
+}
+
+/// Texto UTF-8 do arquivo, sem o BOM que algumas ferramentas gravam.
+fn text(data: &[u8]) -> Result<&str> {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| AppError::InvalidInput("encoding expected=utf-8".to_string()))?;
+
+    Ok(text.trim_start_matches('\u{feff}'))
+}
+
+fn is_blank(value: Option<&str>) -> bool {
+    value.is_none_or(|v| v.trim().is_empty())
+}
+
+impl Vault {
+    /// Grava os itens lidos numa única transação. Projetos com o mesmo nome de
+    /// um existente são reaproveitados, e segredos idênticos (título, usuário,
+    /// senha e payload) a um existente são ignorados, como na importação de
+    /// backup.
+    pub fn import_external(&mut self, parsed: ParsedImport) -> Result<ExternalImportSummary> {
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+        let mut summary = ExternalImportSummary::default();
+
+        // O payload entra na chave como JSON
+        let payload_key = |payload: Option<&SecretPayload>| {
+            payload.map(|p| serde_json::to_string(p).unwrap_or_default())
+        };
+
+        let mut existing: HashSet<(String, String, String, Option<String>)> = HashSet::new();
+        {
+            let mut stmt =
+                tx.prepare("SELECT id, title, username, password_blob, payload_blob FROM secrets")?;
+            let rows = stmt.query_map([], |row| {
+                let id: i64 = row.get(0)?;
+
+                Ok((
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    decrypt_column_string(row, 3, field_key, SECRET_PASSWORD_COLUMN, id)?,
+                    payload_key(decrypt_payload(row, 4, field_key, id)?.as_ref()),
+                ))
+            })?;
+
+            for r in rows {
+                existing.insert(r?);
+            }
+        }
+
+        let mut project_ids: HashMap<String, i64> = HashMap::new();
+        for item in parsed.items {
+            let key = (
+                item.title.clone(),
+                item.username.clone(),
+                item.password.clone(),
+                payload_key(item.payload.as_ref()),
+            );
+            if existing.contains(&key) {
+                summary.secrets_skipped += 1;
+                continue;
+            }
+
+            let project_id = match item.project.filter(|name| !name.trim().is_empty()) {
+                Some(name) => match project_ids.get(&name) {
+                    Some(id) => Some(*id),
+                    None => {
+                        let found: Option<i64> = tx
+                            .query_row(
+                                "SELECT id FROM projects WHERE name = ?1 ORDER BY deleted_at IS NOT NULL, id LIMIT 1",
+                                [&name],
+                                |row| row.get(0),
+                            )
+                            .optional()?;
+
+                        let id = match found {
+                            Some(id) => {
+                                summary.projects_merged += 1;
+                                id
+                            }
+                            None => {
+                                tx.execute("INSERT INTO projects (name) VALUES (?1)", [&name])?;
+                                summary.projects_inserted += 1;
+                                tx.last_insert_rowid()
+                            }
+                        };
+
+                        project_ids.insert(name, id);
+                        Some(id)
+                    }
+                },
+                None => None,
+            };
+
+            let kind = item
+                .payload
+                .as_ref()
+                .map(SecretPayload::kind)
+                .unwrap_or_default();
+
+            tx.execute(
+                "INSERT INTO secrets (title, username, project_id, kind, encryption_version) VALUES (?1, ?2, ?3, ?4, ?5)",
+                (
+                    &item.title,
+                    &item.username,
+                    project_id,
+                    kind.as_str(),
+                    FIELD_ENCRYPTION_VERSION,
+                ),
+            )?;
+
+            let id = tx.last_insert_rowid();
+            let password_blob = encrypt_column(
+                item.password.as_bytes(),
+                field_key,
+                SECRET_PASSWORD_COLUMN,
+                id,
+            )?;
+            let payload_blob = encrypt_payload(item.payload.as_ref(), field_key, id)?;
+            tx.execute(
+                "UPDATE secrets SET password_blob = ?1, payload_blob = ?2 WHERE id = ?3",
+                (password_blob, payload_blob, id),
+            )?;
+
+            insert_fields(&tx, field_key, id, &item.fields)?;
+            if let Some(totp) = &item.totp {
+                store_totp(&tx, field_key, id, Some(totp))?;
+            }
+
+            existing.insert(key);
+            summary.secrets_inserted += 1;
+        }
+
+        tx.commit()?;
+
+        summary.unmapped = parsed.unmapped;
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+
+    fn item(title: &str, password: &str, project: Option<&str>) -> ImportedItem {
+        ImportedItem {
+            title: title.to_string(),
+            username: "eu".to_string(),
+            password: password.to_string(),
+            project: project.map(str::to_string),
+            payload: None,
+            fields: Vec::new(),
+            totp: None,
+        }
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        for format in ImportFormat::ALL {
+            assert_eq!(format.as_str().parse::<ImportFormat>().unwrap(), format);
+        }
+        assert!("lastpass".parse::<ImportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_import_merges_projects_and_skips_duplicates() {
+        let dir = std::env::temp_dir().join("test_import_external");
+        let _ = std::fs::remove_dir_all(&dir);
+        let entry = VaultEntry {
+            id: "importers".to_string(),
+            name: "importers".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: String::new(),
+            external: false,
+        };
+
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+        vault.create_project("Trabalho", None).unwrap();
+        vault.create_secret("GitHub", "eu", "ghp").unwrap();
+
+        let mut aws = item("AWS", "aws", Some("Trabalho"));
+        aws.add_field("URL", "https://aws.amazon.com", CustomFieldType::Url);
+        aws.add_field("url", "console.aws", CustomFieldType::Url);
+        aws.add_field("Vazio", " ", CustomFieldType::Text);
+        let mut unmapped = Vec::new();
+        aws.set_totp(
+            "otpauth://totp/AWS:root?secret=GEZDGNBVGY3TQOJQ",
+            &mut unmapped,
+        );
+        aws.set_totp("otpauth://hotp/AWS?secret=GEZDGNBVGY3TQOJQ", &mut unmapped);
+        assert_eq!(unmapped, ["totp"]);
+
+        let parsed = ParsedImport {
+            items: vec![
+                item("GitHub", "ghp", Some("Trabalho")),
+                aws,
+                item("Banco", "1234", Some("Pessoal")),
+                item("Banco", "1234", Some("Pessoal")),
+                item("Wi-Fi", "wifi", None),
+            ],
+            unmapped: vec![UnmappedItem {
+                title: "AWS".to_string(),
+                fields: vec!["url".to_string()],
+                skipped: false,
+            }],
+        };
+
+        let summary = vault.import_external(parsed).unwrap();
+        assert_eq!(summary.projects_inserted, 1);
+        assert_eq!(summary.projects_merged, 1);
+        assert_eq!(summary.secrets_inserted, 3);
+        assert_eq!(summary.secrets_skipped, 2);
+        assert_eq!(summary.unmapped.len(), 1);
+
+        let projects = vault.projects(false).unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let secrets = vault.secret_summaries(false).unwrap();
+        let aws = secrets.iter().find(|s| s.title == "AWS").unwrap();
+        let aws_fields = vault.secret_fields(aws.id).unwrap();
+        let fields: Vec<(&str, CustomFieldType)> = aws_fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("URL", CustomFieldType::Url),
+                ("url 2", CustomFieldType::Text)
+            ]
+        );
+
+        let totp = vault.secret_totp(aws.id).unwrap().unwrap();
+        assert_eq!(totp.issuer.as_deref(), Some("AWS"));
+        assert_eq!(totp.account.as_deref(), Some("root"));
+        assert_eq!(vault.secret_totp(secrets[0].id).unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
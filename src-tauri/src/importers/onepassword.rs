@@ -0,0 +1,360 @@
+//! Exportação `.1pux` do 1Password 8: um zip com o JSON `export.data`.
+//!
+//! Logins, senhas e notas seguras são importados; o cofre do 1Password define
+//! o projeto. A URL e os campos das seções viram campos personalizados.
+//! O CSV do 1Password é lido por [`super::generic_csv`] com
+//! [`super::CsvMapping::one_password`].
+
+use super::{is_blank, text, ImportedItem, ParsedImport};
+use crate::custom_fields::CustomFieldType;
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{NotePayload, SecretPayload};
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+
+const LOGIN: &str = "001";
+const SECURE_NOTE: &str = "003";
+const PASSWORD: &str = "005";
+
+#[derive(Deserialize)]
+struct Export {
+    #[serde(default)]
+    accounts: Vec<Account>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    #[serde(default)]
+    vaults: Vec<VaultExport>,
+}
+
+#[derive(Deserialize)]
+struct VaultExport {
+    attrs: VaultAttrs,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct VaultAttrs {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    category_uuid: String,
+    overview: Overview,
+    details: Details,
+}
+
+#[derive(Deserialize)]
+struct Overview {
+    #[serde(default)]
+    title: String,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Details {
+    #[serde(default)]
+    login_fields: Vec<LoginField>,
+    notes_plain: Option<String>,
+    #[serde(default)]
+    sections: Vec<Section>,
+    password: Option<String>,
+    document_attributes: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginField {
+    designation: Option<String>,
+    name: Option<String>,
+    value: Option<String>,
+    field_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Section {
+    #[serde(default)]
+    fields: Vec<SectionField>,
+}
+
+#[derive(Deserialize)]
+struct SectionField {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    value: Value,
+}
+
+fn category_name(uuid: &str) -> String {
+    match uuid {
+        "002" => "card".to_string(),
+        "003" => "note".to_string(),
+        "004" => "identity".to_string(),
+        "006" => "document".to_string(),
+        "114" => "ssh-key".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Tipo e texto de um valor de seção, quando há um campo equivalente.
+fn section_field(value: &Value) -> Option<(CustomFieldType, String)> {
+    let (kind, inner) = value.as_object()?.iter().next()?;
+
+    let field_type = match kind.as_str() {
+        "concealed" => CustomFieldType::Hidden,
+        "url" => CustomFieldType::Url,
+        "email" => CustomFieldType::Email,
+        "date" => {
+            let date = chrono::DateTime::from_timestamp(inner.as_i64()?, 0)?;
+            return Some((CustomFieldType::Date, date.format("%Y-%m-%d").to_string()));
+        }
+        _ => CustomFieldType::Text,
+    };
+
+    let text = match inner {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        // E-mails mais recentes vêm como `{"email_address": ..., "provider": ...}`
+        Value::Object(map) => map.get("email_address")?.as_str()?.to_string(),
+        _ => return None,
+    };
+
+    Some((field_type, text))
+}
+
+/// Valores de seção vêm como `{"tipo": valor}`; vazio se o valor for nulo ou "".
+fn section_value_is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Object(map) => map.values().all(section_value_is_blank),
+        _ => false,
+    }
+}
+
+pub fn parse_1pux(data: &[u8]) -> Result<ParsedImport> {
+    let invalid = |e: &dyn std::fmt::Display| AppError::InvalidInput(format!("1pux: {}", e));
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(|e| invalid(&e))?;
+    let mut json = Vec::new();
+    archive
+        .by_name("export.data")
+        .map_err(|e| invalid(&e))?
+        .read_to_end(&mut json)?;
+
+    let export: Export = serde_json::from_str(text(&json)?).map_err(|e| invalid(&e))?;
+
+    let mut parsed = ParsedImport::default();
+
+    for vault in export.accounts.iter().flat_map(|a| &a.vaults) {
+        for item in &vault.items {
+            let title = &item.overview.title;
+            let details = &item.details;
+            let mut unmapped = Vec::new();
+
+            let mut payload = None;
+            let mut login_fields = Vec::new();
+            let (username, password) = match item.category_uuid.as_str() {
+                LOGIN => {
+                    let mut username = String::new();
+                    let mut password = String::new();
+
+                    for field in &details.login_fields {
+                        let value = field.value.clone().unwrap_or_default();
+                        match field.designation.as_deref() {
+                            Some("username") => username = value,
+                            Some("password") => password = value,
+                            _ => {
+                                let field_type = match field.field_type.as_deref() {
+                                    Some("P") => CustomFieldType::Hidden,
+                                    Some("E") => CustomFieldType::Email,
+                                    Some("U") => CustomFieldType::Url,
+                                    _ => CustomFieldType::Text,
+                                };
+                                login_fields.push((
+                                    field.name.clone().unwrap_or_default(),
+                                    value,
+                                    field_type,
+                                ));
+                            }
+                        }
+                    }
+
+                    (username, password)
+                }
+                PASSWORD => (String::new(), details.password.clone().unwrap_or_default()),
+                SECURE_NOTE if !is_blank(details.notes_plain.as_deref()) => {
+                    let content = details.notes_plain.clone().unwrap_or_default();
+                    payload = Some(SecretPayload::Note(NotePayload { content }));
+                    (String::new(), String::new())
+                }
+                other => {
+                    parsed.skip(title, format!("type:{}", category_name(other)));
+                    continue;
+                }
+            };
+
+            let mut imported = ImportedItem {
+                title: title.clone(),
+                username,
+                password,
+                project: Some(vault.attrs.name.clone()),
+                payload,
+                fields: Vec::new(),
+                totp: None,
+            };
+
+            imported.add_field(
+                "URL",
+                item.overview.url.as_deref().unwrap_or_default(),
+                CustomFieldType::Url,
+            );
+            for (name, value, field_type) in login_fields {
+                imported.add_field(&name, &value, field_type);
+            }
+
+            if imported.payload.is_none() && !is_blank(details.notes_plain.as_deref()) {
+                unmapped.push("notes".to_string());
+            }
+            for field in details.sections.iter().flat_map(|s| &s.fields) {
+                if section_value_is_blank(&field.value) {
+                    continue;
+                }
+
+                if field.value.get("file").is_some() {
+                    unmapped.push("attachments".to_string());
+                } else if field.value.get("totp").is_some() || field.id.starts_with("TOTP_") {
+                    let value = field.value.get("totp").and_then(Value::as_str);
+                    imported.set_totp(value.unwrap_or_default(), &mut unmapped);
+                } else if let Some((field_type, value)) = section_field(&field.value) {
+                    imported.add_field(&field.title, &value, field_type);
+                } else {
+                    unmapped.push(format!("field:{}", field.title));
+                }
+            }
+            if details.document_attributes.is_some() {
+                unmapped.push("attachments".to_string());
+            }
+            unmapped.dedup();
+
+            parsed.push(imported, unmapped);
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn pux(export_data: &str) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            zip.start_file(
+                "export.attributes",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.write_all(br#"{"version":3}"#).unwrap();
+            zip.start_file("export.data", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(export_data.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_parse_1pux() {
+        let data = pux(r#"{
+            "accounts": [{
+                "attrs": { "name": "Equipe" },
+                "vaults": [{
+                    "attrs": { "uuid": "v1", "name": "Infra" },
+                    "items": [
+                        {
+                            "uuid": "a", "categoryUuid": "001", "state": "active",
+                            "overview": { "title": "GitHub", "url": "https://github.com" },
+                            "details": {
+                                "loginFields": [
+                                    { "designation": "username", "name": "login", "value": "eu" },
+                                    { "designation": "password", "name": "senha", "value": "ghp" }
+                                ],
+                                "notesPlain": "",
+                                "sections": [{
+                                    "title": "",
+                                    "fields": [
+                                        { "title": "one-time password", "id": "TOTP_1", "value": { "totp": "otpauth://totp/x" } },
+                                        { "title": "Recuperação", "id": "r", "value": { "concealed": "abc" } },
+                                        { "title": "Contato", "id": "e", "value": { "email": { "email_address": "eu@example.com", "provider": null } } },
+                                        { "title": "Desde", "id": "d", "value": { "date": 1704067200 } },
+                                        { "title": "Endereço", "id": "a", "value": { "address": { "city": "X" } } }
+                                    ]
+                                }]
+                            }
+                        },
+                        {
+                            "uuid": "b", "categoryUuid": "005",
+                            "overview": { "title": "Wi-Fi" },
+                            "details": { "password": "wifi" }
+                        },
+                        {
+                            "uuid": "c", "categoryUuid": "003",
+                            "overview": { "title": "Nota" },
+                            "details": { "notesPlain": "texto" }
+                        },
+                        {
+                            "uuid": "d", "categoryUuid": "004",
+                            "overview": { "title": "Eu" },
+                            "details": {}
+                        }
+                    ]
+                }]
+            }]
+        }"#);
+
+        let parsed = parse_1pux(&data).unwrap();
+
+        assert_eq!(parsed.items.len(), 3);
+        assert_eq!(parsed.items[0].username, "eu");
+        assert_eq!(parsed.items[0].password, "ghp");
+        assert_eq!(parsed.items[0].project.as_deref(), Some("Infra"));
+        assert_eq!(parsed.items[1].password, "wifi");
+        assert_eq!(
+            parsed.items[2].payload,
+            Some(SecretPayload::Note(NotePayload {
+                content: "texto".to_string()
+            }))
+        );
+
+        let fields: Vec<(&str, Option<&str>, CustomFieldType)> = parsed.items[0]
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_deref(), f.field_type))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("URL", Some("https://github.com"), CustomFieldType::Url),
+                ("Recuperação", Some("abc"), CustomFieldType::Hidden),
+                ("Contato", Some("eu@example.com"), CustomFieldType::Email),
+                ("Desde", Some("2024-01-01"), CustomFieldType::Date),
+            ]
+        );
+        assert_eq!(parsed.unmapped[0].fields, ["totp", "field:Endereço"]);
+        assert!(parsed.unmapped[1].skipped);
+        assert_eq!(parsed.unmapped[1].fields, ["type:identity"]);
+    }
+}
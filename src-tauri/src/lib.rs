@@ -1,54 +1,133 @@
 mod commands;
 
+pub mod backup;
+pub mod breach;
+pub mod custom_fields;
 pub mod database;
+pub mod dotenv;
+pub mod error;
+pub mod exporters;
+pub mod generator;
+pub mod health;
+pub mod i18n;
+pub mod importers;
 pub mod models;
+pub mod project_env;
+pub mod query;
+pub mod registry;
+pub mod search;
+pub mod secret_history;
+pub mod secret_kinds;
 pub mod security;
+pub mod settings;
 pub mod state;
+pub mod strength;
+pub mod totp;
+pub mod trash_retention;
 pub mod utils;
-
-use commands::{attachments, projects, secrets, trash, vaults};
+pub mod vault;
 
 use state::AppState;
 use std::sync::Mutex;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // O idioma precisa estar definido antes de qualquer mensagem ser gerada
+    settings::apply_saved_locale();
+
     tauri::Builder::default()
         .manage(AppState {
-            db: Mutex::new(None),
+            vault: Mutex::new(None),
         })
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_os::init())
+        .setup(|app| {
+            // Com o cofre aberto, a lixeira vencida é excluída periodicamente
+            let handle = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(trash_retention::PURGE_INTERVAL);
+                let purge = handle.state::<AppState>().purge_expired_trash();
+                if let Some(result) = purge.transpose() {
+                    commands::trash::report_purge(&handle, result);
+                }
+            });
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
-            vaults::unlock_vault,
-            vaults::setup_vault,
-            vaults::check_vault_status,
-            vaults::lock_vault,
-            vaults::export_vault,
-            vaults::import_vault,
-            secrets::get_all_secrets,
-            secrets::get_deleted_secrets,
-            secrets::create_secret,
-            secrets::soft_delete_secret,
-            secrets::delete_secret,
-            secrets::restore_secret,
-            secrets::update_secret,
-            attachments::add_attachment,
-            attachments::get_attachments_metadata,
-            attachments::get_attachment_content,
-            attachments::delete_attachment,
-            projects::create_project,
-            projects::get_all_projects,
-            projects::get_deleted_projects,
-            projects::get_project,
-            projects::update_project,
-            projects::soft_delete_project,
-            projects::delete_project,
-            projects::restore_project,
-            trash::empty_trash,
+            commands::vaults::unlock_vault,
+            commands::vaults::setup_vault,
+            commands::vaults::check_vault_status,
+            commands::vaults::list_vaults,
+            commands::vaults::create_vault,
+            commands::vaults::open_vault,
+            commands::vaults::open_vault_at,
+            commands::vaults::rename_vault,
+            commands::vaults::delete_vault,
+            commands::vaults::lock_vault,
+            commands::vaults::change_master_password,
+            commands::vaults::export_vault,
+            commands::vaults::import_vault,
+            commands::vaults::import_external,
+            commands::vaults::export_plaintext,
+            commands::secrets::list_secrets,
+            commands::secrets::list_deleted_secrets,
+            commands::secrets::reveal_secret_field,
+            commands::secrets::get_secret_fields,
+            commands::secrets::reveal_custom_field,
+            commands::secrets::create_secret,
+            commands::secrets::soft_delete_secret,
+            commands::secrets::delete_secret,
+            commands::secrets::restore_secret,
+            commands::secrets::update_secret,
+            commands::secrets::update_secret_payload,
+            commands::secrets::set_secret_project,
+            commands::secrets::get_secret_versions,
+            commands::secrets::compare_secret_versions,
+            commands::secrets::restore_secret_version,
+            commands::secrets::get_history_limit,
+            commands::secrets::set_history_limit,
+            commands::secrets::set_secret_totp,
+            commands::secrets::get_secret_totp,
+            commands::secrets::get_totp_code,
+            commands::attachments::add_attachment,
+            commands::attachments::get_attachments_metadata,
+            commands::attachments::get_attachment_content,
+            commands::attachments::delete_attachment,
+            commands::projects::create_project,
+            commands::projects::get_all_projects,
+            commands::projects::get_deleted_projects,
+            commands::projects::get_project,
+            commands::projects::update_project,
+            commands::projects::soft_delete_project,
+            commands::projects::delete_project,
+            commands::projects::restore_project,
+            commands::projects::get_project_env_vars,
+            commands::projects::get_project_env_bindings,
+            commands::projects::set_project_env_var,
+            commands::projects::remove_project_env_var,
+            commands::projects::import_project_dotenv,
+            commands::projects::export_project_dotenv,
+            commands::trash::empty_trash,
+            commands::trash::get_trash_retention,
+            commands::trash::set_trash_retention,
+            commands::trash::purge_expired_trash,
+            commands::search::search,
+            commands::generator::generate_secret,
+            commands::generator::get_generator_policy,
+            commands::generator::set_generator_policy,
+            commands::health::vault_health_report,
+            commands::health::get_password_max_age,
+            commands::health::set_password_max_age,
+            commands::health::check_breached_passwords,
+            commands::settings::get_settings,
+            commands::settings::get_locale,
+            commands::settings::set_locale,
+            commands::settings::set_vaults_dir,
         ])
         .on_page_load(|webview, _payload| {
             // Desabilita menu de contexto apenas em produção
@@ -1,6 +1,8 @@
+use crate::custom_fields::CustomField;
+use crate::secret_kinds::{SecretKind, SecretPayload};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Project {
     pub id: i64,
@@ -8,9 +10,12 @@ pub struct Project {
     pub description: Option<String>,
     pub created_at: String,
     pub deleted_at: Option<String>,
+    /// Quando sai da lixeira (ver `trash_retention`)
+    #[serde(default)]
+    pub purge_at: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Secret {
     pub id: i64,
@@ -20,6 +25,61 @@ pub struct Secret {
     pub password: String,
     pub created_at: String,
     pub deleted_at: Option<String>,
+    #[serde(default)]
+    pub kind: SecretKind,
+    /// Dados do tipo; `None` para logins
+    #[serde(default)]
+    pub payload: Option<SecretPayload>,
+    #[serde(default)]
+    pub fields: Vec<CustomField>,
+    /// Última troca de senha; `None` enquanto for a da criação (ver `health`)
+    #[serde(default)]
+    pub password_changed_at: Option<String>,
+    /// A senha aparece na base local do HIBP (ver `breach`)
+    #[serde(default)]
+    pub breached: bool,
+    /// Vezes que a senha aparece na base; `None` enquanto não foi verificada
+    #[serde(default)]
+    pub breach_count: Option<i64>,
+    /// Quando sai da lixeira (ver `trash_retention`)
+    #[serde(default)]
+    pub purge_at: Option<String>,
+}
+
+/// Segredo sem nenhum valor sensível, usado nas listagens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretSummary {
+    pub id: i64,
+    pub title: String,
+    pub username: String,
+    pub project_id: Option<i64>,
+    pub created_at: String,
+    pub deleted_at: Option<String>,
+    #[serde(default)]
+    pub kind: SecretKind,
+    #[serde(default)]
+    pub breached: bool,
+    #[serde(default)]
+    pub purge_at: Option<String>,
+}
+
+/// Campos sensíveis que podem ser revelados individualmente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretField {
+    Password,
+    /// Dados estruturados dos tipos que não são login, em JSON
+    Payload,
+}
+
+impl SecretField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretField::Password => "password",
+            SecretField::Payload => "payload",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,3 +103,39 @@ pub struct Attachment {
     pub file_size: i64,
     pub content: Vec<u8>,
 }
+
+/// Cofre do registro, como é exibido na tela de seleção.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultInfo {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    /// O arquivo meta existe, ou seja, o cofre já foi criado em disco
+    pub initialized: bool,
+    /// O cofre é o que está aberto no momento
+    pub unlocked: bool,
+    /// Cofre aberto de um caminho fora da pasta de cofres
+    pub external: bool,
+}
+
+/// Nome de variável de ambiente configurado para um segredo de um projeto.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEnvVar {
+    pub id: i64,
+    pub project_id: i64,
+    pub secret_title: String,
+    pub env_name: String,
+}
+
+/// Variável que um segredo do projeto vai gerar no `secrets-cli run`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvBinding {
+    pub secret_id: i64,
+    pub secret_title: String,
+    pub env_name: String,
+    /// `false` quando o nome foi derivado do título
+    pub configured: bool,
+}
@@ -0,0 +1,328 @@
+//! Variáveis de ambiente dos projetos, usadas pelo `secrets-cli run`.
+//!
+//! Cada segredo ativo de um projeto vira uma variável cujo valor é a senha
+//! (ou, nos tipos que não são login, o valor principal do payload).
+//! O nome vem de `project_env_vars` ou, sem configuração, do título do segredo
+//! (ex: "GitHub Token" -> `GITHUB_TOKEN`).
+
+use crate::database::{decrypt_column_string, SECRET_PASSWORD_COLUMN};
+use crate::error::{AppError, Result};
+use crate::models::{EnvBinding, Project, ProjectEnvVar};
+use crate::secret_kinds::decrypt_payload;
+use crate::vault::Vault;
+use rusqlite::OptionalExtension;
+use std::collections::HashMap;
+use zeroize::Zeroizing;
+
+/// Nome derivado do título: letras e números em maiúsculas, o resto vira `_`.
+pub fn default_env_name(title: &str) -> String {
+    let mut name = String::new();
+
+    for c in title.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_uppercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+
+    let name = name.trim_matches('_');
+
+    if name.is_empty() {
+        "SECRET".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Aceita apenas nomes portáveis: `[A-Za-z_][A-Za-z0-9_]*`.
+pub fn validate_env_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid {
+        return Err(AppError::InvalidInput(format!("env_name value={:?}", name)));
+    }
+
+    Ok(())
+}
+
+impl Vault {
+    /// Procura um projeto ativo pelo id ou pelo nome (sem diferenciar
+    /// maiúsculas). Nomes repetidos precisam ser indicados pelo id.
+    pub fn find_project(&self, id_or_name: &str) -> Result<Project> {
+        let projects = self.projects(false)?;
+
+        if let Ok(id) = id_or_name.parse::<i64>() {
+            if let Some(project) = projects.iter().find(|p| p.id == id) {
+                return Ok(project.clone());
+            }
+        }
+
+        let matches: Vec<Project> = projects
+            .into_iter()
+            .filter(|p| p.name.eq_ignore_ascii_case(id_or_name.trim()))
+            .collect();
+
+        match matches.as_slice() {
+            [] => Err(AppError::NotFound(format!("project name={:?}", id_or_name))),
+            [only] => Ok(only.clone()),
+            _ => Err(AppError::Conflict(format!(
+                "project name={:?} count={}",
+                id_or_name,
+                matches.len()
+            ))),
+        }
+    }
+
+    pub fn env_vars(&self, project_id: i64) -> Result<Vec<ProjectEnvVar>> {
+        let mut stmt = self.conn().prepare(
+            "SELECT id, project_id, secret_title, env_name FROM project_env_vars WHERE project_id = ?1 ORDER BY secret_title",
+        )?;
+
+        let vars = stmt
+            .query_map([project_id], |row| {
+                Ok(ProjectEnvVar {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    secret_title: row.get(2)?,
+                    env_name: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(vars)
+    }
+
+    /// Define o nome da variável para os segredos com esse título no projeto.
+    pub fn set_env_var(
+        &self,
+        project_id: i64,
+        secret_title: &str,
+        env_name: &str,
+    ) -> Result<ProjectEnvVar> {
+        validate_env_name(env_name)?;
+
+        let taken_by: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT secret_title FROM project_env_vars WHERE project_id = ?1 AND env_name = ?2 AND secret_title <> ?3",
+                (project_id, env_name, secret_title),
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(other) = taken_by {
+            return Err(AppError::Conflict(format!(
+                "env_name value={} secret={:?}",
+                env_name, other
+            )));
+        }
+
+        self.conn().execute(
+            "INSERT INTO project_env_vars (project_id, secret_title, env_name) VALUES (?1, ?2, ?3)
+             ON CONFLICT(project_id, secret_title) DO UPDATE SET env_name = excluded.env_name",
+            (project_id, secret_title, env_name),
+        )?;
+
+        let var = self.conn().query_row(
+            "SELECT id FROM project_env_vars WHERE project_id = ?1 AND secret_title = ?2",
+            (project_id, secret_title),
+            |row| {
+                Ok(ProjectEnvVar {
+                    id: row.get(0)?,
+                    project_id,
+                    secret_title: secret_title.to_string(),
+                    env_name: env_name.to_string(),
+                })
+            },
+        )?;
+
+        Ok(var)
+    }
+
+    /// Volta a usar o nome derivado do título.
+    pub fn remove_env_var(&self, project_id: i64, secret_title: &str) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM project_env_vars WHERE project_id = ?1 AND secret_title = ?2",
+            (project_id, secret_title),
+        )?;
+
+        Ok(())
+    }
+
+    /// Variáveis que os segredos ativos do projeto vão gerar, sem os valores.
+    /// Falha se dois segredos resultarem no mesmo nome.
+    pub fn env_bindings(&self, project_id: i64) -> Result<Vec<EnvBinding>> {
+        let configured: HashMap<String, String> = self
+            .env_vars(project_id)?
+            .into_iter()
+            .map(|var| (var.secret_title, var.env_name))
+            .collect();
+
+        let mut stmt = self.conn().prepare(
+            "SELECT id, title FROM secrets WHERE project_id = ?1 AND deleted_at IS NULL ORDER BY id",
+        )?;
+
+        let bindings = stmt
+            .query_map([project_id], |row| {
+                let secret_title: String = row.get(1)?;
+                let env_name = configured.get(&secret_title).cloned();
+
+                Ok(EnvBinding {
+                    secret_id: row.get(0)?,
+                    configured: env_name.is_some(),
+                    env_name: env_name.unwrap_or_else(|| default_env_name(&secret_title)),
+                    secret_title,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut seen: HashMap<&str, &str> = HashMap::new();
+        for binding in &bindings {
+            if let Some(other) = seen.insert(&binding.env_name, &binding.secret_title) {
+                return Err(AppError::Conflict(format!(
+                    "env_name value={} secrets={:?}|{:?}",
+                    binding.env_name, other, binding.secret_title
+                )));
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    /// Decifra as variáveis do projeto para injetar num processo filho. Cada
+    /// segredo lido é registrado no `audit_log` como 'inject'.
+    pub fn project_environment(
+        &mut self,
+        project_id: i64,
+    ) -> Result<Vec<(String, Zeroizing<String>)>> {
+        self.read_environment(project_id, "inject")
+    }
+
+    /// Decifra as variáveis do projeto registrando `audit_action` no
+    /// `audit_log` para cada segredo lido.
+    pub(crate) fn read_environment(
+        &mut self,
+        project_id: i64,
+        audit_action: &str,
+    ) -> Result<Vec<(String, Zeroizing<String>)>> {
+        let bindings = self.env_bindings(project_id)?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let mut env = Vec::with_capacity(bindings.len());
+        for binding in bindings {
+            let (value, field) = tx.query_row(
+                "SELECT password_blob, payload_blob FROM secrets WHERE id = ?1",
+                [binding.secret_id],
+                |row| match decrypt_payload(row, 1, field_key, binding.secret_id)? {
+                    Some(payload) => Ok((payload.primary_value(), "payload")),
+                    None => Ok((
+                        decrypt_column_string(
+                            row,
+                            0,
+                            field_key,
+                            SECRET_PASSWORD_COLUMN,
+                            binding.secret_id,
+                        )?,
+                        "password",
+                    )),
+                },
+            )?;
+
+            tx.execute(
+                "INSERT INTO audit_log (action, secret_id, field, created_at) VALUES (?1, ?2, ?3, ?4)",
+                (audit_action, binding.secret_id, field, &now),
+            )?;
+
+            env.push((binding.env_name, Zeroizing::new(value)));
+        }
+
+        tx.commit()?;
+
+        Ok(env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+
+    #[test]
+    fn test_default_env_name() {
+        assert_eq!(default_env_name("GitHub Token"), "GITHUB_TOKEN");
+        assert_eq!(default_env_name("  aws / secret-key "), "AWS_SECRET_KEY");
+        assert_eq!(default_env_name("2fa backup"), "_2FA_BACKUP");
+        assert_eq!(default_env_name("çã!"), "SECRET");
+
+        assert!(validate_env_name("DATABASE_URL").is_ok());
+        assert!(validate_env_name("_X1").is_ok());
+        assert!(validate_env_name("1X").is_err());
+        assert!(validate_env_name("A-B").is_err());
+        assert!(validate_env_name("").is_err());
+    }
+
+    #[test]
+    fn test_project_environment_uses_mapping() {
+        let dir = std::env::temp_dir().join("test_project_environment");
+        let _ = std::fs::remove_dir_all(&dir);
+        let entry = VaultEntry {
+            id: "env".to_string(),
+            name: "env".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: String::new(),
+            external: false,
+        };
+
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+        let project_id = vault.create_project("API", None).unwrap();
+        assert_eq!(vault.find_project("api").unwrap().id, project_id);
+
+        for (title, password) in [("GitHub Token", "ghp_1"), ("Banco", "postgres://")] {
+            let secret = vault.create_secret(title, "", password).unwrap();
+            vault
+                .set_secret_project(secret.id, Some(project_id))
+                .unwrap();
+        }
+
+        vault
+            .set_env_var(project_id, "Banco", "DATABASE_URL")
+            .unwrap();
+        assert!(matches!(
+            vault.set_env_var(project_id, "Outro", "DATABASE_URL"),
+            Err(AppError::Conflict(_))
+        ));
+
+        let env = vault.project_environment(project_id).unwrap();
+        let env: Vec<(&str, &str)> = env
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            env,
+            vec![("GITHUB_TOKEN", "ghp_1"), ("DATABASE_URL", "postgres://")]
+        );
+
+        // Mapeamento que colide com o nome derivado de outro segredo
+        vault
+            .set_env_var(project_id, "Banco", "GITHUB_TOKEN")
+            .unwrap();
+        assert!(matches!(
+            vault.env_bindings(project_id),
+            Err(AppError::Conflict(_))
+        ));
+
+        vault.remove_env_var(project_id, "Banco").unwrap();
+        assert_eq!(vault.env_bindings(project_id).unwrap()[1].env_name, "BANCO");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
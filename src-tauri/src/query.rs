@@ -0,0 +1,317 @@
+//! Paginação, ordenação e filtros comuns aos comandos de listagem.
+//!
+//! A paginação é por cursor (keyset): cada página devolve `next_cursor`, que
+//! guarda a chave de ordenação e o id do último item. A próxima consulta
+//! continua depois dele com `WHERE (chave, id) > (...)`, sem `OFFSET`, então o
+//! custo por página não cresce com o tamanho do cofre. Sem `limit`, a
+//! listagem devolve tudo, como antes.
+//!
+//! Datas são comparadas via `datetime()` do SQLite, que entende tanto o
+//! `CURRENT_TIMESTAMP` das colunas `created_at` quanto o RFC 3339 gravado
+//! pelo Rust. Faixas são `[from, to)`.
+
+use crate::error::{AppError, Result};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortField {
+    Id,
+    /// Título do segredo, nome do projeto ou nome do arquivo
+    Title,
+    Username,
+    CreatedAt,
+    DeletedAt,
+    /// Tamanho do anexo
+    Size,
+}
+
+impl SortField {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortField::Id => "id",
+            SortField::Title => "title",
+            SortField::Username => "username",
+            SortField::CreatedAt => "createdAt",
+            SortField::DeletedAt => "deletedAt",
+            SortField::Size => "size",
+        }
+    }
+}
+
+impl FromStr for SortField {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        [
+            SortField::Id,
+            SortField::Title,
+            SortField::Username,
+            SortField::CreatedAt,
+            SortField::DeletedAt,
+            SortField::Size,
+        ]
+        .into_iter()
+        .find(|field| field.as_str() == value)
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "sort value={:?} expected=id|title|username|createdAt|deletedAt|size",
+                value
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueryOptions {
+    /// Itens por página. Sem limite, devolve tudo.
+    pub limit: Option<usize>,
+    /// `next_cursor` da página anterior
+    pub cursor: Option<String>,
+    /// Sem `sort`, cada listagem usa a sua ordem padrão.
+    pub sort: Option<SortField>,
+    pub direction: Option<SortDirection>,
+    pub project_id: Option<i64>,
+    pub created_from: Option<String>,
+    pub created_to: Option<String>,
+    pub deleted_from: Option<String>,
+    pub deleted_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor para a próxima página; `None` na última.
+    pub next_cursor: Option<String>,
+}
+
+/// Como uma tabela é listada: colunas, chaves de ordenação aceitas e quais
+/// filtros fazem sentido nela.
+pub(crate) struct ListSpec {
+    pub table: &'static str,
+    /// Colunas lidas pelo `map` da listagem, a partir do índice 0
+    pub columns: &'static [&'static str],
+    /// Expressão SQL de cada chave de ordenação aceita
+    pub sorts: &'static [(SortField, &'static str)],
+    pub default_sort: (SortField, SortDirection),
+    /// Condição do filtro por projeto, com um `?`
+    pub project_filter: Option<&'static str>,
+    pub has_deleted_at: bool,
+}
+
+/// Uma página de `spec` filtrada por `conditions` (SQL com `?`, e os valores)
+/// e pelas `options`.
+pub(crate) fn query_page<T>(
+    conn: &Connection,
+    spec: &ListSpec,
+    conditions: &[(&str, Vec<Value>)],
+    options: &QueryOptions,
+    mut map: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> Result<Page<T>> {
+    let (sort, direction) = match options.sort {
+        Some(sort) => (sort, options.direction.unwrap_or_default()),
+        None => (
+            spec.default_sort.0,
+            options.direction.unwrap_or(spec.default_sort.1),
+        ),
+    };
+    let key = spec
+        .sorts
+        .iter()
+        .find(|(field, _)| *field == sort)
+        .map(|(_, expr)| *expr)
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("sort value={} table={}", sort.as_str(), spec.table))
+        })?;
+
+    let mut clauses: Vec<String> = Vec::new();
+    let mut params: Vec<Value> = Vec::new();
+
+    for (sql, values) in conditions {
+        clauses.push(sql.to_string());
+        params.extend(values.iter().cloned());
+    }
+
+    if let Some(project_id) = options.project_id {
+        let filter = spec
+            .project_filter
+            .ok_or_else(|| AppError::InvalidInput(format!("projectId table={}", spec.table)))?;
+        clauses.push(filter.to_string());
+        params.push(Value::Integer(project_id));
+    }
+
+    let mut ranges = vec![
+        ("created_at", &options.created_from, ">="),
+        ("created_at", &options.created_to, "<"),
+    ];
+    if spec.has_deleted_at {
+        ranges.push(("deleted_at", &options.deleted_from, ">="));
+        ranges.push(("deleted_at", &options.deleted_to, "<"));
+    } else if options.deleted_from.is_some() || options.deleted_to.is_some() {
+        return Err(AppError::InvalidInput(format!(
+            "deletedFrom|deletedTo table={}",
+            spec.table
+        )));
+    }
+    for (column, bound, op) in ranges {
+        if let Some(bound) = bound {
+            clauses.push(format!("datetime({}) {} ?", column, op));
+            params.push(Value::Text(normalize_date(bound)?));
+        }
+    }
+
+    let (cmp, order) = match direction {
+        SortDirection::Asc => (">", "ASC"),
+        SortDirection::Desc => ("<", "DESC"),
+    };
+    if let Some(cursor) = &options.cursor {
+        let (value, id) = decode_cursor(cursor)?;
+        clauses.push(format!(
+            "({key} {cmp} ? OR ({key} = ? AND id {cmp} ?))",
+            key = key,
+            cmp = cmp
+        ));
+        params.extend([value.clone(), value, Value::Integer(id)]);
+    }
+
+    if options.limit == Some(0) {
+        return Err(AppError::InvalidInput("limit value=0".to_string()));
+    }
+
+    let mut sql = format!(
+        "SELECT {}, {} AS sort_key, id AS sort_id FROM {}",
+        spec.columns.join(", "),
+        key,
+        spec.table
+    );
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+    sql.push_str(&format!(
+        " ORDER BY sort_key {order}, id {order}",
+        order = order
+    ));
+    if let Some(limit) = options.limit {
+        // Um item a mais diz se existe próxima página
+        sql.push_str(" LIMIT ?");
+        params.push(Value::Integer(limit as i64 + 1));
+    }
+
+    let key_index = spec.columns.len();
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(params), |row| {
+        Ok((
+            map(row)?,
+            row.get::<_, Value>(key_index)?,
+            row.get::<_, i64>(key_index + 1)?,
+        ))
+    })?;
+
+    let mut items = Vec::new();
+    let mut keys = Vec::new();
+    for row in rows {
+        let (item, key, id) = row?;
+        items.push(item);
+        keys.push((key, id));
+    }
+
+    let mut next_cursor = None;
+    if let Some(limit) = options.limit {
+        if items.len() > limit {
+            items.truncate(limit);
+            let (key, id) = keys.swap_remove(limit - 1);
+            next_cursor = Some(encode_cursor(key, id)?);
+        }
+    }
+
+    Ok(Page { items, next_cursor })
+}
+
+/// Aceita RFC 3339, `AAAA-MM-DD HH:MM:SS` ou só a data, e devolve no formato
+/// de `datetime()` (UTC).
+fn normalize_date(value: &str) -> Result<String> {
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    let value = value.trim();
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&chrono::Utc).format(FORMAT).to_string());
+    }
+    if let Ok(date) = chrono::NaiveDateTime::parse_from_str(value, FORMAT) {
+        return Ok(date.format(FORMAT).to_string());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(format!("{} 00:00:00", date));
+    }
+
+    Err(AppError::InvalidInput(format!("date value={:?}", value)))
+}
+
+fn encode_cursor(key: Value, id: i64) -> Result<String> {
+    let key = match key {
+        Value::Integer(n) => serde_json::Value::from(n),
+        Value::Real(n) => serde_json::Value::from(n),
+        Value::Text(s) => serde_json::Value::from(s),
+        Value::Null => serde_json::Value::Null,
+        Value::Blob(_) => return Err(AppError::Internal("cursor.sort_key type=blob".to_string())),
+    };
+
+    let json = serde_json::to_vec(&(key, id)).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(hex::encode(json))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(Value, i64)> {
+    let invalid = || AppError::InvalidInput("cursor".to_string());
+
+    let json = hex::decode(cursor).map_err(|_| invalid())?;
+    let (key, id): (serde_json::Value, i64) =
+        serde_json::from_slice(&json).map_err(|_| invalid())?;
+
+    let key = match key {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::String(s) => Value::Text(s),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(n) => Value::Integer(n),
+            None => Value::Real(n.as_f64().ok_or_else(invalid)?),
+        },
+        _ => return Err(invalid()),
+    };
+
+    Ok((key, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip_and_dates() {
+        let cursor = encode_cursor(Value::Text("GitHub".into()), 42).unwrap();
+        assert_eq!(
+            decode_cursor(&cursor).unwrap(),
+            (Value::Text("GitHub".into()), 42)
+        );
+        assert!(decode_cursor("zz").is_err());
+
+        assert_eq!(
+            normalize_date("2026-10-18T12:00:00-03:00").unwrap(),
+            "2026-10-18 15:00:00"
+        );
+        assert_eq!(normalize_date("2026-10-18").unwrap(), "2026-10-18 00:00:00");
+        assert!(normalize_date("18/10/2026").is_err());
+    }
+}
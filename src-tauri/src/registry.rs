@@ -0,0 +1,500 @@
+use crate::error::{AppError, Result};
+use crate::utils::{read_vault_meta, write_atomic};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+pub const REGISTRY_VERSION: u32 = 1;
+
+/// Id usado para o cofre único das versões antigas, que fica direto em
+/// `vaults/vault.db` e `vaults/vault.meta`.
+pub const LEGACY_VAULT_ID: &str = "default";
+pub const DEFAULT_VAULT_NAME: &str = "Principal";
+
+const DB_FILE: &str = "vault.db";
+const META_FILE: &str = "vault.meta";
+const SQLITE_PLAIN_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Um cofre registrado. Cada cofre tem seu próprio par banco/meta.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultEntry {
+    pub id: String,
+    pub name: String,
+    pub db_path: PathBuf,
+    pub meta_path: PathBuf,
+    pub created_at: String,
+    /// Cofre aberto de um caminho qualquer (`open_vault_at`). Excluir um
+    /// cofre externo apenas o remove do registro, sem apagar os arquivos.
+    #[serde(default)]
+    pub external: bool,
+}
+
+impl VaultEntry {
+    /// Apaga os arquivos do cofre. Um cofre criado pelo aplicativo tem uma
+    /// pasta só dele (`<id>/`), removida inteira com os temporários que tenham
+    /// sobrado (`.rekey`, `-journal`, `.tmp`). O cofre antigo divide a pasta
+    /// com os demais e perde só os próprios arquivos. Cofres externos não são
+    /// tocados.
+    pub fn remove_files(&self) -> Result<()> {
+        if self.external {
+            return Ok(());
+        }
+
+        if let Some(dir) = self.own_dir() {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+            return Ok(());
+        }
+
+        for path in [&self.db_path, &self.meta_path] {
+            for suffix in ["", ".rekey", "-journal", ".tmp"] {
+                let mut name = path.as_os_str().to_owned();
+                name.push(suffix);
+
+                let file = PathBuf::from(name);
+                if file.exists() {
+                    fs::remove_file(file)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn own_dir(&self) -> Option<&Path> {
+        let dir = self.db_path.parent()?;
+        let owned = self.id != LEGACY_VAULT_ID
+            && dir.file_name()? == self.id.as_str()
+            && self.meta_path.parent() == Some(dir);
+
+        owned.then_some(dir)
+    }
+}
+
+/// Lista de cofres conhecidos, guardada em `vaults/registry.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRegistry {
+    pub version: u32,
+    #[serde(default)]
+    pub vaults: Vec<VaultEntry>,
+    /// Último cofre aberto, usado quando nenhum cofre é indicado
+    #[serde(default)]
+    pub last_opened: Option<String>,
+}
+
+impl Default for VaultRegistry {
+    fn default() -> Self {
+        Self {
+            version: REGISTRY_VERSION,
+            vaults: Vec::new(),
+            last_opened: None,
+        }
+    }
+}
+
+impl VaultRegistry {
+    /// Lê o registro. Sem arquivo, começa vazio e adota o cofre antigo de
+    /// `vaults_dir`, se houver um.
+    pub fn load(path: &Path, vaults_dir: &Path) -> Result<Self> {
+        if path.exists() {
+            let content = fs::read_to_string(path)?;
+            let registry: Self = serde_json::from_str(&content)
+                .map_err(|e| AppError::InvalidInput(e.to_string()))?;
+
+            if registry.version > REGISTRY_VERSION {
+                return Err(AppError::UnsupportedVersion(format!(
+                    "registry version={}",
+                    registry.version
+                )));
+            }
+
+            return Ok(registry);
+        }
+
+        let mut registry = Self::default();
+
+        let legacy = VaultEntry {
+            id: LEGACY_VAULT_ID.to_string(),
+            name: DEFAULT_VAULT_NAME.to_string(),
+            db_path: vaults_dir.join(DB_FILE),
+            meta_path: vaults_dir.join(META_FILE),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+        if legacy.meta_path.exists() {
+            registry.last_opened = Some(legacy.id.clone());
+            registry.vaults.push(legacy);
+        }
+
+        Ok(registry)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| AppError::Internal(e.to_string()))?;
+        write_atomic(path, json.as_bytes())
+    }
+
+    pub fn get(&self, id: &str) -> Result<&VaultEntry> {
+        self.vaults
+            .iter()
+            .find(|v| v.id == id)
+            .ok_or(AppError::VaultNotFound)
+    }
+
+    /// Procura um cofre pelo id ou pelo nome (sem diferenciar maiúsculas).
+    pub fn find(&self, id_or_name: &str) -> Result<&VaultEntry> {
+        self.vaults
+            .iter()
+            .find(|v| v.id == id_or_name)
+            .or_else(|| {
+                self.vaults
+                    .iter()
+                    .find(|v| v.name.eq_ignore_ascii_case(id_or_name.trim()))
+            })
+            .ok_or(AppError::VaultNotFound)
+    }
+
+    /// Escolhe o cofre pedido; sem id, usa o último aberto ou o único existente.
+    pub fn resolve(&self, id: Option<&str>) -> Result<&VaultEntry> {
+        if let Some(id) = id {
+            return self.get(id);
+        }
+
+        if let Some(last) = self.last_opened.as_deref() {
+            if let Ok(entry) = self.get(last) {
+                return Ok(entry);
+            }
+        }
+
+        match self.vaults.as_slice() {
+            [only] => Ok(only),
+            [] => Err(AppError::VaultNotFound),
+            vaults => Err(AppError::InvalidInput(format!(
+                "vault missing count={}",
+                vaults.len()
+            ))),
+        }
+    }
+
+    /// Monta a entrada de um cofre novo em `vaults_dir/<id>/`, sem registrá-lo.
+    /// O cofre só entra no registro depois que os arquivos forem criados.
+    pub fn new_entry(&self, name: &str, vaults_dir: &Path) -> Result<VaultEntry> {
+        let name = self.validate_name(name, None)?;
+
+        let id = hex::encode(rand::random::<[u8; 8]>());
+        let dir = vaults_dir.join(&id);
+
+        Ok(VaultEntry {
+            id,
+            name,
+            db_path: dir.join(DB_FILE),
+            meta_path: dir.join(META_FILE),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        })
+    }
+
+    /// Registra um cofre de fora da pasta de cofres. Se o par já estiver
+    /// registrado, devolve a entrada existente.
+    pub fn register_external(&mut self, db_path: &Path, meta_path: &Path) -> VaultEntry {
+        if let Some(entry) = self
+            .vaults
+            .iter()
+            .find(|v| v.db_path == db_path && v.meta_path == meta_path)
+        {
+            return entry.clone();
+        }
+
+        // Nome sugerido a partir da pasta do cofre, sem colidir com os existentes
+        let base = db_path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| DEFAULT_VAULT_NAME.to_string());
+        let mut name = base.clone();
+        let mut counter = 2;
+        while self.validate_name(&name, None).is_err() {
+            name = format!("{} ({})", base, counter);
+            counter += 1;
+        }
+
+        let entry = VaultEntry {
+            id: hex::encode(rand::random::<[u8; 8]>()),
+            name,
+            db_path: db_path.to_path_buf(),
+            meta_path: meta_path.to_path_buf(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: true,
+        };
+        self.vaults.push(entry.clone());
+
+        entry
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<()> {
+        let name = self.validate_name(name, Some(id))?;
+
+        let entry = self
+            .vaults
+            .iter_mut()
+            .find(|v| v.id == id)
+            .ok_or(AppError::VaultNotFound)?;
+        entry.name = name;
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<VaultEntry> {
+        let index = self
+            .vaults
+            .iter()
+            .position(|v| v.id == id)
+            .ok_or(AppError::VaultNotFound)?;
+
+        if self.last_opened.as_deref() == Some(id) {
+            self.last_opened = None;
+        }
+
+        Ok(self.vaults.remove(index))
+    }
+
+    fn validate_name(&self, name: &str, ignore_id: Option<&str>) -> Result<String> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("vault.name empty".to_string()));
+        }
+
+        let taken = self
+            .vaults
+            .iter()
+            .any(|v| Some(v.id.as_str()) != ignore_id && v.name.eq_ignore_ascii_case(name));
+        if taken {
+            return Err(AppError::Conflict(format!("vault.name value={:?}", name)));
+        }
+
+        Ok(name.to_string())
+    }
+}
+
+/// Descobre o par banco/meta a partir de uma pasta, do `vault.db` ou do
+/// `vault.meta`. O outro arquivo do par deve estar ao lado, com o mesmo nome.
+pub fn locate_vault_pair(path: &Path) -> Result<(PathBuf, PathBuf)> {
+    if path.is_dir() {
+        return Ok((path.join(DB_FILE), path.join(META_FILE)));
+    }
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("db") => Ok((path.to_path_buf(), path.with_extension("meta"))),
+        Some("meta") => Ok((path.with_extension("db"), path.to_path_buf())),
+        _ => Err(AppError::InvalidInput(format!(
+            "vault_path path={:?} expected=dir|.db|.meta",
+            path
+        ))),
+    }
+}
+
+/// Confere, antes de pedir a senha, se o par banco/meta parece ser um cofre:
+/// os dois arquivos existem, o meta é válido e o banco está cifrado.
+pub fn validate_vault_pair(db_path: &Path, meta_path: &Path) -> Result<()> {
+    for path in [db_path, meta_path] {
+        if !path.is_file() {
+            return Err(AppError::InvalidInput(format!(
+                "vault_path path={:?} missing",
+                path
+            )));
+        }
+    }
+
+    read_vault_meta(meta_path)?;
+
+    // Um banco SQLCipher não tem o cabeçalho em texto puro do SQLite
+    let mut header = [0u8; 16];
+    let read = File::open(db_path)?.read(&mut header)?;
+    if read < header.len() {
+        return Err(AppError::InvalidInput(format!(
+            "vault_db path={:?} header=truncated",
+            db_path
+        )));
+    }
+    if &header == SQLITE_PLAIN_HEADER {
+        return Err(AppError::InvalidInput(format!(
+            "vault_db path={:?} header=plaintext",
+            db_path
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_registry_adopts_legacy_vault() {
+        let dir = temp_dir("test_registry_legacy");
+        let registry_path = dir.join("registry.json");
+
+        assert!(VaultRegistry::load(&registry_path, &dir)
+            .unwrap()
+            .vaults
+            .is_empty());
+
+        fs::write(dir.join(META_FILE), "salt").unwrap();
+        let registry = VaultRegistry::load(&registry_path, &dir).unwrap();
+
+        let entry = registry.resolve(None).unwrap();
+        assert_eq!(entry.id, LEGACY_VAULT_ID);
+        assert_eq!(entry.db_path, dir.join(DB_FILE));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_remove_files() {
+        let dir = temp_dir("test_registry_remove_files");
+        let registry = VaultRegistry::default();
+
+        let entry = registry.new_entry("Trabalho", &dir).unwrap();
+        let vault_dir = entry.db_path.parent().unwrap();
+        fs::create_dir_all(vault_dir).unwrap();
+        for name in [DB_FILE, META_FILE, "vault.db.rekey", "vault.db-journal"] {
+            fs::write(vault_dir.join(name), "x").unwrap();
+        }
+
+        // O cofre antigo fica direto na pasta de cofres
+        let legacy = VaultEntry {
+            id: LEGACY_VAULT_ID.to_string(),
+            db_path: dir.join(DB_FILE),
+            meta_path: dir.join(META_FILE),
+            ..entry.clone()
+        };
+        for name in [DB_FILE, META_FILE, "vault.meta.tmp", "registry.json"] {
+            fs::write(dir.join(name), "x").unwrap();
+        }
+
+        legacy.remove_files().unwrap();
+        let mut left: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(left, [entry.id.as_str(), "registry.json"]);
+
+        entry.remove_files().unwrap();
+        assert!(!vault_dir.exists());
+        assert!(dir.join("registry.json").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        let dir = temp_dir("test_registry_names");
+        let registry_path = dir.join("registry.json");
+        let mut registry = VaultRegistry::default();
+
+        let work = registry.new_entry("Trabalho", &dir).unwrap();
+        registry.vaults.push(work.clone());
+        let personal = registry.new_entry("  Pessoal ", &dir).unwrap();
+        assert_eq!(personal.name, "Pessoal");
+        assert_ne!(personal.db_path.parent(), work.db_path.parent());
+        registry.vaults.push(personal.clone());
+
+        assert!(matches!(
+            registry.new_entry("trabalho", &dir),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            registry.rename(&personal.id, "TRABALHO"),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            registry.new_entry("   ", &dir),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        // Renomear para o próprio nome (mudando só a caixa) é permitido
+        registry.rename(&work.id, "trabalho").unwrap();
+        assert_eq!(registry.find("TRABALHO").unwrap().id, work.id);
+        assert_eq!(registry.find(&personal.id).unwrap().name, "Pessoal");
+
+        // Com mais de um cofre e nenhum aberto antes, é preciso escolher
+        assert!(registry.resolve(None).is_err());
+        registry.last_opened = Some(personal.id.clone());
+        assert_eq!(registry.resolve(None).unwrap().id, personal.id);
+
+        registry.save(&registry_path).unwrap();
+        let mut loaded = VaultRegistry::load(&registry_path, &dir).unwrap();
+        assert_eq!(loaded, registry);
+
+        loaded.remove(&personal.id).unwrap();
+        assert!(loaded.last_opened.is_none());
+        assert_eq!(loaded.resolve(None).unwrap().name, "trabalho");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_locate_and_validate_vault_pair() {
+        let dir = temp_dir("test_registry_pair");
+        let db_path = dir.join(DB_FILE);
+        let meta_path = dir.join(META_FILE);
+
+        assert_eq!(
+            locate_vault_pair(&dir).unwrap(),
+            (db_path.clone(), meta_path.clone())
+        );
+        assert_eq!(
+            locate_vault_pair(&meta_path).unwrap(),
+            (db_path.clone(), meta_path.clone())
+        );
+        assert!(locate_vault_pair(&dir.join("cofre.txt")).is_err());
+
+        // Falta o meta
+        fs::write(&db_path, [7u8; 64]).unwrap();
+        assert!(validate_vault_pair(&db_path, &meta_path).is_err());
+
+        // Meta inválido
+        fs::write(&meta_path, r#"{"version": 1}"#).unwrap();
+        assert!(validate_vault_pair(&db_path, &meta_path).is_err());
+
+        fs::write(
+            &meta_path,
+            crate::security::VaultMeta::generate().to_json().unwrap(),
+        )
+        .unwrap();
+        validate_vault_pair(&db_path, &meta_path).unwrap();
+
+        // Banco SQLite sem cifra
+        let mut plain = SQLITE_PLAIN_HEADER.to_vec();
+        plain.extend_from_slice(&[0u8; 48]);
+        fs::write(&db_path, plain).unwrap();
+        assert!(validate_vault_pair(&db_path, &meta_path).is_err());
+
+        // Registrar o mesmo par duas vezes não duplica a entrada
+        let mut registry = VaultRegistry::default();
+        let first = registry.register_external(&db_path, &meta_path);
+        let again = registry.register_external(&db_path, &meta_path);
+        assert!(first.external);
+        assert_eq!(first.id, again.id);
+        assert_eq!(registry.vaults.len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,342 @@
+//! Busca de texto completo sobre segredos e projetos.
+//!
+//! Usa os índices FTS5 `secrets_fts` e `projects_fts` (migração 010), que os
+//! gatilhos do banco mantêm em dia dentro da mesma transação de cada alteração.
+//! Só textos que já ficam em claro são indexados: título, usuário, projeto,
+//! nomes de anexos e campos personalizados não ocultos. Senhas, payloads e
+//! campos ocultos ficam de fora.
+
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{kind_from_row, SecretKind};
+use crate::vault::Vault;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Resultados devolvidos quando o filtro não define `limit`.
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Pesos do bm25 por coluna de `secrets_fts`: título, usuário, URLs, notas,
+/// projeto e anexos.
+const SECRET_WEIGHTS: &str = "10.0, 4.0, 3.0, 1.0, 2.0, 2.0";
+
+/// Pesos de `projects_fts`: nome e descrição.
+const PROJECT_WEIGHTS: &str = "10.0, 1.0";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashFilter {
+    /// Só itens fora da lixeira
+    #[default]
+    Active,
+    /// Só itens na lixeira
+    Deleted,
+    All,
+}
+
+impl TrashFilter {
+    fn sql(self) -> &'static str {
+        match self {
+            TrashFilter::Active => "deleted_at IS NULL",
+            TrashFilter::Deleted => "deleted_at IS NOT NULL",
+            TrashFilter::All => "1 = 1",
+        }
+    }
+}
+
+impl FromStr for TrashFilter {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "active" => Ok(TrashFilter::Active),
+            "deleted" => Ok(TrashFilter::Deleted),
+            "all" => Ok(TrashFilter::All),
+            _ => Err(AppError::InvalidInput(format!(
+                "trash value={:?} expected=active|deleted|all",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchFilters {
+    /// Só segredos deste projeto (exclui os projetos do resultado)
+    pub project_id: Option<i64>,
+    /// Só segredos deste tipo (exclui os projetos do resultado)
+    pub kind: Option<SecretKind>,
+    pub trash: TrashFilter,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEntity {
+    Secret,
+    Project,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub entity: SearchEntity,
+    pub id: i64,
+    /// Título do segredo ou nome do projeto
+    pub title: String,
+    pub username: Option<String>,
+    pub kind: Option<SecretKind>,
+    pub project_id: Option<i64>,
+    pub deleted_at: Option<String>,
+    /// Relevância do resultado; maior é melhor.
+    pub score: f64,
+}
+
+/// Converte o texto digitado numa expressão FTS5: cada palavra vira um termo
+/// entre aspas com prefixo (`"git"*`), e todos precisam aparecer. Devolve
+/// `None` se não sobrar nenhuma palavra.
+pub fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+impl Vault {
+    /// Busca segredos e projetos pelo texto, do mais para o menos relevante.
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Result<Vec<SearchHit>> {
+        let Some(expression) = match_expression(query) else {
+            return Ok(Vec::new());
+        };
+        let limit = filters.limit.unwrap_or(DEFAULT_SEARCH_LIMIT) as i64;
+
+        let mut hits = Vec::new();
+
+        let mut stmt = self.conn().prepare(&format!(
+            "SELECT s.id, s.title, s.username, s.kind, s.project_id, s.deleted_at, bm25(secrets_fts, {}) AS rank
+             FROM secrets_fts JOIN secrets s ON s.id = secrets_fts.rowid
+             WHERE secrets_fts MATCH ?1
+               AND (?2 IS NULL OR s.project_id = ?2)
+               AND (?3 IS NULL OR s.kind = ?3)
+               AND {}
+             ORDER BY rank LIMIT ?4",
+            SECRET_WEIGHTS,
+            filters.trash.sql()
+        ))?;
+
+        let rows = stmt.query_map(
+            (
+                &expression,
+                filters.project_id,
+                filters.kind.map(SecretKind::as_str),
+                limit,
+            ),
+            |row| {
+                Ok(SearchHit {
+                    entity: SearchEntity::Secret,
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    username: row.get(2)?,
+                    kind: Some(kind_from_row(row, 3)?),
+                    project_id: row.get(4)?,
+                    deleted_at: row.get(5)?,
+                    score: -row.get::<_, f64>(6)?,
+                })
+            },
+        )?;
+        for row in rows {
+            hits.push(row?);
+        }
+
+        if filters.project_id.is_none() && filters.kind.is_none() {
+            let mut stmt = self.conn().prepare(&format!(
+                "SELECT p.id, p.name, p.deleted_at, bm25(projects_fts, {}) AS rank
+                 FROM projects_fts JOIN projects p ON p.id = projects_fts.rowid
+                 WHERE projects_fts MATCH ?1 AND {}
+                 ORDER BY rank LIMIT ?2",
+                PROJECT_WEIGHTS,
+                filters.trash.sql()
+            ))?;
+
+            let rows = stmt.query_map((&expression, limit), |row| {
+                Ok(SearchHit {
+                    entity: SearchEntity::Project,
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    username: None,
+                    kind: None,
+                    project_id: Some(row.get(0)?),
+                    deleted_at: row.get(2)?,
+                    score: -row.get::<_, f64>(3)?,
+                })
+            })?;
+            for row in rows {
+                hits.push(row?);
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit as usize);
+
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_fields::{CustomField, CustomFieldType};
+    use crate::registry::VaultEntry;
+    use crate::secret_kinds::{NotePayload, SecretPayload};
+    use std::fs;
+
+    fn temp_vault(name: &str) -> (Vault, VaultEntry) {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+
+        (Vault::create(&entry, "senha").unwrap(), entry)
+    }
+
+    fn ids(hits: &[SearchHit], entity: SearchEntity) -> Vec<i64> {
+        hits.iter()
+            .filter(|hit| hit.entity == entity)
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_match_expression() {
+        assert_eq!(
+            match_expression("git hub").as_deref(),
+            Some("\"git\"* \"hub\"*")
+        );
+        assert_eq!(
+            match_expression("api.exemplo.com/\"x\"").as_deref(),
+            Some("\"api\"* \"exemplo\"* \"com\"* \"x\"*")
+        );
+        assert_eq!(match_expression("  -- "), None);
+    }
+
+    #[test]
+    fn test_search_indexes_metadata_but_not_values() {
+        let (mut vault, entry) = temp_vault("test_search_index");
+
+        let project = vault
+            .create_project("Pagamentos", Some("Gateway de cartões"))
+            .unwrap();
+
+        let github = vault
+            .create_typed_secret(
+                "GitHub",
+                "octocat",
+                "segredo_super",
+                None,
+                &[
+                    CustomField::new("Site", "https://github.com/login", CustomFieldType::Url),
+                    CustomField::new("PIN", "oculto123", CustomFieldType::Hidden),
+                ],
+            )
+            .unwrap();
+        let stripe = vault
+            .create_typed_secret(
+                "Stripe",
+                "financeiro",
+                "sk_live",
+                Some(&SecretPayload::Note(NotePayload {
+                    content: "conteudo reservado".into(),
+                })),
+                &[],
+            )
+            .unwrap();
+        vault.set_secret_project(stripe.id, Some(project)).unwrap();
+        vault
+            .add_attachment(github.id, "recovery-codes.txt", "text/plain", b"x")
+            .unwrap();
+
+        let search = |vault: &Vault, query: &str| vault.search(query, &SearchFilters::default());
+
+        // Prefixo, acentos e campos de URL e anexos
+        assert_eq!(
+            ids(&search(&vault, "octo").unwrap(), SearchEntity::Secret),
+            [github.id]
+        );
+        assert_eq!(
+            ids(&search(&vault, "github.com").unwrap(), SearchEntity::Secret),
+            [github.id]
+        );
+        assert_eq!(
+            ids(&search(&vault, "recovery").unwrap(), SearchEntity::Secret),
+            [github.id]
+        );
+        let hits = search(&vault, "cartoes").unwrap();
+        assert_eq!(ids(&hits, SearchEntity::Project), [project]);
+
+        // O nome do projeto encontra o projeto e os segredos dele, o projeto primeiro
+        let hits = search(&vault, "pagamentos").unwrap();
+        assert_eq!(hits[0].entity, SearchEntity::Project);
+        assert_eq!(ids(&hits, SearchEntity::Secret), [stripe.id]);
+
+        // Valores protegidos nunca entram no índice
+        for value in ["segredo", "oculto123", "sk_live", "reservado"] {
+            assert!(search(&vault, value).unwrap().is_empty(), "{}", value);
+        }
+
+        // Filtros por tipo, projeto e lixeira
+        let by_kind = SearchFilters {
+            kind: Some(SecretKind::Note),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(
+                &vault.search("stripe", &by_kind).unwrap(),
+                SearchEntity::Secret
+            ),
+            [stripe.id]
+        );
+        assert!(vault.search("github", &by_kind).unwrap().is_empty());
+
+        vault.soft_delete_secret(github.id).unwrap();
+        assert!(search(&vault, "github").unwrap().is_empty());
+        let in_trash = SearchFilters {
+            trash: TrashFilter::Deleted,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(
+                &vault.search("github", &in_trash).unwrap(),
+                SearchEntity::Secret
+            ),
+            [github.id]
+        );
+
+        // O índice acompanha renomeações e exclusões
+        vault.update_project(project, "Cobrança", None).unwrap();
+        assert!(search(&vault, "pagamentos").unwrap().is_empty());
+        assert_eq!(
+            ids(&search(&vault, "cobranca").unwrap(), SearchEntity::Secret),
+            [stripe.id]
+        );
+
+        vault.delete_secret(github.id).unwrap();
+        let indexed: i64 = vault
+            .conn()
+            .query_row("SELECT count(*) FROM secrets_fts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(indexed, 1);
+
+        drop(vault);
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+}
@@ -0,0 +1,605 @@
+//! Histórico de versões dos segredos.
+//!
+//! Toda alteração de um segredo (título, usuário, senha, payload, campos ou
+//! semente TOTP) guarda antes o estado anterior em `secret_versions`, como
+//! JSON cifrado em `snapshot_blob` com a mesma proteção da senha. O número de
+//! versões por segredo é limitado pela configuração `history_limit` do cofre;
+//! com limite zero o histórico fica desligado.
+//!
+//! Na comparação entre versões, a senha, a semente TOTP, os campos ocultos e
+//! as partes sensíveis do payload aparecem mascarados: a interface sabe que
+//! mudaram, mas não vê os valores. Para recuperar um valor antigo, restaure a
+//! versão.
+
+use crate::custom_fields::{insert_fields, load_fields, CustomField, CustomFieldType};
+use crate::database::{
+    decrypt_column, decrypt_column_string, encrypt_column, set_vault_setting, vault_setting,
+    SECRET_PASSWORD_COLUMN, SECRET_VERSION_COLUMN,
+};
+use crate::error::{AppError, Result};
+use crate::secret_kinds::{
+    decrypt_payload, encrypt_payload, kind_from_row, SecretKind, SecretPayload,
+};
+use crate::security::{MasterKey, FIELD_ENCRYPTION_VERSION};
+use crate::totp::{load_totp, store_totp};
+use crate::vault::Vault;
+use rusqlite::{types::Type, Connection, Error, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Versões guardadas por segredo quando o cofre não define outro limite.
+pub const DEFAULT_HISTORY_LIMIT: usize = 10;
+
+const HISTORY_LIMIT_SETTING: &str = "history_limit";
+
+const MASK: &str = "********";
+
+/// Chaves do payload que nunca aparecem na comparação.
+const SENSITIVE_PAYLOAD_KEYS: &[&str] = &[
+    "content",
+    "key",
+    "password",
+    "privateKey",
+    "passphrase",
+    "number",
+    "cvv",
+];
+
+/// Estado completo de um segredo, com os valores sensíveis decifrados.
+/// Só existe em memória e, cifrado, em `secret_versions.snapshot_blob`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretSnapshot {
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub kind: SecretKind,
+    #[serde(default)]
+    pub payload: Option<SecretPayload>,
+    #[serde(default)]
+    pub fields: Vec<CustomField>,
+    /// URI otpauth normalizada (ver `totp`)
+    #[serde(default)]
+    pub totp: Option<String>,
+}
+
+/// Uma versão anterior de um segredo, sem nenhum valor sensível.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVersion {
+    pub id: i64,
+    pub secret_id: i64,
+    pub title: String,
+    pub username: String,
+    pub kind: SecretKind,
+    /// Quando esta versão deixou de ser a atual.
+    pub created_at: String,
+}
+
+/// Diferença de um campo entre duas versões. `before`/`after` são `None`
+/// quando o campo não existe naquele lado; valores mascarados vêm como
+/// `********`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub masked: bool,
+}
+
+/// Estado atual do segredo `secret_id`, incluindo os campos ocultos.
+pub(crate) fn snapshot(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+) -> Result<SecretSnapshot> {
+    let mut snapshot = conn.query_row(
+        "SELECT title, username, password_blob, kind, payload_blob FROM secrets WHERE id = ?1",
+        [secret_id],
+        |row| {
+            Ok(SecretSnapshot {
+                title: row.get(0)?,
+                username: row.get(1)?,
+                password: decrypt_column_string(
+                    row,
+                    2,
+                    field_key,
+                    SECRET_PASSWORD_COLUMN,
+                    secret_id,
+                )?,
+                kind: kind_from_row(row, 3)?,
+                payload: decrypt_payload(row, 4, field_key, secret_id)?,
+                fields: Vec::new(),
+                totp: None,
+            })
+        },
+    )?;
+
+    snapshot.fields = load_fields(conn, field_key, Some(secret_id), true)?
+        .remove(&secret_id)
+        .unwrap_or_default();
+    snapshot.totp = load_totp(conn, field_key, secret_id)?.map(|uri| uri.to_string());
+
+    Ok(snapshot)
+}
+
+/// Guarda `before` como versão anterior do segredo, se o estado atual for
+/// diferente dele, e descarta as versões além do limite do cofre. Se a senha
+/// mudou, marca também `password_changed_at` e descarta a verificação de
+/// vazamento anterior. Deve ser chamada na mesma transação da alteração.
+pub(crate) fn record_version(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    before: &SecretSnapshot,
+) -> Result<()> {
+    let after = snapshot(conn, field_key, secret_id)?;
+    if after.password != before.password {
+        conn.execute(
+            "UPDATE secrets SET password_changed_at = ?1, breach_count = NULL WHERE id = ?2",
+            (chrono::Utc::now().to_rfc3339(), secret_id),
+        )?;
+    }
+
+    let limit = history_limit(conn)?;
+    if limit == 0 || after == *before {
+        return Ok(());
+    }
+
+    let json = serde_json::to_vec(before).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    conn.execute(
+        "INSERT INTO secret_versions (secret_id, snapshot_blob, created_at, encryption_version) VALUES (?1, x'', ?2, ?3)",
+        (
+            secret_id,
+            chrono::Utc::now().to_rfc3339(),
+            FIELD_ENCRYPTION_VERSION,
+        ),
+    )?;
+
+    let version_id = conn.last_insert_rowid();
+    let snapshot_blob = encrypt_column(&json, field_key, SECRET_VERSION_COLUMN, version_id)?;
+    conn.execute(
+        "UPDATE secret_versions SET snapshot_blob = ?1 WHERE id = ?2",
+        (snapshot_blob, version_id),
+    )?;
+
+    prune_versions(conn, Some(secret_id), limit)?;
+
+    Ok(())
+}
+
+/// Limite de versões por segredo configurado no cofre.
+pub(crate) fn history_limit(conn: &Connection) -> Result<usize> {
+    match vault_setting(conn, HISTORY_LIMIT_SETTING)? {
+        Some(value) => value
+            .parse()
+            .map_err(|_| AppError::Internal(format!("history_limit value={:?}", value))),
+        None => Ok(DEFAULT_HISTORY_LIMIT),
+    }
+}
+
+/// Mantém só as `limit` versões mais recentes de cada segredo (ou de um só).
+fn prune_versions(conn: &Connection, secret_id: Option<i64>, limit: usize) -> Result<()> {
+    conn.execute(
+        "DELETE FROM secret_versions WHERE id IN (
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (PARTITION BY secret_id ORDER BY id DESC) AS n
+                FROM secret_versions WHERE ?1 IS NULL OR secret_id = ?1
+            ) WHERE n > ?2
+        )",
+        (secret_id, limit as i64),
+    )?;
+
+    Ok(())
+}
+
+fn snapshot_from_row(
+    row: &Row,
+    idx: usize,
+    field_key: &MasterKey,
+    version_id: i64,
+) -> rusqlite::Result<SecretSnapshot> {
+    let json =
+        decrypt_column(row, idx, field_key, SECRET_VERSION_COLUMN, version_id)?.unwrap_or_default();
+
+    serde_json::from_slice(&json)
+        .map_err(|e| Error::FromSqlConversionFailure(idx, Type::Blob, e.into()))
+}
+
+fn load_version(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    version_id: i64,
+) -> Result<SecretSnapshot> {
+    let snapshot = conn.query_row(
+        "SELECT snapshot_blob FROM secret_versions WHERE id = ?1 AND secret_id = ?2",
+        (version_id, secret_id),
+        |row| snapshot_from_row(row, 0, field_key, version_id),
+    );
+
+    match snapshot {
+        Err(Error::QueryReturnedNoRows) => Err(AppError::NotFound(format!(
+            "version secret={} id={}",
+            secret_id, version_id
+        ))),
+        other => Ok(other?),
+    }
+}
+
+/// Diferenças de `before` para `after`, com os valores sensíveis mascarados.
+pub fn diff_snapshots(before: &SecretSnapshot, after: &SecretSnapshot) -> Vec<VersionChange> {
+    let mut changes = Vec::new();
+
+    let mut push = |field: String, before: Option<&str>, after: Option<&str>, masked: bool| {
+        if before != after {
+            let show = |value: Option<&str>| {
+                value.map(|v| {
+                    if masked {
+                        MASK.to_string()
+                    } else {
+                        v.to_string()
+                    }
+                })
+            };
+            changes.push(VersionChange {
+                field,
+                before: show(before),
+                after: show(after),
+                masked,
+            });
+        }
+    };
+
+    push(
+        "title".into(),
+        Some(&before.title),
+        Some(&after.title),
+        false,
+    );
+    push(
+        "username".into(),
+        Some(&before.username),
+        Some(&after.username),
+        false,
+    );
+    push(
+        "password".into(),
+        Some(&before.password),
+        Some(&after.password),
+        true,
+    );
+    push(
+        "totp".into(),
+        before.totp.as_deref(),
+        after.totp.as_deref(),
+        true,
+    );
+    push(
+        "kind".into(),
+        Some(before.kind.as_str()),
+        Some(after.kind.as_str()),
+        false,
+    );
+
+    let before_payload = flatten_payload(before.payload.as_ref());
+    let after_payload = flatten_payload(after.payload.as_ref());
+    let mut keys: Vec<&String> = before_payload.keys().chain(after_payload.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        push(
+            format!("payload.{}", key),
+            before_payload.get(key).map(String::as_str),
+            after_payload.get(key).map(String::as_str),
+            SENSITIVE_PAYLOAD_KEYS.contains(&key.as_str()),
+        );
+    }
+
+    // Campos na ordem da versão mais nova, seguidos dos que foram removidos
+    let mut names: Vec<&str> = Vec::new();
+    for field in after.fields.iter().chain(&before.fields) {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(&field.name)) {
+            names.push(&field.name);
+        }
+    }
+    for name in names {
+        let old = find_field(&before.fields, name);
+        let new = find_field(&after.fields, name);
+        let masked = old
+            .into_iter()
+            .chain(new)
+            .any(|f| f.field_type == CustomFieldType::Hidden);
+
+        push(
+            format!("field:{}", name),
+            old.map(|f| f.value.as_deref().unwrap_or_default()),
+            new.map(|f| f.value.as_deref().unwrap_or_default()),
+            masked,
+        );
+    }
+
+    changes
+}
+
+fn find_field<'a>(fields: &'a [CustomField], name: &str) -> Option<&'a CustomField> {
+    fields.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+/// Payload como pares chave/valor em texto, sem a etiqueta do tipo.
+fn flatten_payload(payload: Option<&SecretPayload>) -> BTreeMap<String, String> {
+    let Some(serde_json::Value::Object(map)) = payload.and_then(|p| serde_json::to_value(p).ok())
+    else {
+        return BTreeMap::new();
+    };
+
+    map.into_iter()
+        .filter(|(key, _)| key != "kind")
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some((key, s)),
+            other => Some((key, other.to_string())),
+        })
+        .collect()
+}
+
+impl Vault {
+    /// Versões anteriores do segredo, da mais recente para a mais antiga.
+    pub fn secret_versions(&self, secret_id: i64) -> Result<Vec<SecretVersion>> {
+        self.secret_summary(secret_id)?;
+
+        let mut stmt = self.conn().prepare(
+            "SELECT id, secret_id, snapshot_blob, created_at FROM secret_versions WHERE secret_id = ?1 ORDER BY id DESC",
+        )?;
+
+        let rows = stmt.query_map([secret_id], |row| {
+            let snapshot = snapshot_from_row(row, 2, self.field_key(), row.get(0)?)?;
+
+            Ok(SecretVersion {
+                id: row.get(0)?,
+                secret_id: row.get(1)?,
+                title: snapshot.title,
+                username: snapshot.username,
+                kind: snapshot.kind,
+                created_at: row.get(3)?,
+            })
+        })?;
+
+        let mut versions = Vec::new();
+        for row in rows {
+            versions.push(row?);
+        }
+
+        Ok(versions)
+    }
+
+    /// Compara duas versões do segredo. `None` em qualquer lado representa o
+    /// estado atual.
+    pub fn compare_secret_versions(
+        &self,
+        secret_id: i64,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<Vec<VersionChange>> {
+        let load = |version: Option<i64>| match version {
+            Some(version_id) => load_version(self.conn(), self.field_key(), secret_id, version_id),
+            None => snapshot(self.conn(), self.field_key(), secret_id),
+        };
+
+        Ok(diff_snapshots(&load(from)?, &load(to)?))
+    }
+
+    /// Torna a versão `version_id` a atual. O estado de antes entra no
+    /// histórico, então a restauração também pode ser desfeita.
+    pub fn restore_secret_version(&mut self, secret_id: i64, version_id: i64) -> Result<()> {
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+
+        let before = snapshot(&tx, field_key, secret_id)?;
+        let version = load_version(&tx, field_key, secret_id, version_id)?;
+
+        let password_blob = encrypt_column(
+            version.password.as_bytes(),
+            field_key,
+            SECRET_PASSWORD_COLUMN,
+            secret_id,
+        )?;
+        let payload_blob = encrypt_payload(version.payload.as_ref(), field_key, secret_id)?;
+
+        tx.execute(
+            "UPDATE secrets SET title = ?1, username = ?2, password_blob = ?3, kind = ?4, payload_blob = ?5, encryption_version = ?6 WHERE id = ?7",
+            (
+                &version.title,
+                &version.username,
+                password_blob,
+                version.kind.as_str(),
+                payload_blob,
+                FIELD_ENCRYPTION_VERSION,
+                secret_id,
+            ),
+        )?;
+
+        tx.execute(
+            "DELETE FROM secret_fields WHERE secret_id = ?1",
+            [secret_id],
+        )?;
+        insert_fields(&tx, field_key, secret_id, &version.fields)?;
+        store_totp(&tx, field_key, secret_id, version.totp.as_deref())?;
+
+        record_version(&tx, field_key, secret_id, &before)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn history_limit(&self) -> Result<usize> {
+        history_limit(self.conn())
+    }
+
+    /// Define quantas versões guardar por segredo (0 desliga o histórico).
+    /// Versões além do novo limite são descartadas na hora.
+    pub fn set_history_limit(&mut self, limit: usize) -> Result<()> {
+        let (conn, _) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+
+        set_vault_setting(&tx, HISTORY_LIMIT_SETTING, &limit.to_string())?;
+        prune_versions(&tx, None, limit)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SecretField;
+    use crate::registry::VaultEntry;
+    use crate::secret_kinds::ApiKeyPayload;
+    use std::fs;
+
+    fn temp_vault(name: &str) -> (Vault, VaultEntry) {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+
+        (Vault::create(&entry, "senha").unwrap(), entry)
+    }
+
+    #[test]
+    fn test_update_records_version_and_restore() {
+        let (mut vault, entry) = temp_vault("test_secret_history_restore");
+
+        let secret = vault
+            .create_typed_secret(
+                "GitHub",
+                "dev",
+                "ghp_antigo",
+                None,
+                &[CustomField::new("PIN", "1234", CustomFieldType::Hidden)],
+            )
+            .unwrap();
+
+        // Sem mudança de fato, nenhuma versão é criada
+        vault
+            .update_secret(secret.id, "GitHub", "dev", None, None)
+            .unwrap();
+        assert!(vault.secret_versions(secret.id).unwrap().is_empty());
+
+        vault
+            .update_secret(
+                secret.id,
+                "GitHub (rotacionado)",
+                "dev",
+                Some("ghp_novo"),
+                Some(&[CustomField::new("PIN", "9999", CustomFieldType::Hidden)]),
+            )
+            .unwrap();
+
+        let versions = vault.secret_versions(secret.id).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].title, "GitHub");
+
+        let changes = vault
+            .compare_secret_versions(secret.id, Some(versions[0].id), None)
+            .unwrap();
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["title", "password", "field:PIN"]);
+        assert!(changes[1].masked && changes[2].masked);
+        assert_eq!(changes[1].before.as_deref(), Some(MASK));
+        assert!(!changes.iter().any(|c| {
+            [&c.before, &c.after].iter().any(|v| {
+                matches!(
+                    v.as_deref(),
+                    Some("ghp_antigo" | "ghp_novo" | "1234" | "9999")
+                )
+            })
+        }));
+
+        vault
+            .restore_secret_version(secret.id, versions[0].id)
+            .unwrap();
+        assert_eq!(
+            vault
+                .reveal_secret_field(secret.id, SecretField::Password)
+                .unwrap(),
+            "ghp_antigo"
+        );
+        assert_eq!(vault.reveal_custom_field(secret.id, "PIN").unwrap(), "1234");
+
+        // O estado rotacionado virou uma versão, então a restauração pode ser desfeita
+        let versions = vault.secret_versions(secret.id).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].title, "GitHub (rotacionado)");
+
+        assert!(matches!(
+            vault.restore_secret_version(secret.id, 9999),
+            Err(AppError::NotFound(_))
+        ));
+
+        drop(vault);
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_history_limit_prunes_old_versions() {
+        let (mut vault, entry) = temp_vault("test_secret_history_limit");
+
+        let secret = vault.create_secret("AWS", "root", "v0").unwrap();
+        vault.set_history_limit(3).unwrap();
+
+        for i in 1..=5 {
+            vault
+                .update_secret(secret.id, "AWS", "root", Some(&format!("v{}", i)), None)
+                .unwrap();
+        }
+        assert_eq!(vault.secret_versions(secret.id).unwrap().len(), 3);
+
+        // Payload também gera versão, com as chaves sensíveis mascaradas
+        let payload = SecretPayload::ApiKey(ApiKeyPayload {
+            key: "sk_live".into(),
+            endpoint: Some("https://api.exemplo.com".into()),
+        });
+        vault
+            .update_secret_payload(secret.id, Some(&payload))
+            .unwrap();
+        let latest = vault.secret_versions(secret.id).unwrap()[0].id;
+        let changes = vault
+            .compare_secret_versions(secret.id, Some(latest), None)
+            .unwrap();
+        let key = changes.iter().find(|c| c.field == "payload.key").unwrap();
+        assert!(key.masked && key.after.as_deref() == Some(MASK));
+        let endpoint = changes
+            .iter()
+            .find(|c| c.field == "payload.endpoint")
+            .unwrap();
+        assert_eq!(endpoint.after.as_deref(), Some("https://api.exemplo.com"));
+
+        vault.set_history_limit(1).unwrap();
+        assert_eq!(vault.history_limit().unwrap(), 1);
+        assert_eq!(vault.secret_versions(secret.id).unwrap().len(), 1);
+
+        vault.set_history_limit(0).unwrap();
+        assert!(vault.secret_versions(secret.id).unwrap().is_empty());
+        vault
+            .update_secret(secret.id, "AWS", "root", Some("v9"), None)
+            .unwrap();
+        assert!(vault.secret_versions(secret.id).unwrap().is_empty());
+
+        drop(vault);
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+}
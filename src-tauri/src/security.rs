@@ -1,29 +1,141 @@
+use crate::error::{AppError, Result};
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng as AeadOsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng as AeadOsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use argon2::{
     password_hash::{rand_core::OsRng as Argon2OsRng, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, Version,
 };
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-type Result<T> = std::result::Result<T, String>;
-
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct MasterKey {
     pub key: [u8; 32], // 32 bytes
 }
 
+/// Contexto HKDF da chave usada para cifrar colunas sensíveis.
+const FIELD_KEY_CONTEXT: &[u8] = b"secrets-manager/field-encryption/v1";
+
+/// Versão atual da cifragem de colunas (`encryption_version` nas tabelas).
+pub const FIELD_ENCRYPTION_VERSION: i64 = 1;
+
+/// Versão atual do formato do `vault.meta`.
+pub const META_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KdfAlgorithm {
+    Argon2id,
+    Argon2i,
+    Argon2d,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    pub algorithm: KdfAlgorithm,
+    /// Memória em KiB
+    pub memory_cost: u32,
+    /// Número de iterações
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// Mesmos parâmetros de `Argon2::default()`, usados pelos cofres antigos.
+    fn default() -> Self {
+        Self {
+            algorithm: KdfAlgorithm::Argon2id,
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Conteúdo do `vault.meta`: tudo o que é preciso para derivar a chave do cofre.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMeta {
+    pub version: u32,
+    pub kdf: KdfParams,
+    pub salt: String,
+}
+
+impl VaultMeta {
+    /// Cria o meta de um cofre novo, com salt aleatório e os parâmetros atuais.
+    pub fn generate() -> Self {
+        Self {
+            version: META_FORMAT_VERSION,
+            kdf: KdfParams::default(),
+            salt: generate_salt(),
+        }
+    }
+
+    /// Lê o conteúdo do `vault.meta`. Cofres antigos guardavam apenas o salt
+    /// puro; nesse caso o segundo valor retornado é `true` e o meta deve ser
+    /// regravado no formato novo.
+    pub fn parse(contents: &str) -> Result<(Self, bool)> {
+        let contents = contents.trim();
+
+        if !contents.starts_with('{') {
+            let meta = Self {
+                version: META_FORMAT_VERSION,
+                kdf: KdfParams::default(),
+                salt: contents.to_string(),
+            };
+            return Ok((meta, true));
+        }
+
+        let meta: Self = serde_json::from_str(contents)
+            .map_err(|e| AppError::InvalidInput(format!("vault.meta: {}", e)))?;
+
+        if meta.version > META_FORMAT_VERSION {
+            return Err(AppError::UnsupportedVersion(format!(
+                "vault.meta version={}",
+                meta.version
+            )));
+        }
+
+        Ok((meta, false))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    pub fn derive_key(&self, password: &str) -> Result<MasterKey> {
+        derive_key(password, &self.salt, &self.kdf)
+    }
+}
+
 pub fn generate_salt() -> String {
     let salt = SaltString::generate(&mut Argon2OsRng);
     salt.as_str().to_string()
 }
 
 pub fn derive_key_from_password(password: &str, salt_str: &str) -> Result<MasterKey> {
-    let argon2 = Argon2::default();
+    derive_key(password, salt_str, &KdfParams::default())
+}
+
+pub fn derive_key(password: &str, salt_str: &str, kdf: &KdfParams) -> Result<MasterKey> {
+    let algorithm = match kdf.algorithm {
+        KdfAlgorithm::Argon2id => Algorithm::Argon2id,
+        KdfAlgorithm::Argon2i => Algorithm::Argon2i,
+        KdfAlgorithm::Argon2d => Algorithm::Argon2d,
+    };
+
+    let params = Params::new(kdf.memory_cost, kdf.time_cost, kdf.parallelism, Some(32))
+        .map_err(|e| AppError::InvalidInput(format!("kdf: {}", e)))?;
 
-    let salt = SaltString::from_b64(salt_str).map_err(|e| format!("Erro ao ler salt: {}", e))?;
+    let argon2 = Argon2::new(algorithm, Version::V0x13, params);
+
+    let salt = SaltString::from_b64(salt_str)
+        .map_err(|e| AppError::InvalidInput(format!("salt: {}", e)))?;
 
     let mut key_buffer = [0u8; 32];
 
@@ -33,19 +145,73 @@ pub fn derive_key_from_password(password: &str, salt_str: &str) -> Result<Master
             salt.as_str().as_bytes(),
             &mut key_buffer,
         )
-        .map_err(|e| format!("Erro ao derivar chave: {}", e))?;
+        .map_err(|e| AppError::Crypto(format!("argon2: {}", e)))?;
 
     Ok(MasterKey { key: key_buffer })
 }
 
+/// Deriva uma subchave independente da chave mestra via HKDF-SHA256.
+pub fn derive_subkey(key: &MasterKey, context: &[u8]) -> Result<MasterKey> {
+    let hkdf = Hkdf::<Sha256>::new(None, &key.key);
+
+    let mut subkey = [0u8; 32];
+    hkdf.expand(context, &mut subkey)
+        .map_err(|e| AppError::Crypto(format!("hkdf: {}", e)))?;
+
+    Ok(MasterKey { key: subkey })
+}
+
+/// Chave usada para cifrar `password_blob` e o conteúdo dos anexos. É
+/// separada da chave do SQLCipher para que a conexão aberta não baste para
+/// ler os segredos.
+pub fn derive_field_key(key: &MasterKey) -> Result<MasterKey> {
+    derive_subkey(key, FIELD_KEY_CONTEXT)
+}
+
+/// Cifra o valor de uma coluna sensível com nonce próprio. A coluna e o id
+/// da linha entram como AAD, então um valor copiado para outra coluna ou
+/// trocado com o de outra linha deixa de decifrar.
+pub fn encrypt_field(data: &[u8], key: &MasterKey, column: &str, row_id: i64) -> Result<Vec<u8>> {
+    encrypt_bytes(data, key, field_aad(column, row_id).as_bytes())
+}
+
+pub fn decrypt_field(
+    encrypted_data: &[u8],
+    key: &MasterKey,
+    column: &str,
+    row_id: i64,
+) -> Result<Vec<u8>> {
+    decrypt_bytes(encrypted_data, key, field_aad(column, row_id).as_bytes())
+        .map_err(|_| AppError::Crypto(format!("{} id={}", column, row_id)))
+}
+
+fn field_aad(column: &str, row_id: i64) -> String {
+    format!("{}#{}", column, row_id)
+}
+
 pub fn encrypt_data(data: &str, key: &MasterKey) -> Result<Vec<u8>> {
+    encrypt_bytes(data.as_bytes(), key, &[])
+}
+
+pub fn decrypt_data(encrypted_data: &[u8], key: &MasterKey) -> Result<String> {
+    let plaintext_bytes = decrypt_bytes(encrypted_data, key, &[])?;
+
+    let plaintext = String::from_utf8(plaintext_bytes)
+        .map_err(|_| AppError::InvalidInput("utf8".to_string()))?;
+
+    Ok(plaintext)
+}
+
+/// Cifra `data` com AES-256-GCM e um nonce aleatório. O `aad` é autenticado
+/// mas não cifrado. Retorna `nonce || ciphertext`.
+pub fn encrypt_bytes(data: &[u8], key: &MasterKey, aad: &[u8]) -> Result<Vec<u8>> {
     let cipher = Aes256Gcm::new(&key.key.into());
 
     let nonce = Aes256Gcm::generate_nonce(&mut AeadOsRng);
 
     let ciphertext = cipher
-        .encrypt(&nonce, data.as_bytes())
-        .map_err(|e| format!("Falha na criptografia AES: {}", e))?;
+        .encrypt(&nonce, Payload { msg: data, aad })
+        .map_err(|e| AppError::Crypto(format!("aes-gcm: {}", e)))?;
 
     let mut package = nonce.to_vec();
     package.extend(ciphertext);
@@ -53,9 +219,12 @@ pub fn encrypt_data(data: &str, key: &MasterKey) -> Result<Vec<u8>> {
     Ok(package)
 }
 
-pub fn decrypt_data(encrypted_data: &[u8], key: &MasterKey) -> Result<String> {
+pub fn decrypt_bytes(encrypted_data: &[u8], key: &MasterKey, aad: &[u8]) -> Result<Vec<u8>> {
     if encrypted_data.len() < 12 {
-        return Err("Arquivo inválido ou corrompido".to_string());
+        return Err(AppError::Crypto(format!(
+            "ciphertext len={}",
+            encrypted_data.len()
+        )));
     }
 
     let cipher = Aes256Gcm::new(&key.key.into());
@@ -63,14 +232,15 @@ pub fn decrypt_data(encrypted_data: &[u8], key: &MasterKey) -> Result<String> {
     let nonce = Nonce::from_slice(&encrypted_data[0..12]);
     let ciphertext = &encrypted_data[12..];
 
-    let plaintext_bytes = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|_| "Senha incorreta ou arquivo incompleto".to_string())?;
-
-    let plaintext = String::from_utf8(plaintext_bytes.to_vec())
-        .map_err(|_| "Erro de encoding UTF-8".to_string())?;
-
-    Ok(plaintext)
+    cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| AppError::WrongPassword)
 }
 
 #[cfg(test)]
@@ -103,4 +273,61 @@ mod tests {
 
         assert_eq!(k1.key, k2.key);
     }
+
+    #[test]
+    fn test_field_key_is_independent_and_bound_to_column() {
+        let master = derive_key_from_password("minha_senha", &generate_salt()).unwrap();
+        let field_key = derive_field_key(&master).unwrap();
+        assert_ne!(field_key.key, master.key);
+
+        let encrypted = encrypt_field(b"hunter2", &field_key, "secrets.password_blob", 7).unwrap();
+        assert_ne!(&encrypted[12..], b"hunter2");
+
+        assert_eq!(
+            decrypt_field(&encrypted, &field_key, "secrets.password_blob", 7).unwrap(),
+            b"hunter2"
+        );
+        assert!(decrypt_field(&encrypted, &field_key, "attachments.content", 7).is_err());
+        assert!(decrypt_field(&encrypted, &master, "secrets.password_blob", 7).is_err());
+        // Trocar o valor de linha também invalida a autenticação
+        assert!(decrypt_field(&encrypted, &field_key, "secrets.password_blob", 8).is_err());
+    }
+
+    #[test]
+    fn test_legacy_meta_is_upgraded_with_same_key() {
+        let salt = generate_salt();
+
+        let (meta, legacy) = VaultMeta::parse(&salt).unwrap();
+        assert!(legacy);
+        assert_eq!(meta.salt, salt);
+        assert_eq!(meta.kdf, KdfParams::default());
+
+        // A chave derivada pelo meta novo precisa abrir o cofre antigo
+        let legacy_key = derive_key_from_password("minha_senha", &salt).unwrap();
+        let upgraded_key = meta.derive_key("minha_senha").unwrap();
+        assert_eq!(legacy_key.key, upgraded_key.key);
+    }
+
+    #[test]
+    fn test_meta_roundtrip_keeps_kdf_params() {
+        let mut meta = VaultMeta::generate();
+        meta.kdf.memory_cost = 8 * 1024;
+        meta.kdf.time_cost = 3;
+
+        let (parsed, legacy) = VaultMeta::parse(&meta.to_json().unwrap()).unwrap();
+        assert!(!legacy);
+        assert_eq!(parsed, meta);
+
+        let k1 = meta.derive_key("minha_senha").unwrap();
+        let k2 = derive_key_from_password("minha_senha", &meta.salt).unwrap();
+        assert_ne!(k1.key, k2.key, "Custos diferentes devem gerar chaves diferentes");
+    }
+
+    #[test]
+    fn test_meta_from_newer_version_is_rejected() {
+        let mut meta = VaultMeta::generate();
+        meta.version = META_FORMAT_VERSION + 1;
+
+        assert!(VaultMeta::parse(&meta.to_json().unwrap()).is_err());
+    }
 }
@@ -0,0 +1,72 @@
+use crate::error::{AppError, Result};
+use crate::i18n::{set_current_locale, Locale};
+use crate::utils::{get_settings_path, write_atomic};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Preferências do aplicativo, independentes de qualquer cofre. Ficam em
+/// `~/.secrets-manager/settings.json` e podem ser lidas antes do login.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub locale: Locale,
+    /// Pasta onde novos cofres são criados. `None` usa a pasta padrão.
+    pub vaults_dir: Option<PathBuf>,
+}
+
+impl Settings {
+    /// Lê as configurações. Arquivo ausente resulta nos valores padrão.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| AppError::InvalidInput(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| AppError::Internal(e.to_string()))?;
+        write_atomic(path, json.as_bytes())
+    }
+}
+
+/// Aplica o idioma salvo. Se as configurações não puderem ser lidas, fica o
+/// idioma padrão.
+pub fn apply_saved_locale() {
+    let settings = get_settings_path()
+        .and_then(|path| Settings::load(&path))
+        .unwrap_or_default();
+
+    set_current_locale(settings.locale);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_roundtrip_and_defaults() {
+        let path = std::env::temp_dir().join("test_settings_roundtrip.json");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+        let settings = Settings {
+            locale: Locale::En,
+            vaults_dir: Some(PathBuf::from("/mnt/cofres")),
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), settings);
+
+        // Campos desconhecidos ou ausentes não impedem a leitura
+        fs::write(&path, r#"{"outro": 1}"#).unwrap();
+        assert_eq!(Settings::load(&path).unwrap().locale, Locale::PtBr);
+
+        let _ = fs::remove_file(&path);
+    }
+}
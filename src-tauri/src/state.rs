@@ -1,6 +1,26 @@
-use rusqlite::Connection;
+use crate::error::{AppError, Result};
+use crate::trash_retention::TrashPurgeSummary;
+use crate::vault::Vault;
 use std::sync::Mutex;
 
 pub struct AppState {
-    pub db: Mutex<Option<Connection>>,
+    /// Cofre aberto no momento, com sua conexão e chave. `None` quando trancado.
+    pub vault: Mutex<Option<Vault>>,
+}
+
+impl AppState {
+    /// Executa `f` com o cofre aberto, ou falha com `VaultLocked`.
+    pub fn with_vault<T>(&self, f: impl FnOnce(&mut Vault) -> Result<T>) -> Result<T> {
+        let mut lock = self.vault.lock()?;
+        let vault = lock.as_mut().ok_or(AppError::VaultLocked)?;
+
+        f(vault)
+    }
+
+    /// Exclui a lixeira vencida do cofre aberto. `None` se estiver trancado.
+    pub fn purge_expired_trash(&self) -> Result<Option<TrashPurgeSummary>> {
+        let mut lock = self.vault.lock()?;
+
+        lock.as_mut().map(Vault::purge_expired_trash).transpose()
+    }
 }
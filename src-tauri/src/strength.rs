@@ -0,0 +1,679 @@
+//! Estimativa de força de senhas no estilo do zxcvbn.
+//!
+//! A senha é decomposta nos padrões que um atacante tentaria primeiro
+//! (palavras de dicionário, inclusive invertidas ou com l33t, sequências,
+//! repetições, caminhos no teclado e datas); o que sobra é força bruta. A
+//! estimativa é o menor número de tentativas entre as decomposições possíveis,
+//! calculado como no zxcvbn: `l! · Π tentativas + 10000^(l-1)` para `l`
+//! padrões. A nota vai de 0 a 4 com os mesmos limites do zxcvbn.
+//!
+//! O dicionário junta as senhas mais comuns (`wordlists/common-passwords.txt`,
+//! em ordem de popularidade), as listas de palavras do gerador e textos do
+//! próprio segredo, como título e usuário.
+
+use crate::generator::{wordlist, WordlistLanguage};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const COMMON_PASSWORDS: &str = include_str!("../wordlists/common-passwords.txt");
+
+/// Posição atribuída às palavras das listas do gerador, que não têm ordem de
+/// popularidade.
+const COMMON_WORD_RANK: usize = 2048;
+
+/// Só o começo de senhas muito longas é analisado; o resto já não muda a nota.
+const MAX_ANALYZED_LENGTH: usize = 64;
+const MAX_WORD_LENGTH: usize = 24;
+
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_SUBMATCH_GUESSES_SINGLE_CHAR: f64 = 10.0;
+const MIN_SUBMATCH_GUESSES_MULTI_CHAR: f64 = 50.0;
+const MIN_GUESSES_BEFORE_GROWING_SEQUENCE: f64 = 10000.0;
+const MIN_YEAR_SPACE: f64 = 20.0;
+
+/// Linhas do teclado QWERTY sem e com shift. Cada linha é deslocada meia tecla
+/// em relação à de cima, então a tecla `(r, c)` encosta em `(r-1, c)`,
+/// `(r-1, c+1)`, `(r+1, c-1)` e `(r+1, c)`.
+const KEYBOARD_ROWS: [&str; 4] = ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"];
+const KEYBOARD_SHIFTED_ROWS: [&str; 4] =
+    ["!@#$%^&*()_+", "QWERTYUIOP{}", "ASDFGHJKL:\"", "ZXCVBNM<>?"];
+/// Vizinhos por tecla, em média, no layout acima
+const KEYBOARD_AVERAGE_DEGREE: f64 = 4.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternKind {
+    Dictionary,
+    Sequence,
+    Repeat,
+    Keyboard,
+    Date,
+    Bruteforce,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrengthEstimate {
+    /// De 0 (trivial) a 4 (forte)
+    pub score: u8,
+    /// log10 do número estimado de tentativas
+    pub guesses_log10: f64,
+    /// Padrões da decomposição mais barata, em ordem, sem o texto da senha
+    pub patterns: Vec<PatternKind>,
+}
+
+#[derive(Debug, Clone)]
+struct Match {
+    start: usize,
+    /// Inclusivo
+    end: usize,
+    guesses: f64,
+    kind: PatternKind,
+}
+
+/// Estima a força de `password`. `user_inputs` são textos que um atacante
+/// conheceria (título, usuário...) e entram no dicionário.
+pub fn estimate(password: &str, user_inputs: &[&str]) -> StrengthEstimate {
+    let chars: Vec<char> = password.chars().take(MAX_ANALYZED_LENGTH).collect();
+    if chars.is_empty() {
+        return StrengthEstimate {
+            score: 0,
+            guesses_log10: 0.0,
+            patterns: Vec::new(),
+        };
+    }
+
+    let mut inputs = HashMap::new();
+    for input in user_inputs {
+        let tokens = input
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| token.chars().count() >= 3);
+        for token in std::iter::once(input.trim()).chain(tokens) {
+            let rank = inputs.len() + 1;
+            inputs.entry(token.to_lowercase()).or_insert(rank);
+        }
+    }
+
+    let (guesses, patterns) = most_guessable(&chars, &inputs, 0);
+
+    StrengthEstimate {
+        score: score(guesses),
+        guesses_log10: guesses.log10(),
+        patterns,
+    }
+}
+
+fn score(guesses: f64) -> u8 {
+    const DELTA: f64 = 5.0;
+
+    [1e3, 1e6, 1e8, 1e10]
+        .iter()
+        .position(|limit| guesses < limit + DELTA)
+        .unwrap_or(4) as u8
+}
+
+/// Menor número de tentativas para `chars`, e os padrões usados.
+fn most_guessable(
+    chars: &[char],
+    inputs: &HashMap<String, usize>,
+    depth: usize,
+) -> (f64, Vec<PatternKind>) {
+    let n = chars.len();
+
+    let mut matches = dictionary_matches(chars, inputs);
+    matches.extend(sequence_matches(chars));
+    matches.extend(keyboard_matches(chars));
+    matches.extend(date_matches(chars));
+    // Repetições estimam o bloco repetido recursivamente; um nível basta
+    if depth == 0 {
+        matches.extend(repeat_matches(chars, inputs));
+    }
+    for start in 0..n {
+        for end in start..n {
+            matches.push(Match {
+                start,
+                end,
+                guesses: BRUTEFORCE_CARDINALITY.powi((end - start + 1) as i32),
+                kind: PatternKind::Bruteforce,
+            });
+        }
+    }
+
+    for m in &mut matches {
+        if m.end - m.start + 1 < n {
+            let min = if m.start == m.end {
+                MIN_SUBMATCH_GUESSES_SINGLE_CHAR
+            } else {
+                MIN_SUBMATCH_GUESSES_MULTI_CHAR
+            };
+            m.guesses = m.guesses.max(min);
+        }
+    }
+
+    // best[k][l]: menor produto de tentativas cobrindo chars[..=k] com l
+    // padrões, e o índice do último padrão
+    let mut best: Vec<Vec<Option<(f64, usize)>>> = vec![vec![None; n + 1]; n];
+    let mut by_end: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (index, m) in matches.iter().enumerate() {
+        by_end[m.end].push(index);
+    }
+
+    for k in 0..n {
+        for &index in &by_end[k] {
+            let m = &matches[index];
+            for l in 1..=k + 1 {
+                let previous = if m.start == 0 {
+                    (l == 1).then_some(1.0)
+                } else {
+                    best[m.start - 1][l - 1].map(|(product, _)| product)
+                };
+                let Some(previous) = previous else { continue };
+
+                let product = previous * m.guesses;
+                if best[k][l].is_none_or(|(current, _)| product < current) {
+                    best[k][l] = Some((product, index));
+                }
+            }
+        }
+    }
+
+    let (guesses, count) = (1..=n)
+        .filter_map(|l| {
+            best[n - 1][l].map(|(product, _)| {
+                let total =
+                    factorial(l) * product + MIN_GUESSES_BEFORE_GROWING_SEQUENCE.powi(l as i32 - 1);
+                (total, l)
+            })
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap_or((BRUTEFORCE_CARDINALITY.powi(n as i32), 0));
+
+    let mut patterns = Vec::with_capacity(count);
+    let (mut k, mut l) = (n, count);
+    while l > 0 {
+        let Some((_, index)) = best[k - 1][l] else {
+            break;
+        };
+        patterns.push(matches[index].kind);
+        k = matches[index].start;
+        l -= 1;
+    }
+    patterns.reverse();
+
+    (guesses, patterns)
+}
+
+fn dictionary() -> &'static HashMap<String, usize> {
+    static DICTIONARY: OnceLock<HashMap<String, usize>> = OnceLock::new();
+
+    DICTIONARY.get_or_init(|| {
+        let mut ranks = HashMap::new();
+        for (index, word) in COMMON_PASSWORDS
+            .lines()
+            .filter(|word| !word.is_empty())
+            .enumerate()
+        {
+            ranks.entry(word.to_lowercase()).or_insert(index + 1);
+        }
+        for language in [WordlistLanguage::En, WordlistLanguage::Pt] {
+            for word in wordlist(language) {
+                ranks.entry(word.to_string()).or_insert(COMMON_WORD_RANK);
+            }
+        }
+
+        ranks
+    })
+}
+
+fn rank(word: &str, inputs: &HashMap<String, usize>) -> Option<usize> {
+    match (dictionary().get(word), inputs.get(word)) {
+        (Some(a), Some(b)) => Some(*a.min(b)),
+        (a, b) => a.or(b).copied(),
+    }
+}
+
+/// Troca l33t pela letra. Como `1`, `|` e `7` valem mais de uma letra, a
+/// segunda tabela usa a alternativa.
+fn unleet(c: char, alternate: bool) -> Option<char> {
+    Some(match c {
+        '4' | '@' => 'a',
+        '8' => 'b',
+        '(' | '{' | '[' | '<' => 'c',
+        '3' => 'e',
+        '6' | '9' => 'g',
+        '1' | '!' | '|' if !alternate => 'i',
+        '1' | '|' | '7' if alternate => 'l',
+        '0' => 'o',
+        '$' | '5' => 's',
+        '7' | '+' => 't',
+        '%' => 'x',
+        '2' => 'z',
+        _ => return None,
+    })
+}
+
+fn dictionary_matches(chars: &[char], inputs: &HashMap<String, usize>) -> Vec<Match> {
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    // to_lowercase pode mudar o tamanho de alguns caracteres; nesse caso as
+    // posições não batem mais e só a força bruta vale
+    if lower.len() != chars.len() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    for start in 0..chars.len() {
+        for end in start + 2..chars.len().min(start + MAX_WORD_LENGTH) {
+            let original = &chars[start..=end];
+            let word: String = lower[start..=end].iter().collect();
+            let variations = uppercase_variations(original);
+
+            let mut candidates = Vec::new();
+            if let Some(rank) = rank(&word, inputs) {
+                candidates.push(rank as f64 * variations);
+            }
+
+            let reversed: String = word.chars().rev().collect();
+            if reversed != word {
+                if let Some(rank) = rank(&reversed, inputs) {
+                    candidates.push(rank as f64 * variations * 2.0);
+                }
+            }
+
+            for alternate in [false, true] {
+                let translated: Vec<char> = lower[start..=end]
+                    .iter()
+                    .map(|c| unleet(*c, alternate).unwrap_or(*c))
+                    .collect();
+                if translated[..] == lower[start..=end] {
+                    continue;
+                }
+
+                let translated_word: String = translated.iter().collect();
+                if let Some(rank) = rank(&translated_word, inputs) {
+                    let leet = leet_variations(&lower[start..=end], &translated);
+                    candidates.push(rank as f64 * variations * leet);
+                }
+            }
+
+            if let Some(guesses) = candidates.into_iter().min_by(f64::total_cmp) {
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses,
+                    kind: PatternKind::Dictionary,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+fn factorial(n: usize) -> f64 {
+    (2..=n).fold(1.0, |acc, i| acc * i as f64)
+}
+
+/// Quantas variações de um conjunto de `changed` posições trocadas entre
+/// `changed + unchanged`, como no zxcvbn.
+fn variations(changed: usize, unchanged: usize) -> f64 {
+    match (changed, unchanged) {
+        (0, _) => 1.0,
+        (_, 0) => 2.0,
+        _ => (1..=changed.min(unchanged))
+            .map(|k| binomial(changed + unchanged, k))
+            .sum(),
+    }
+}
+
+fn uppercase_variations(word: &[char]) -> f64 {
+    let upper = word.iter().filter(|c| c.is_uppercase()).count();
+    let lower = word.iter().filter(|c| c.is_lowercase()).count();
+
+    // Primeira ou última maiúscula, ou tudo maiúsculo, são os casos comuns
+    let first_or_last =
+        upper == 1 && (word[0].is_uppercase() || word[word.len() - 1].is_uppercase());
+    if upper > 0 && (lower == 0 || first_or_last) {
+        return 2.0;
+    }
+
+    variations(upper, lower)
+}
+
+fn leet_variations(original: &[char], translated: &[char]) -> f64 {
+    let mut pairs: Vec<(char, char)> = original
+        .iter()
+        .zip(translated)
+        .filter(|(o, t)| o != t)
+        .map(|(o, t)| (*o, *t))
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+
+    pairs
+        .into_iter()
+        .map(|(leet, letter)| {
+            let changed = original.iter().filter(|c| **c == leet).count();
+            let unchanged = original.iter().filter(|c| **c == letter).count();
+            variations(changed, unchanged)
+        })
+        .product()
+}
+
+fn char_class(c: char) -> u8 {
+    if c.is_ascii_lowercase() {
+        0
+    } else if c.is_ascii_uppercase() {
+        1
+    } else if c.is_ascii_digit() {
+        2
+    } else {
+        3
+    }
+}
+
+/// Sequências como `abc`, `9753` ou `ZYX`: passo constante de até 5 dentro
+/// da mesma classe.
+fn sequence_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start + 2 < chars.len() {
+        let delta = chars[start + 1] as i64 - chars[start] as i64;
+        let class = char_class(chars[start]);
+
+        let mut end = start + 1;
+        while end + 1 < chars.len()
+            && chars[end + 1] as i64 - chars[end] as i64 == delta
+            && char_class(chars[end + 1]) == class
+        {
+            end += 1;
+        }
+
+        let valid = class < 3 && char_class(chars[start + 1]) == class;
+        if valid && end - start >= 2 && (1..=5).contains(&delta.abs()) {
+            let first = chars[start];
+            let base = if matches!(first, 'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9') {
+                4.0
+            } else if first.is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let direction = if delta < 0 { 2.0 } else { 1.0 };
+
+            matches.push(Match {
+                start,
+                end,
+                guesses: base * direction * (end - start + 1) as f64,
+                kind: PatternKind::Sequence,
+            });
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+/// Blocos repetidos (`aaaa`, `abcabc`). O bloco é estimado recursivamente e
+/// multiplicado pelo número de repetições.
+fn repeat_matches(chars: &[char], inputs: &HashMap<String, usize>) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let remaining = chars.len() - start;
+        let found = (1..=remaining / 2).find_map(|unit| {
+            let block = &chars[start..start + unit];
+            let count = chars[start..]
+                .chunks(unit)
+                .take_while(|chunk| *chunk == block)
+                .count();
+            let enough = if unit == 1 { count >= 3 } else { count >= 2 };
+            enough.then_some((unit, count))
+        });
+
+        match found {
+            Some((unit, count)) => {
+                let (block_guesses, _) = most_guessable(&chars[start..start + unit], inputs, 1);
+                let end = start + unit * count - 1;
+
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses: block_guesses * count as f64,
+                    kind: PatternKind::Repeat,
+                });
+                start = end + 1;
+            }
+            None => start += 1,
+        }
+    }
+
+    matches
+}
+
+/// Posição da tecla e se precisa de shift.
+fn key_position(c: char) -> Option<(i64, i64, bool)> {
+    for (shifted, rows) in [(false, KEYBOARD_ROWS), (true, KEYBOARD_SHIFTED_ROWS)] {
+        for (row, keys) in rows.iter().enumerate() {
+            if let Some(column) = keys.chars().position(|key| key == c) {
+                return Some((row as i64, column as i64, shifted));
+            }
+        }
+    }
+
+    None
+}
+
+fn adjacent_direction(from: (i64, i64), to: (i64, i64)) -> Option<(i64, i64)> {
+    let direction = (to.0 - from.0, to.1 - from.1);
+
+    [(0, -1), (0, 1), (-1, 0), (-1, 1), (1, -1), (1, 0)]
+        .contains(&direction)
+        .then_some(direction)
+}
+
+/// Caminhos de teclas vizinhas (`qwerty`, `zaqxsw`, `1qaz`), com pelo menos
+/// três teclas.
+fn keyboard_matches(chars: &[char]) -> Vec<Match> {
+    let keys: Vec<Option<(i64, i64, bool)>> = chars.iter().map(|c| key_position(*c)).collect();
+    let starting_positions: f64 = KEYBOARD_ROWS.iter().map(|row| row.len() as f64).sum();
+
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let mut end = start;
+        let mut turns = 0;
+        let mut last_direction = None;
+
+        while end + 1 < chars.len() {
+            let (Some(from), Some(to)) = (keys[end], keys[end + 1]) else {
+                break;
+            };
+            let Some(direction) = adjacent_direction((from.0, from.1), (to.0, to.1)) else {
+                break;
+            };
+            if last_direction != Some(direction) {
+                turns += 1;
+                last_direction = Some(direction);
+            }
+            end += 1;
+        }
+
+        let length = end - start + 1;
+        if length >= 3 {
+            let mut guesses = 0.0;
+            for i in 2..=length {
+                for j in 1..=turns.min(i - 1) {
+                    guesses += binomial(i - 1, j - 1)
+                        * starting_positions
+                        * KEYBOARD_AVERAGE_DEGREE.powi(j as i32);
+                }
+            }
+
+            let shifted = keys[start..=end]
+                .iter()
+                .filter(|key| matches!(key, Some((_, _, true))))
+                .count();
+            guesses *= variations(shifted, length - shifted);
+
+            matches.push(Match {
+                start,
+                end,
+                guesses,
+                kind: PatternKind::Keyboard,
+            });
+            start = end + 1;
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+fn year_space(year: i32) -> f64 {
+    let reference = chrono::Utc::now().year();
+    ((year - reference).abs() as f64).max(MIN_YEAR_SPACE)
+}
+
+fn full_year(year: u32, digits: usize) -> Option<i32> {
+    let year = match digits {
+        2 if year <= 50 => 2000 + year,
+        2 => 1900 + year,
+        4 => year,
+        _ => return None,
+    };
+
+    (1900..=2050).contains(&year).then_some(year as i32)
+}
+
+/// Ano de uma data em `parts` (dia, mês e ano em alguma ordem comum), se
+/// houver uma leitura válida.
+fn date_year(parts: &[&str]) -> Option<i32> {
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+
+    // ano-mês-dia, dia-mês-ano e mês-dia-ano
+    [(0, 1, 2), (2, 1, 0), (2, 0, 1)]
+        .into_iter()
+        .find_map(|(y, m, d)| {
+            let valid = (1..=12).contains(&numbers[m])
+                && (1..=31).contains(&numbers[d])
+                && parts[m].len() <= 2
+                && parts[d].len() <= 2;
+            valid
+                .then(|| full_year(numbers[y], parts[y].len()))
+                .flatten()
+        })
+}
+
+/// Anos (`1990`) e datas com ou sem separador (`25/12/1990`, `19901225`,
+/// `251290`).
+fn date_matches(chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+
+    for start in 0..chars.len() {
+        for end in start + 3..chars.len().min(start + 10) {
+            let text: String = chars[start..=end].iter().collect();
+            let length = end - start + 1;
+            let all_digits = text.chars().all(|c| c.is_ascii_digit());
+
+            let guesses = if all_digits && length == 4 {
+                text.parse()
+                    .ok()
+                    .and_then(|year| full_year(year, 4))
+                    .map(year_space)
+            } else if all_digits && (length == 6 || length == 8) {
+                let splits: &[[usize; 2]] = if length == 8 {
+                    &[[2, 4], [4, 6]]
+                } else {
+                    &[[2, 4]]
+                };
+                splits.iter().find_map(|[a, b]| {
+                    date_year(&[&text[..*a], &text[*a..*b], &text[*b..]])
+                        .map(|year| 365.0 * year_space(year))
+                })
+            } else {
+                let separator = text.chars().find(|c| !c.is_ascii_digit());
+                separator
+                    .filter(|s| matches!(s, '/' | '-' | '.' | ' ' | '_'))
+                    .and_then(|s| {
+                        let parts: Vec<&str> = text.split(s).collect();
+                        (parts.len() == 3 && parts.iter().all(|p| !p.is_empty()))
+                            .then(|| date_year(&parts))
+                            .flatten()
+                    })
+                    .map(|year| 365.0 * year_space(year) * 4.0)
+            };
+
+            if let Some(guesses) = guesses {
+                matches.push(Match {
+                    start,
+                    end,
+                    guesses,
+                    kind: PatternKind::Date,
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_of(password: &str) -> u8 {
+        estimate(password, &[]).score
+    }
+
+    #[test]
+    fn test_common_patterns_are_weak() {
+        for password in [
+            "",
+            "password",
+            "Senha123",
+            "qwerty123",
+            "p@ssw0rd",
+            "drowssap",
+            "abcdefgh",
+            "aaaaaaaaaa",
+            "25/12/1990",
+            "19901225",
+            "abcabcabc",
+        ] {
+            assert!(score_of(password) <= 1, "{}", password);
+        }
+
+        let estimate = estimate("1qazxsw2", &[]);
+        assert!(estimate.score <= 1);
+        assert!(estimate.patterns.contains(&PatternKind::Keyboard));
+        assert_eq!(
+            super::estimate("zaqwsxcde", &[]).patterns,
+            [PatternKind::Keyboard]
+        );
+    }
+
+    #[test]
+    fn test_random_and_user_inputs() {
+        assert_eq!(score_of("k#9Tq!vZ2@xLm4&w"), 4);
+        assert_eq!(score_of("correto-cavalo-bateria-grampo-lua"), 4);
+
+        // O usuário dentro da senha não conta como segredo
+        assert_eq!(score_of("Octocat8421"), 4);
+        assert!(estimate("Octocat8421", &["GitHub", "octocat"]).score <= 2);
+    }
+}
@@ -0,0 +1,646 @@
+//! Códigos de segundo fator (TOTP, RFC 6238) guardados nos segredos.
+//!
+//! Um segredo pode ter uma semente TOTP, informada como a URI `otpauth://`
+//! dos QR codes ou como a chave em base32 pura. A semente é gravada como URI
+//! normalizada em `secrets.totp_blob`, cifrada como a senha, e nunca volta
+//! para a interface: só os dados de exibição (emissor, conta, algoritmo) e o
+//! código atual.
+//!
+//! Formato da URI (o do Google Authenticator):
+//!
+//! ```text
+//! otpauth://totp/Emissor:conta?secret=BASE32&issuer=Emissor&algorithm=SHA1&digits=6&period=30
+//! ```
+
+use crate::database::{decrypt_column, encrypt_column, SECRET_TOTP_COLUMN};
+use crate::error::{AppError, Result};
+use crate::secret_history::{record_version, snapshot};
+use crate::security::{MasterKey, FIELD_ENCRYPTION_VERSION};
+use crate::vault::Vault;
+use hmac::{Hmac, Mac};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use zeroize::Zeroizing;
+
+const URI_SCHEME: &str = "otpauth://";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub const DEFAULT_DIGITS: u32 = 6;
+pub const DEFAULT_PERIOD: u64 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl TotpAlgorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TotpAlgorithm::Sha1 => "SHA1",
+            TotpAlgorithm::Sha256 => "SHA256",
+            TotpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_uppercase().replace('-', "").as_str() {
+            "SHA1" => Ok(TotpAlgorithm::Sha1),
+            "SHA256" => Ok(TotpAlgorithm::Sha256),
+            "SHA512" => Ok(TotpAlgorithm::Sha512),
+            _ => Err(AppError::InvalidInput(format!(
+                "totp.algorithm value={:?}",
+                value
+            ))),
+        }
+    }
+
+    fn mac(self, key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+        fn digest<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+            let mut mac = <M as Mac>::new_from_slice(key)
+                .map_err(|e| AppError::Crypto(format!("hmac: {}", e)))?;
+            mac.update(message);
+            Ok(mac.finalize().into_bytes().to_vec())
+        }
+
+        match self {
+            TotpAlgorithm::Sha1 => digest::<Hmac<Sha1>>(key, message),
+            TotpAlgorithm::Sha256 => digest::<Hmac<Sha256>>(key, message),
+            TotpAlgorithm::Sha512 => digest::<Hmac<Sha512>>(key, message),
+        }
+    }
+}
+
+/// Semente TOTP completa. Só existe em memória.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotpKey {
+    pub secret: Zeroizing<Vec<u8>>,
+    pub issuer: Option<String>,
+    pub account: Option<String>,
+    pub algorithm: TotpAlgorithm,
+    pub digits: u32,
+    pub period: u64,
+}
+
+/// O que a interface pode ver de uma semente: tudo menos a chave.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpInfo {
+    pub issuer: Option<String>,
+    pub account: Option<String>,
+    pub algorithm: TotpAlgorithm,
+    pub digits: u32,
+    pub period: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpCode {
+    pub code: String,
+    /// Segundos até o código trocar
+    pub seconds_remaining: u64,
+    pub period: u64,
+}
+
+impl TotpKey {
+    /// Lê uma URI `otpauth://totp/...` ou uma chave base32 pura (espaços e
+    /// hífens são ignorados). Chaves puras usam SHA1, 6 dígitos e 30 s.
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+
+        let key = match strip_prefix_ignore_case(input, URI_SCHEME) {
+            Some(rest) => Self::parse_uri(rest)?,
+            None => Self {
+                secret: Zeroizing::new(base32_decode(input)?),
+                issuer: None,
+                account: None,
+                algorithm: TotpAlgorithm::Sha1,
+                digits: DEFAULT_DIGITS,
+                period: DEFAULT_PERIOD,
+            },
+        };
+        key.validate()?;
+
+        Ok(key)
+    }
+
+    fn parse_uri(rest: &str) -> Result<Self> {
+        let (kind, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        if kind.eq_ignore_ascii_case("hotp") {
+            return Err(AppError::InvalidInput(
+                "otpauth.type value=hotp expected=totp".to_string(),
+            ));
+        }
+        if !kind.eq_ignore_ascii_case("totp") {
+            return Err(AppError::InvalidInput(format!(
+                "otpauth.type value={:?} expected=totp",
+                kind
+            )));
+        }
+
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let label = percent_decode(label, false)?;
+        let (mut issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (non_empty(issuer), non_empty(account)),
+            None => (None, non_empty(&label)),
+        };
+
+        let mut secret = None;
+        let mut algorithm = TotpAlgorithm::Sha1;
+        let mut digits = DEFAULT_DIGITS;
+        let mut period = DEFAULT_PERIOD;
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = Zeroizing::new(percent_decode(value, true)?);
+
+            match name.to_ascii_lowercase().as_str() {
+                "secret" => secret = Some(Zeroizing::new(base32_decode(&value)?)),
+                // O parâmetro vale mais que o prefixo do rótulo
+                "issuer" => issuer = non_empty(&value).or(issuer),
+                "algorithm" => algorithm = TotpAlgorithm::parse(&value)?,
+                "digits" => {
+                    digits = value.parse().map_err(|_| {
+                        AppError::InvalidInput(format!("totp.digits value={:?}", *value))
+                    })?
+                }
+                "period" => {
+                    period = value.parse().map_err(|_| {
+                        AppError::InvalidInput(format!("totp.period value={:?}", *value))
+                    })?
+                }
+                // Parâmetros de outros aplicativos (image, color...) são ignorados
+                _ => {}
+            }
+        }
+
+        let secret =
+            secret.ok_or_else(|| AppError::InvalidInput("otpauth.secret missing".to_string()))?;
+
+        Ok(Self {
+            secret,
+            issuer,
+            account,
+            algorithm,
+            digits,
+            period,
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.secret.is_empty() {
+            return Err(AppError::InvalidInput("totp.secret empty".to_string()));
+        }
+        if !(6..=8).contains(&self.digits) {
+            return Err(AppError::InvalidInput(format!(
+                "totp.digits value={} min=6 max=8",
+                self.digits
+            )));
+        }
+        if self.period == 0 {
+            return Err(AppError::InvalidInput("totp.period value=0".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// URI normalizada, como gravada no cofre.
+    pub fn to_uri(&self) -> Zeroizing<String> {
+        let label = match (&self.issuer, &self.account) {
+            (Some(issuer), Some(account)) => {
+                format!("{}:{}", percent_encode(issuer), percent_encode(account))
+            }
+            (Some(name), None) | (None, Some(name)) => percent_encode(name),
+            (None, None) => String::new(),
+        };
+
+        let mut uri = Zeroizing::new(format!(
+            "{}totp/{}?secret={}&algorithm={}&digits={}&period={}",
+            URI_SCHEME,
+            label,
+            *base32_encode(&self.secret),
+            self.algorithm.as_str(),
+            self.digits,
+            self.period,
+        ));
+        if let Some(issuer) = &self.issuer {
+            uri.push_str("&issuer=");
+            uri.push_str(&percent_encode(issuer));
+        }
+
+        uri
+    }
+
+    pub fn info(&self) -> TotpInfo {
+        TotpInfo {
+            issuer: self.issuer.clone(),
+            account: self.account.clone(),
+            algorithm: self.algorithm,
+            digits: self.digits,
+            period: self.period,
+        }
+    }
+
+    /// Código válido no instante `unix_time` (segundos desde 1970).
+    pub fn code_at(&self, unix_time: u64) -> Result<TotpCode> {
+        Ok(TotpCode {
+            code: hotp(
+                &self.secret,
+                unix_time / self.period,
+                self.algorithm,
+                self.digits,
+            )?,
+            seconds_remaining: self.period - unix_time % self.period,
+            period: self.period,
+        })
+    }
+
+    pub fn current_code(&self) -> Result<TotpCode> {
+        self.code_at(chrono::Utc::now().timestamp().max(0) as u64)
+    }
+}
+
+/// HOTP (RFC 4226) com o contador `counter`; o TOTP é o HOTP do passo de tempo.
+pub fn hotp(secret: &[u8], counter: u64, algorithm: TotpAlgorithm, digits: u32) -> Result<String> {
+    let mac = algorithm.mac(secret, &counter.to_be_bytes())?;
+
+    // Truncamento dinâmico
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        mac[offset],
+        mac[offset + 1],
+        mac[offset + 2],
+        mac[offset + 3],
+    ]) & 0x7fff_ffff;
+
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(digits),
+        width = digits as usize
+    ))
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &value[prefix.len()..])
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Base32 (RFC 4648) sem diferenciar maiúsculas, ignorando espaços, hífens e
+/// o preenchimento `=`.
+fn base32_decode(value: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+
+    for c in value.chars() {
+        if c.is_whitespace() || c == '-' || c == '=' {
+            continue;
+        }
+
+        let index = BASE32_ALPHABET
+            .iter()
+            .position(|&b| b == c.to_ascii_uppercase() as u8)
+            .ok_or_else(|| AppError::InvalidInput(format!("totp.secret char={:?}", c)))?;
+
+        buffer = (buffer << 5) | index as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Base32 sem preenchimento, como nas URIs otpauth.
+fn base32_encode(bytes: &[u8]) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::with_capacity(bytes.len().div_ceil(5) * 8));
+    let (mut buffer, mut bits) = (0u32, 0u32);
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    out
+}
+
+/// Decodifica `%XX`; na query, `+` também vale espaço.
+fn percent_decode(value: &str, plus_as_space: bool) -> Result<String> {
+    let invalid = || AppError::InvalidInput(format!("otpauth.uri value={:?}", value));
+
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let hex = [
+                    input.next().ok_or_else(invalid)?,
+                    input.next().ok_or_else(invalid)?,
+                ];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            b'+' if plus_as_space => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Semente TOTP do segredo `secret_id`, se houver. Erro se o segredo não existe.
+pub(crate) fn load_totp(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+) -> Result<Option<Zeroizing<String>>> {
+    let bytes = conn.query_row(
+        "SELECT totp_blob FROM secrets WHERE id = ?1",
+        [secret_id],
+        |row| decrypt_column(row, 0, field_key, SECRET_TOTP_COLUMN, secret_id),
+    )?;
+
+    bytes
+        .map(|bytes| {
+            String::from_utf8(bytes)
+                .map(Zeroizing::new)
+                .map_err(|e| AppError::Internal(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Grava (ou, com `None`, apaga) a URI normalizada do segredo.
+pub(crate) fn store_totp(
+    conn: &Connection,
+    field_key: &MasterKey,
+    secret_id: i64,
+    uri: Option<&str>,
+) -> Result<()> {
+    let totp_blob = uri
+        .map(|uri| encrypt_column(uri.as_bytes(), field_key, SECRET_TOTP_COLUMN, secret_id))
+        .transpose()?;
+
+    conn.execute(
+        "UPDATE secrets SET totp_blob = ?1, encryption_version = ?2 WHERE id = ?3",
+        (totp_blob, FIELD_ENCRYPTION_VERSION, secret_id),
+    )?;
+
+    Ok(())
+}
+
+impl Vault {
+    /// Associa uma semente TOTP (URI `otpauth://` ou base32) ao segredo, ou a
+    /// remove com `None`. A semente anterior entra no histórico de versões.
+    pub fn set_secret_totp(&mut self, id: i64, input: Option<&str>) -> Result<Option<TotpInfo>> {
+        let key = input.map(TotpKey::parse).transpose()?;
+
+        let (conn, field_key) = self.conn_with_key_mut();
+        let tx = conn.transaction()?;
+        let before = snapshot(&tx, field_key, id)?;
+
+        let uri = key.as_ref().map(TotpKey::to_uri);
+        store_totp(&tx, field_key, id, uri.as_deref().map(String::as_str))?;
+        record_version(&tx, field_key, id, &before)?;
+
+        tx.commit()?;
+
+        Ok(key.map(|key| key.info()))
+    }
+
+    /// Dados de exibição da semente TOTP do segredo, sem a chave.
+    pub fn secret_totp(&self, id: i64) -> Result<Option<TotpInfo>> {
+        load_totp(self.conn(), self.field_key(), id)?
+            .map(|uri| TotpKey::parse(&uri).map(|key| key.info()))
+            .transpose()
+    }
+
+    /// Código TOTP atual do segredo e quantos segundos ele ainda vale.
+    pub fn secret_totp_code(&self, id: i64) -> Result<TotpCode> {
+        let uri = load_totp(self.conn(), self.field_key(), id)?
+            .ok_or_else(|| AppError::NotFound(format!("totp secret={}", id)))?;
+
+        TotpKey::parse(&uri)?.current_code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+    use std::fs;
+
+    fn key(secret: &[u8], algorithm: TotpAlgorithm) -> TotpKey {
+        TotpKey {
+            secret: Zeroizing::new(secret.to_vec()),
+            issuer: None,
+            account: None,
+            algorithm,
+            digits: 8,
+            period: 30,
+        }
+    }
+
+    #[test]
+    fn test_rfc_vectors() {
+        // RFC 4226, apêndice D
+        let hotp_codes = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+        for (counter, expected) in hotp_codes.iter().enumerate() {
+            assert_eq!(
+                hotp(
+                    b"12345678901234567890",
+                    counter as u64,
+                    TotpAlgorithm::Sha1,
+                    6
+                )
+                .unwrap(),
+                *expected
+            );
+        }
+
+        // RFC 6238, apêndice B
+        let sha1 = key(b"12345678901234567890", TotpAlgorithm::Sha1);
+        let sha256 = key(b"12345678901234567890123456789012", TotpAlgorithm::Sha256);
+        let sha512 = key(
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+            TotpAlgorithm::Sha512,
+        );
+        let vectors: [(u64, &str, &str, &str); 6] = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1111111111, "14050471", "67062674", "99943326"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+        for (time, code1, code256, code512) in vectors {
+            assert_eq!(sha1.code_at(time).unwrap().code, code1, "SHA1 em {}", time);
+            assert_eq!(
+                sha256.code_at(time).unwrap().code,
+                code256,
+                "SHA256 em {}",
+                time
+            );
+            assert_eq!(
+                sha512.code_at(time).unwrap().code,
+                code512,
+                "SHA512 em {}",
+                time
+            );
+        }
+
+        assert_eq!(sha1.code_at(59).unwrap().seconds_remaining, 1);
+        assert_eq!(sha1.code_at(60).unwrap().seconds_remaining, 30);
+    }
+
+    #[test]
+    fn test_parse_uri_and_base32() {
+        let key = TotpKey::parse(
+            "otpauth://totp/ACME%20Co:john.doe@email.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co&algorithm=SHA256&digits=7&period=60&image=x",
+        )
+        .unwrap();
+        assert_eq!(key.issuer.as_deref(), Some("ACME Co"));
+        assert_eq!(key.account.as_deref(), Some("john.doe@email.com"));
+        assert_eq!(
+            (key.algorithm, key.digits, key.period),
+            (TotpAlgorithm::Sha256, 7, 60)
+        );
+        assert_eq!(key.secret.len(), 20);
+
+        // A URI normalizada é lida de volta igual
+        assert_eq!(TotpKey::parse(&key.to_uri()).unwrap(), key);
+
+        // Base32 em minúsculas, com espaços, vira a mesma chave com os padrões
+        let raw = TotpKey::parse("hxdm vjec jjws rb3h wizr 4ifu gftm xboz").unwrap();
+        assert_eq!(raw.secret, key.secret);
+        assert_eq!((raw.digits, raw.period), (DEFAULT_DIGITS, DEFAULT_PERIOD));
+        assert_eq!(
+            *base32_encode(b"12345678901234567890"),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+        );
+
+        for invalid in [
+            "otpauth://hotp/x?secret=GEZDGNBV&counter=1",
+            "otpauth://totp/x?issuer=y",
+            "otpauth://totp/x?secret=GEZDGNBV&digits=9",
+            "otpauth://totp/x?secret=GEZDGNBV&algorithm=MD5",
+            "otpauth://totp/x?secret=GEZDGNBV&period=0",
+            "chave1!",
+            "",
+        ] {
+            assert!(TotpKey::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_secret_totp_is_stored_encrypted() {
+        let dir = std::env::temp_dir().join("test_totp_vault");
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: "test_totp_vault".to_string(),
+            name: "test_totp_vault".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+        let secret = vault.create_secret("GitHub", "octocat", "senha").unwrap();
+
+        assert_eq!(vault.secret_totp(secret.id).unwrap(), None);
+        assert!(matches!(
+            vault.secret_totp_code(secret.id),
+            Err(AppError::NotFound(_))
+        ));
+
+        let info = vault
+            .set_secret_totp(
+                secret.id,
+                Some("otpauth://totp/GitHub:octocat?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=GitHub"),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.issuer.as_deref(), Some("GitHub"));
+        assert_eq!(vault.secret_totp(secret.id).unwrap(), Some(info));
+
+        let code = vault.secret_totp_code(secret.id).unwrap();
+        let expected = key(b"12345678901234567890", TotpAlgorithm::Sha1);
+        let now = chrono::Utc::now().timestamp() as u64;
+        assert_eq!(code.code.len(), 6);
+        assert!(code.seconds_remaining >= 1 && code.seconds_remaining <= 30);
+        assert!([now, now - 1].iter().any(|t| hotp(
+            &expected.secret,
+            t / 30,
+            TotpAlgorithm::Sha1,
+            6
+        )
+        .unwrap()
+            == code.code));
+
+        // A chave não fica em texto puro no banco
+        let blob: Vec<u8> = vault
+            .conn()
+            .query_row(
+                "SELECT totp_blob FROM secrets WHERE id = ?1",
+                [secret.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&blob).contains("GEZDGNBV"));
+
+        // Trocar a semente entra no histórico, mascarada
+        vault.set_secret_totp(secret.id, None).unwrap();
+        assert_eq!(vault.secret_totp(secret.id).unwrap(), None);
+        let changes = vault
+            .compare_secret_versions(
+                secret.id,
+                Some(vault.secret_versions(secret.id).unwrap()[0].id),
+                None,
+            )
+            .unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "totp");
+        assert!(changes[0].masked);
+
+        assert!(vault.set_secret_totp(999, Some("GEZDGNBV")).is_err());
+
+        drop(vault);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,237 @@
+//! Retenção da lixeira.
+//!
+//! Segredos e projetos na lixeira (`deleted_at` preenchido) são excluídos de
+//! vez depois de `trash_retention_days` dias; zero guarda para sempre. A
+//! limpeza roda ao destrancar o cofre e, com o aplicativo aberto, a cada
+//! [`PURGE_INTERVAL`]. Os anexos dos segredos excluídos vão junto.
+//!
+//! As listagens da lixeira trazem `purge_at`, o instante em que cada item
+//! será excluído.
+
+use crate::database::{set_vault_setting, vault_setting};
+use crate::error::{AppError, Result};
+use crate::vault::Vault;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Dias na lixeira quando o cofre não define outro prazo.
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// Intervalo entre limpezas enquanto o cofre está aberto.
+pub const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+const TRASH_RETENTION_SETTING: &str = "trash_retention_days";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashPurgeSummary {
+    pub secrets: usize,
+    pub projects: usize,
+    pub attachments: usize,
+}
+
+impl TrashPurgeSummary {
+    pub fn is_empty(&self) -> bool {
+        self.secrets == 0 && self.projects == 0
+    }
+}
+
+/// Quando um item apagado em `deleted_at` sai da lixeira. `None` para itens
+/// fora da lixeira ou com a retenção desligada.
+pub fn purge_at(deleted_at: Option<&str>, retention_days: u32) -> Option<String> {
+    if retention_days == 0 {
+        return None;
+    }
+
+    let deleted_at = DateTime::parse_from_rfc3339(deleted_at?).ok()?;
+
+    Some((deleted_at.with_timezone(&Utc) + Duration::days(retention_days as i64)).to_rfc3339())
+}
+
+/// Exclui os itens da lixeira apagados até `cutoff` (RFC 3339), ou todos com
+/// `None`, numa única transação.
+pub(crate) fn purge_trash(
+    conn: &mut Connection,
+    cutoff: Option<&str>,
+) -> Result<TrashPurgeSummary> {
+    const EXPIRED: &str =
+        "deleted_at IS NOT NULL AND (?1 IS NULL OR julianday(deleted_at) <= julianday(?1))";
+
+    let tx = conn.transaction()?;
+
+    // O ON DELETE CASCADE já cuidaria dos anexos; a exclusão explícita é uma
+    // segurança extra e permite contá-los
+    let attachments = tx.execute(
+        &format!(
+            "DELETE FROM attachments WHERE secret_id IN (SELECT id FROM secrets WHERE {})",
+            EXPIRED
+        ),
+        [cutoff],
+    )?;
+    let secrets = tx.execute(&format!("DELETE FROM secrets WHERE {}", EXPIRED), [cutoff])?;
+    let projects = tx.execute(&format!("DELETE FROM projects WHERE {}", EXPIRED), [cutoff])?;
+
+    tx.commit()?;
+
+    Ok(TrashPurgeSummary {
+        secrets,
+        projects,
+        attachments,
+    })
+}
+
+impl Vault {
+    /// Dias que um item fica na lixeira. Zero desliga a limpeza automática.
+    pub fn trash_retention(&self) -> Result<u32> {
+        match vault_setting(self.conn(), TRASH_RETENTION_SETTING)? {
+            Some(value) => value
+                .parse()
+                .map_err(|_| AppError::Internal(format!("trash_retention_days value={:?}", value))),
+            None => Ok(DEFAULT_TRASH_RETENTION_DAYS),
+        }
+    }
+
+    /// Troca o prazo e já exclui o que venceu com o prazo novo.
+    pub fn set_trash_retention(&mut self, days: u32) -> Result<TrashPurgeSummary> {
+        set_vault_setting(self.conn(), TRASH_RETENTION_SETTING, &days.to_string())?;
+
+        self.purge_expired_trash()
+    }
+
+    /// Exclui os itens que estão na lixeira há mais tempo que o prazo do cofre.
+    pub fn purge_expired_trash(&mut self) -> Result<TrashPurgeSummary> {
+        let days = self.trash_retention()?;
+        if days == 0 {
+            return Ok(TrashPurgeSummary::default());
+        }
+
+        let cutoff = (Utc::now() - Duration::days(days as i64)).to_rfc3339();
+        let (conn, _) = self.conn_with_key_mut();
+
+        purge_trash(conn, Some(&cutoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::VaultEntry;
+    use std::fs;
+
+    #[test]
+    fn test_purge_expired_trash() {
+        let dir = std::env::temp_dir().join("test_trash_retention");
+        let _ = fs::remove_dir_all(&dir);
+
+        let entry = VaultEntry {
+            id: "test_trash_retention".to_string(),
+            name: "test_trash_retention".to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        };
+        let mut vault = Vault::create(&entry, "senha").unwrap();
+
+        let old = vault.create_secret("Antigo", "eu", "a").unwrap();
+        let recent = vault.create_secret("Recente", "eu", "b").unwrap();
+        let active = vault.create_secret("Ativo", "eu", "c").unwrap();
+        vault
+            .add_attachment(old.id, "chave.pem", "text/plain", b"conteudo")
+            .unwrap();
+        let old_project = vault.create_project("Velho", None).unwrap();
+        let new_project = vault.create_project("Novo", None).unwrap();
+
+        vault.soft_delete_secret(old.id).unwrap();
+        vault.soft_delete_secret(recent.id).unwrap();
+        vault.soft_delete_project(old_project).unwrap();
+        vault.soft_delete_project(new_project).unwrap();
+        let forty_days_ago = (Utc::now() - Duration::days(40)).to_rfc3339();
+        for table in ["secrets", "projects"] {
+            vault
+                .conn()
+                .execute(
+                    &format!("UPDATE {} SET deleted_at = ?1 WHERE id = ?2", table),
+                    (
+                        &forty_days_ago,
+                        if table == "secrets" {
+                            old.id
+                        } else {
+                            old_project
+                        },
+                    ),
+                )
+                .unwrap();
+        }
+
+        // Prazo de cada item na lixeira
+        let trash = vault.secret_summaries(true).unwrap();
+        assert_eq!(trash.len(), 2);
+        let purge_in_days = |purge_at: &Option<String>| {
+            let purge_at = DateTime::parse_from_rfc3339(purge_at.as_deref().unwrap()).unwrap();
+            (purge_at.with_timezone(&Utc) - Utc::now()).num_days()
+        };
+        assert_eq!(purge_in_days(&trash[0].purge_at), -10);
+        assert_eq!(purge_in_days(&trash[1].purge_at), 29);
+        assert_eq!(vault.secret_summaries(false).unwrap()[0].purge_at, None);
+
+        let summary = vault.purge_expired_trash().unwrap();
+        assert_eq!(
+            summary,
+            TrashPurgeSummary {
+                secrets: 1,
+                projects: 1,
+                attachments: 1,
+            }
+        );
+        let ids = |deleted| -> Vec<i64> {
+            vault
+                .secret_summaries(deleted)
+                .unwrap()
+                .iter()
+                .map(|s| s.id)
+                .collect()
+        };
+        assert_eq!(ids(true), [recent.id]);
+        assert_eq!(ids(false), [active.id]);
+        assert_eq!(vault.projects(true).unwrap()[0].id, new_project);
+        let attachments: i64 = vault
+            .conn()
+            .query_row("SELECT count(*) FROM attachments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(attachments, 0);
+
+        // Sem retenção, nada vence
+        vault.set_trash_retention(0).unwrap();
+        vault
+            .conn()
+            .execute(
+                "UPDATE secrets SET deleted_at = ?1 WHERE deleted_at IS NOT NULL",
+                [&forty_days_ago],
+            )
+            .unwrap();
+        assert!(vault.purge_expired_trash().unwrap().is_empty());
+        assert_eq!(vault.secret_summaries(true).unwrap()[0].purge_at, None);
+
+        // Um prazo menor vale na hora
+        assert_eq!(vault.set_trash_retention(7).unwrap().secrets, 1);
+
+        // A limpeza feita ao destrancar fica guardada para quem abriu o cofre
+        vault.soft_delete_secret(active.id).unwrap();
+        vault
+            .conn()
+            .execute(
+                "UPDATE secrets SET deleted_at = ?1 WHERE id = ?2",
+                (&forty_days_ago, active.id),
+            )
+            .unwrap();
+        drop(vault);
+        let mut vault = Vault::open(&entry, "senha").unwrap();
+        assert_eq!(vault.take_unlock_purge().unwrap().unwrap().secrets, 1);
+        assert!(vault.take_unlock_purge().is_none());
+
+        drop(vault);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
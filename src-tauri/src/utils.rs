@@ -1,27 +1,201 @@
-use std::{env, fs, path::PathBuf};
+use crate::error::{AppError, Result};
+use crate::security::VaultMeta;
+use crate::settings::Settings;
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Pasta de dados do aplicativo. `SECRETS_MANAGER_HOME` substitui o padrão
+/// `~/.secrets-manager`.
+fn get_app_dir() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("SECRETS_MANAGER_HOME").filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
 
-fn get_vaults_dir() -> Result<PathBuf, String> {
     let home_dir = env::var("HOME")
         .or_else(|_| env::var("USERPROFILE"))
-        .map_err(|_| "Não foi possível determinar o diretório home")?;
+        .map_err(|_| AppError::Internal("home_dir missing".to_string()))?;
+
+    Ok(PathBuf::from(home_dir).join(".secrets-manager"))
+}
+
+fn ensure_dir(dir: PathBuf) -> Result<PathBuf> {
+    if !dir.exists() {
+        fs::create_dir_all(&dir)?;
+    }
+
+    Ok(dir)
+}
+
+/// Pasta padrão dos cofres. Guarda o registro e o cofre das versões antigas.
+pub fn get_default_vaults_dir() -> Result<PathBuf> {
+    ensure_dir(get_app_dir()?.join("vaults"))
+}
+
+/// Pasta onde novos cofres são criados: a configurada em settings.json ou a
+/// pasta padrão.
+pub fn get_vaults_dir() -> Result<PathBuf> {
+    match Settings::load(&get_settings_path()?)?.vaults_dir {
+        Some(dir) => ensure_dir(dir),
+        None => get_default_vaults_dir(),
+    }
+}
+
+pub fn get_registry_path() -> Result<PathBuf> {
+    let vaults_dir = get_default_vaults_dir()?;
+    Ok(vaults_dir.join("registry.json"))
+}
+
+pub fn get_settings_path() -> Result<PathBuf> {
+    let app_dir = ensure_dir(get_app_dir()?)?;
+    Ok(app_dir.join("settings.json"))
+}
+
+/// Caminho do arquivo temporário usado enquanto a senha mestre é trocada
+/// (ex: `vault.db` -> `vault.db.rekey`).
+pub fn get_pending_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".rekey");
+    PathBuf::from(name)
+}
+
+/// Grava o arquivo e força a escrita em disco antes de retornar.
+pub fn write_synced(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Renomeia `from` para `to` (substituindo o destino) e sincroniza o diretório,
+/// para que a troca sobreviva a uma queda de energia.
+pub fn replace_file(from: &Path, to: &Path) -> Result<()> {
+    fs::rename(from, to)?;
+
+    #[cfg(unix)]
+    {
+        if let Some(parent) = to.parent() {
+            File::open(parent)?.sync_all()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Grava o arquivo via arquivo temporário + rename, nunca deixando o destino
+/// pela metade.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    write_synced(&tmp_path, contents)?;
+    replace_file(&tmp_path, path)
+}
 
-    let vaults_dir = PathBuf::from(home_dir)
-        .join(".secrets-manager")
-        .join("vaults");
+/// Grava um arquivo que só o dono pode ler (0600 no Unix), para exportações
+/// com valores em texto puro.
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
 
-    if !vaults_dir.exists() {
-        fs::create_dir_all(&vaults_dir).map_err(|e| format!("Erro ao criar diretório: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+
+        // `mode` só vale para arquivos novos
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
     }
 
-    Ok(vaults_dir)
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    Ok(())
 }
 
-pub fn get_meta_path() -> Result<PathBuf, String> {
-    let vaults_dir = get_vaults_dir()?;
-    Ok(vaults_dir.join("vault.meta"))
+/// Lê o `vault.meta`. O `bool` indica se o arquivo ainda está no formato
+/// antigo (apenas o salt) e precisa ser regravado.
+pub fn read_vault_meta(path: &Path) -> Result<(VaultMeta, bool)> {
+    let contents = fs::read_to_string(path)?;
+
+    VaultMeta::parse(&contents)
+}
+
+pub fn write_vault_meta(path: &Path, meta: &VaultMeta) -> Result<()> {
+    write_atomic(path, meta.to_json()?.as_bytes())
+}
+
+/// Conclui ou desfaz uma troca de senha mestre interrompida.
+///
+/// A troca grava `vault.db.rekey` e depois `vault.meta.rekey`, e só então
+/// renomeia o banco (ponto de commit) e o meta. Portanto:
+/// - se `vault.db.rekey` ainda existe, o banco original não foi tocado e os
+///   arquivos pendentes são descartados;
+/// - se só `vault.meta.rekey` existe, o banco já foi trocado e o meta novo
+///   precisa ser promovido.
+pub fn recover_interrupted_rekey(db_path: &Path, meta_path: &Path) -> Result<()> {
+    let pending_db = get_pending_path(db_path);
+    let pending_meta = get_pending_path(meta_path);
+
+    if pending_db.exists() {
+        fs::remove_file(&pending_db)?;
+
+        if pending_meta.exists() {
+            fs::remove_file(&pending_meta)?;
+        }
+    } else if pending_meta.exists() {
+        replace_file(&pending_meta, meta_path)?;
+    }
+
+    Ok(())
 }
 
-pub fn get_db_path() -> Result<PathBuf, String> {
-    let vaults_dir = get_vaults_dir()?;
-    Ok(vaults_dir.join("vault.db"))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_vault(name: &str) -> (PathBuf, PathBuf) {
+        let dir = env::temp_dir().join(format!("secrets-manager-utils-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        (dir.join("vault.db"), dir.join("vault.meta"))
+    }
+
+    #[test]
+    fn test_recover_discards_rekey_before_swap() {
+        let (db_path, meta_path) = temp_vault("before-swap");
+        fs::write(&db_path, "banco antigo").unwrap();
+        fs::write(&meta_path, "salt antigo").unwrap();
+        fs::write(get_pending_path(&db_path), "banco novo").unwrap();
+        fs::write(get_pending_path(&meta_path), "salt novo").unwrap();
+
+        recover_interrupted_rekey(&db_path, &meta_path).unwrap();
+
+        assert_eq!(fs::read_to_string(&db_path).unwrap(), "banco antigo");
+        assert_eq!(fs::read_to_string(&meta_path).unwrap(), "salt antigo");
+        assert!(!get_pending_path(&db_path).exists());
+        assert!(!get_pending_path(&meta_path).exists());
+    }
+
+    #[test]
+    fn test_recover_promotes_meta_after_swap() {
+        let (db_path, meta_path) = temp_vault("after-swap");
+        fs::write(&db_path, "banco novo").unwrap();
+        fs::write(&meta_path, "salt antigo").unwrap();
+        fs::write(get_pending_path(&meta_path), "salt novo").unwrap();
+
+        recover_interrupted_rekey(&db_path, &meta_path).unwrap();
+
+        assert_eq!(fs::read_to_string(&meta_path).unwrap(), "salt novo");
+        assert!(!get_pending_path(&meta_path).exists());
+    }
 }
@@ -0,0 +1,1049 @@
+//! Núcleo do cofre, independente do Tauri.
+//!
+//! [`Vault`] reúne a conexão e a chave das colunas cifradas de um cofre
+//! aberto e concentra as operações sobre ele. Os comandos do Tauri apenas
+//! buscam o cofre no `AppState` e delegam para cá; o `secrets-cli` usa as
+//! mesmas funções sobre o mesmo `vault.db`.
+
+use crate::backup::{self, collect_backup, restore_backup, BackupPayload, ImportSummary};
+use crate::custom_fields::{insert_fields, replace_fields, validate_fields, CustomField};
+use crate::database::{
+    decrypt_column, decrypt_column_string, encrypt_column, export_rekeyed_copy,
+    initialize_database, reencrypt_protected_columns, ATTACHMENT_CONTENT_COLUMN,
+    SECRET_PASSWORD_COLUMN,
+};
+use crate::error::{AppError, Result};
+use crate::i18n::{tf, Msg};
+use crate::models::{AttachmentMetadata, Project, Secret, SecretField, SecretSummary};
+use crate::query::{query_page, ListSpec, Page, QueryOptions, SortDirection, SortField};
+use crate::registry::{VaultEntry, VaultRegistry};
+use crate::secret_history::{record_version, snapshot};
+use crate::secret_kinds::{
+    decrypt_payload, encrypt_payload, kind_from_row, validate_kind, SecretPayload,
+};
+use crate::security::{
+    decrypt_data, derive_field_key, MasterKey, VaultMeta, FIELD_ENCRYPTION_VERSION,
+};
+use crate::trash_retention::{purge_at, purge_trash, TrashPurgeSummary};
+use crate::utils::{
+    get_default_vaults_dir, get_pending_path, get_registry_path, read_vault_meta,
+    recover_interrupted_rekey, replace_file, write_synced, write_vault_meta,
+};
+use rusqlite::{types::Value, Connection, Row};
+use std::{fs, path::PathBuf};
+
+/// Lê o registro de cofres do local padrão.
+pub fn load_registry() -> Result<(VaultRegistry, PathBuf)> {
+    let path = get_registry_path()?;
+    let registry = VaultRegistry::load(&path, &get_default_vaults_dir()?)?;
+
+    Ok((registry, path))
+}
+
+pub struct Vault {
+    entry: VaultEntry,
+    conn: Connection,
+    field_key: MasterKey,
+    /// Resultado da limpeza da lixeira feita ao destrancar, até ser lido
+    unlock_purge: Option<Result<TrashPurgeSummary>>,
+}
+
+impl Vault {
+    /// Valida a senha e abre o cofre, concluindo antes uma troca de senha
+    /// interrompida, se houver.
+    pub fn open(entry: &VaultEntry, password: &str) -> Result<Self> {
+        recover_interrupted_rekey(&entry.db_path, &entry.meta_path)?;
+
+        if !entry.meta_path.exists() {
+            return Err(AppError::VaultNotFound);
+        }
+
+        let (meta, legacy) = read_vault_meta(&entry.meta_path)?;
+
+        let key = meta.derive_key(password)?;
+
+        let mut vault = Self::open_with_key(entry, &key)?;
+
+        // Cofres antigos guardavam apenas o salt. Com a senha validada, regravamos o
+        // meta no formato versionado (mesmo salt e parâmetros, a chave não muda).
+        if legacy {
+            write_vault_meta(&entry.meta_path, &meta)?;
+        }
+
+        // Itens vencidos saem da lixeira ao destrancar; uma falha na limpeza
+        // não impede de abrir o cofre e fica para quem abriu informar
+        vault.unlock_purge = Some(vault.purge_expired_trash());
+
+        Ok(vault)
+    }
+
+    fn open_with_key(entry: &VaultEntry, key: &MasterKey) -> Result<Self> {
+        let conn = initialize_database(&entry.db_path, key)?;
+
+        Ok(Self {
+            entry: entry.clone(),
+            conn,
+            field_key: derive_field_key(key)?,
+            unlock_purge: None,
+        })
+    }
+
+    /// Cria os arquivos de um cofre novo e o abre.
+    pub fn create(entry: &VaultEntry, password: &str) -> Result<Self> {
+        if let Some(dir) = entry.meta_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let meta = VaultMeta::generate();
+
+        write_vault_meta(&entry.meta_path, &meta)?;
+
+        if entry.db_path.exists() {
+            eprintln!(
+                "{}",
+                tf(Msg::CleaningOldDatabase, &[&entry.db_path.display()])
+            );
+            fs::remove_file(&entry.db_path)?;
+        }
+
+        let key = meta.derive_key(password)?;
+
+        Self::open_with_key(entry, &key)
+    }
+
+    pub fn entry(&self) -> &VaultEntry {
+        &self.entry
+    }
+
+    pub fn id(&self) -> &str {
+        &self.entry.id
+    }
+
+    /// Resultado da limpeza da lixeira feita por [`Vault::open`]; só a
+    /// primeira chamada o devolve.
+    pub fn take_unlock_purge(&mut self) -> Option<Result<TrashPurgeSummary>> {
+        self.unlock_purge.take()
+    }
+
+    /// Confere a senha mestre sem reabrir o cofre, para operações que pedem
+    /// confirmação (ex: exportar em texto puro).
+    pub fn verify_password(&self, password: &str) -> Result<()> {
+        let (meta, _) = read_vault_meta(&self.entry.meta_path)?;
+        let field_key = derive_field_key(&meta.derive_key(password)?)?;
+
+        let diff = field_key
+            .key
+            .iter()
+            .zip(self.field_key.key.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return Err(AppError::WrongPassword);
+        }
+
+        Ok(())
+    }
+
+    // Acesso para os módulos que estendem o cofre com `impl Vault`
+    pub(crate) fn conn(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub(crate) fn field_key(&self) -> &MasterKey {
+        &self.field_key
+    }
+
+    /// Conexão mutável junto com a chave dos campos, para transações que
+    /// leem ou gravam colunas protegidas.
+    pub(crate) fn conn_with_key_mut(&mut self) -> (&mut Connection, &MasterKey) {
+        (&mut self.conn, &self.field_key)
+    }
+
+    // --- Segredos ---
+
+    pub fn create_secret(&self, title: &str, username: &str, password: &str) -> Result<Secret> {
+        let tx = self.conn.unchecked_transaction()?;
+        let id = insert_secret(&tx, &self.field_key, title, username, password, None)?;
+        tx.commit()?;
+
+        Ok(new_secret(id, title, username, password, None, Vec::new()))
+    }
+
+    /// Cria um segredo do tipo do `payload` (ou um login sem payload) junto
+    /// com seus campos personalizados, numa única transação.
+    pub fn create_typed_secret(
+        &mut self,
+        title: &str,
+        username: &str,
+        password: &str,
+        payload: Option<&SecretPayload>,
+        fields: &[CustomField],
+    ) -> Result<Secret> {
+        validate_fields(fields)?;
+
+        let tx = self.conn.transaction()?;
+        let id = insert_secret(&tx, &self.field_key, title, username, password, payload)?;
+        insert_fields(&tx, &self.field_key, id, fields)?;
+        tx.commit()?;
+
+        let mut fields = fields.to_vec();
+        fields.sort_by_key(|f| f.position);
+        for (position, field) in fields.iter_mut().enumerate() {
+            field.position = position as i64;
+        }
+
+        Ok(new_secret(
+            id,
+            title,
+            username,
+            password,
+            payload.cloned(),
+            fields,
+        ))
+    }
+
+    /// Segredos sem nenhum valor sensível. Para ler a senha use
+    /// [`Vault::reveal_secret_field`].
+    pub fn secret_summaries(&self, deleted: bool) -> Result<Vec<SecretSummary>> {
+        Ok(self
+            .secret_summaries_page(deleted, &QueryOptions::default())?
+            .items)
+    }
+
+    pub fn secret_summaries_page(
+        &self,
+        deleted: bool,
+        options: &QueryOptions,
+    ) -> Result<Page<SecretSummary>> {
+        let mut page = query_page(
+            &self.conn,
+            &SECRET_LIST,
+            &[(deleted_filter(deleted), Vec::new())],
+            options,
+            summary_from_row,
+        )?;
+
+        if deleted {
+            let retention = self.trash_retention()?;
+            for secret in &mut page.items {
+                secret.purge_at = purge_at(secret.deleted_at.as_deref(), retention);
+            }
+        }
+
+        Ok(page)
+    }
+
+    pub fn secret_summary(&self, id: i64) -> Result<SecretSummary> {
+        let summary = self.conn.query_row(
+            "SELECT id, project_id, title, username, created_at, deleted_at, kind, coalesce(breach_count, 0) > 0 FROM secrets WHERE id = ?1",
+            [id],
+            summary_from_row,
+        )?;
+
+        Ok(summary)
+    }
+
+    /// Decifra um único campo de um segredo. Cada chamada é registrada no
+    /// `audit_log`; se o registro falhar, o valor não é revelado. O payload
+    /// volta em JSON, vazio para logins.
+    pub fn reveal_secret_field(&mut self, id: i64, field: SecretField) -> Result<String> {
+        let tx = self.conn.transaction()?;
+
+        let value = match field {
+            SecretField::Password => tx.query_row(
+                "SELECT password_blob FROM secrets WHERE id = ?1",
+                [id],
+                |row| decrypt_column_string(row, 0, &self.field_key, SECRET_PASSWORD_COLUMN, id),
+            )?,
+            SecretField::Payload => {
+                let payload = tx.query_row(
+                    "SELECT payload_blob FROM secrets WHERE id = ?1",
+                    [id],
+                    |row| decrypt_payload(row, 0, &self.field_key, id),
+                )?;
+
+                match payload {
+                    Some(payload) => serde_json::to_string(&payload)
+                        .map_err(|e| AppError::Internal(e.to_string()))?,
+                    None => String::new(),
+                }
+            }
+        };
+
+        tx.execute(
+            "INSERT INTO audit_log (action, secret_id, field, created_at) VALUES ('reveal', ?1, ?2, ?3)",
+            (id, field.as_str(), chrono::Utc::now().to_rfc3339()),
+        )?;
+
+        tx.commit()?;
+
+        Ok(value)
+    }
+
+    /// Atualiza um segredo. Sem `password`, a senha atual é mantida; com
+    /// `fields`, os campos personalizados são substituídos. O estado anterior
+    /// entra no histórico de versões.
+    pub fn update_secret(
+        &mut self,
+        id: i64,
+        title: &str,
+        username: &str,
+        password: Option<&str>,
+        fields: Option<&[CustomField]>,
+    ) -> Result<()> {
+        if let Some(fields) = fields {
+            validate_fields(fields)?;
+        }
+
+        let tx = self.conn.transaction()?;
+        let before = snapshot(&tx, &self.field_key, id)?;
+
+        match password {
+            Some(password) => {
+                let password_blob = encrypt_column(
+                    password.as_bytes(),
+                    &self.field_key,
+                    SECRET_PASSWORD_COLUMN,
+                    id,
+                )?;
+
+                tx.execute(
+                    "UPDATE secrets SET title = ?1, username = ?2, password_blob = ?3, encryption_version = ?4 WHERE id = ?5",
+                    (title, username, password_blob, FIELD_ENCRYPTION_VERSION, id),
+                )?
+            }
+            None => tx.execute(
+                "UPDATE secrets SET title = ?1, username = ?2 WHERE id = ?3",
+                (title, username, id),
+            )?,
+        };
+
+        if let Some(fields) = fields {
+            replace_fields(&tx, &self.field_key, id, fields)?;
+        }
+
+        record_version(&tx, &self.field_key, id, &before)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Troca os dados estruturados do segredo, e com eles o tipo. Sem
+    /// `payload`, o segredo volta a ser um login.
+    pub fn update_secret_payload(
+        &mut self,
+        id: i64,
+        payload: Option<&SecretPayload>,
+    ) -> Result<()> {
+        let kind = payload.map(SecretPayload::kind).unwrap_or_default();
+        validate_kind(kind, payload)?;
+
+        let tx = self.conn.transaction()?;
+        let before = snapshot(&tx, &self.field_key, id)?;
+
+        let payload_blob = encrypt_payload(payload, &self.field_key, id)?;
+
+        tx.execute(
+            "UPDATE secrets SET kind = ?1, payload_blob = ?2, encryption_version = ?3 WHERE id = ?4",
+            (kind.as_str(), payload_blob, FIELD_ENCRYPTION_VERSION, id),
+        )?;
+
+        record_version(&tx, &self.field_key, id, &before)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Move o segredo para um projeto (ou o tira de qualquer projeto).
+    pub fn set_secret_project(&self, id: i64, project_id: Option<i64>) -> Result<()> {
+        if let Some(project_id) = project_id {
+            self.project(project_id)?;
+        }
+
+        let updated = self.conn.execute(
+            "UPDATE secrets SET project_id = ?1 WHERE id = ?2",
+            (project_id, id),
+        )?;
+
+        expect_found(updated, "secret", id)
+    }
+
+    pub fn soft_delete_secret(&self, id: i64) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE secrets SET deleted_at = ?1 WHERE id = ?2",
+            (chrono::Utc::now().to_rfc3339(), id),
+        )?;
+
+        expect_found(updated, "secret", id)
+    }
+
+    pub fn delete_secret(&self, id: i64) -> Result<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM secrets WHERE id = ?", (id,))?;
+
+        expect_found(deleted, "secret", id)
+    }
+
+    pub fn restore_secret(&self, id: i64) -> Result<()> {
+        let updated = self
+            .conn
+            .execute("UPDATE secrets SET deleted_at = NULL WHERE id = ?", (id,))?;
+
+        expect_found(updated, "secret", id)
+    }
+
+    // --- Projetos ---
+
+    pub fn create_project(&self, name: &str, description: Option<&str>) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO projects (name, description) VALUES (?1, ?2)",
+            (name, description),
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn projects(&self, deleted: bool) -> Result<Vec<Project>> {
+        Ok(self.projects_page(deleted, &QueryOptions::default())?.items)
+    }
+
+    pub fn projects_page(&self, deleted: bool, options: &QueryOptions) -> Result<Page<Project>> {
+        let mut page = query_page(
+            &self.conn,
+            &PROJECT_LIST,
+            &[(deleted_filter(deleted), Vec::new())],
+            options,
+            |row| {
+                Ok(Project {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    created_at: row.get(3)?,
+                    deleted_at: row.get(4)?,
+                    purge_at: None,
+                })
+            },
+        )?;
+
+        if deleted {
+            let retention = self.trash_retention()?;
+            for project in &mut page.items {
+                project.purge_at = purge_at(project.deleted_at.as_deref(), retention);
+            }
+        }
+
+        Ok(page)
+    }
+
+    pub fn project(&self, id: i64) -> Result<Project> {
+        let project = self.conn.query_row(
+            "SELECT id, name, description, created_at FROM projects WHERE id = ?1",
+            [id],
+            |row| {
+                Ok(Project {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    created_at: row.get(3)?,
+                    deleted_at: None,
+                    purge_at: None,
+                })
+            },
+        )?;
+
+        Ok(project)
+    }
+
+    pub fn update_project(&self, id: i64, name: &str, description: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET name = ?1, description = ?2 WHERE id = ?3",
+            (name, description, id),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn soft_delete_project(&mut self, id: i64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+
+        // Passo 1: "Soltar" os segredos (Setar project_id = NULL)
+        // Isso é redundante se o banco suportar e estiver com PRAGMA foreign_keys = ON,
+        // mas é uma segurança extra essencial em SQLite embutido.
+        tx.execute(
+            "UPDATE secrets SET project_id = NULL WHERE project_id = ?1",
+            [id],
+        )?;
+
+        // Passo 2: Soft deletar o projeto
+        tx.execute(
+            "UPDATE projects SET deleted_at = ?1 WHERE id = ?2",
+            (chrono::Utc::now().to_rfc3339(), id),
+        )?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn delete_project(&mut self, id: i64) -> Result<()> {
+        // Iniciamos uma transação para garantir atomicidade
+        let tx = self.conn.transaction()?;
+
+        // Passo 1: "Soltar" os segredos (Setar project_id = NULL)
+        // Isso é redundante se o banco suportar e estiver com PRAGMA foreign_keys = ON,
+        // mas é uma segurança extra essencial em SQLite embutido.
+        tx.execute(
+            "UPDATE secrets SET project_id = NULL WHERE project_id = ?1",
+            [id],
+        )?;
+
+        // Passo 2: Deletar o projeto
+        tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub fn restore_project(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("UPDATE projects SET deleted_at = NULL WHERE id = ?", [id])?;
+
+        Ok(())
+    }
+
+    // --- Anexos ---
+
+    pub fn add_attachment(
+        &self,
+        secret_id: i64,
+        filename: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<AttachmentMetadata> {
+        let file_size = content.len() as i64;
+
+        // O conteúdo é cifrado com o id da linha, que só existe após o INSERT
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO attachments (secret_id, filename, mime_type, file_size, content, encryption_version) VALUES (?1, ?2, ?3, ?4, x'', ?5)",
+            (
+                secret_id,
+                filename,
+                mime_type,
+                file_size,
+                FIELD_ENCRYPTION_VERSION,
+            ),
+        )?;
+
+        let id = tx.last_insert_rowid();
+        let content = encrypt_column(content, &self.field_key, ATTACHMENT_CONTENT_COLUMN, id)?;
+        tx.execute(
+            "UPDATE attachments SET content = ?1 WHERE id = ?2",
+            (content, id),
+        )?;
+        tx.commit()?;
+
+        Ok(AttachmentMetadata {
+            id,
+            secret_id,
+            filename: filename.to_string(),
+            mime_type: mime_type.to_string(),
+            file_size,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    pub fn attachments_metadata(&self, secret_id: i64) -> Result<Vec<AttachmentMetadata>> {
+        Ok(self
+            .attachments_page(secret_id, &QueryOptions::default())?
+            .items)
+    }
+
+    pub fn attachments_page(
+        &self,
+        secret_id: i64,
+        options: &QueryOptions,
+    ) -> Result<Page<AttachmentMetadata>> {
+        query_page(
+            &self.conn,
+            &ATTACHMENT_LIST,
+            &[("secret_id = ?", vec![Value::Integer(secret_id)])],
+            options,
+            |row| {
+                Ok(AttachmentMetadata {
+                    id: row.get(0)?,
+                    secret_id: row.get(1)?,
+                    filename: row.get(2)?,
+                    mime_type: row.get(3)?,
+                    file_size: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            },
+        )
+    }
+
+    pub fn attachment_content(&self, attachment_id: i64) -> Result<Vec<u8>> {
+        let content = self.conn.query_row(
+            "SELECT content FROM attachments WHERE id = ?1",
+            [attachment_id],
+            |row| {
+                decrypt_column(
+                    row,
+                    0,
+                    &self.field_key,
+                    ATTACHMENT_CONTENT_COLUMN,
+                    attachment_id,
+                )
+            },
+        )?;
+
+        Ok(content.unwrap_or_default())
+    }
+
+    pub fn delete_attachment(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM attachments WHERE id = ?1", (id,))?;
+
+        Ok(())
+    }
+
+    // --- Lixeira ---
+
+    /// Exclui tudo o que está na lixeira, sem esperar o prazo de retenção.
+    pub fn empty_trash(&mut self) -> Result<()> {
+        purge_trash(&mut self.conn, None)?;
+
+        Ok(())
+    }
+
+    // --- Backup ---
+
+    /// Cofre completo num container de backup protegido por `password`.
+    pub fn export_backup(&self, password: &str) -> Result<Vec<u8>> {
+        let payload = collect_backup(&self.conn, &self.field_key)?;
+
+        let json_data =
+            serde_json::to_string(&payload).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // O backup carrega o próprio salt e parâmetros de KDF, então pode ser
+        // importado em qualquer máquina apenas com a senha.
+        backup::seal(json_data.as_bytes(), password)
+    }
+
+    pub fn import_backup(&mut self, data: &[u8], password: &str) -> Result<ImportSummary> {
+        let json_string = if backup::is_backup_container(data) {
+            let plaintext = backup::open(data, password)?;
+            String::from_utf8(plaintext).map_err(|e| AppError::InvalidBackup(e.to_string()))?
+        } else {
+            // Backups antigos (sem cabeçalho) foram cifrados com o salt deste cofre
+            let (meta, _) = read_vault_meta(&self.entry.meta_path)?;
+            let key = meta.derive_key(password)?;
+
+            decrypt_data(data, &key)?
+        };
+
+        let payload = BackupPayload::from_json(&json_string)?;
+
+        restore_backup(&mut self.conn, &self.field_key, &payload)
+    }
+}
+
+const SECRET_SORTS: &[(SortField, &str)] = &[
+    (SortField::Id, "id"),
+    (SortField::Title, "title COLLATE NOCASE"),
+    (SortField::Username, "coalesce(username, '') COLLATE NOCASE"),
+    (SortField::CreatedAt, "coalesce(datetime(created_at), '')"),
+    (SortField::DeletedAt, "coalesce(datetime(deleted_at), '')"),
+];
+
+/// `breach_count` como o flag `breached` das listagens
+const BREACHED_COLUMN: &str = "coalesce(breach_count, 0) > 0";
+
+const SECRET_LIST: ListSpec = ListSpec {
+    table: "secrets",
+    columns: &[
+        "id",
+        "project_id",
+        "title",
+        "username",
+        "created_at",
+        "deleted_at",
+        "kind",
+        BREACHED_COLUMN,
+    ],
+    sorts: SECRET_SORTS,
+    default_sort: (SortField::Id, SortDirection::Asc),
+    project_filter: Some("project_id = ?"),
+    has_deleted_at: true,
+};
+
+const PROJECT_LIST: ListSpec = ListSpec {
+    table: "projects",
+    columns: &["id", "name", "description", "created_at", "deleted_at"],
+    sorts: &[
+        (SortField::Id, "id"),
+        (SortField::Title, "name COLLATE NOCASE"),
+        (SortField::CreatedAt, "coalesce(datetime(created_at), '')"),
+        (SortField::DeletedAt, "coalesce(datetime(deleted_at), '')"),
+    ],
+    default_sort: (SortField::Title, SortDirection::Asc),
+    project_filter: None,
+    has_deleted_at: true,
+};
+
+const ATTACHMENT_LIST: ListSpec = ListSpec {
+    table: "attachments",
+    columns: &[
+        "id",
+        "secret_id",
+        "filename",
+        "mime_type",
+        "file_size",
+        "created_at",
+    ],
+    sorts: &[
+        (SortField::Id, "id"),
+        (SortField::Title, "filename COLLATE NOCASE"),
+        (SortField::CreatedAt, "coalesce(datetime(created_at), '')"),
+        (SortField::Size, "file_size"),
+    ],
+    default_sort: (SortField::CreatedAt, SortDirection::Desc),
+    project_filter: Some("secret_id IN (SELECT id FROM secrets WHERE project_id = ?)"),
+    has_deleted_at: false,
+};
+
+fn deleted_filter(deleted: bool) -> &'static str {
+    if deleted {
+        "deleted_at IS NOT NULL"
+    } else {
+        "deleted_at IS NULL"
+    }
+}
+
+/// Insere o segredo validando o payload contra o tipo. A senha e o payload
+/// são gravados logo após o INSERT, cifrados com o id da linha; chame dentro
+/// de uma transação.
+fn insert_secret(
+    conn: &Connection,
+    field_key: &MasterKey,
+    title: &str,
+    username: &str,
+    password: &str,
+    payload: Option<&SecretPayload>,
+) -> Result<i64> {
+    let kind = payload.map(SecretPayload::kind).unwrap_or_default();
+    validate_kind(kind, payload)?;
+
+    conn.execute(
+        "INSERT INTO secrets (title, username, kind, encryption_version) VALUES (?1, ?2, ?3, ?4)",
+        (title, username, kind.as_str(), FIELD_ENCRYPTION_VERSION),
+    )?;
+
+    let id = conn.last_insert_rowid();
+    let password_blob = encrypt_column(password.as_bytes(), field_key, SECRET_PASSWORD_COLUMN, id)?;
+    let payload_blob = encrypt_payload(payload, field_key, id)?;
+
+    conn.execute(
+        "UPDATE secrets SET password_blob = ?1, payload_blob = ?2 WHERE id = ?3",
+        (password_blob, payload_blob, id),
+    )?;
+
+    Ok(id)
+}
+
+fn new_secret(
+    id: i64,
+    title: &str,
+    username: &str,
+    password: &str,
+    payload: Option<SecretPayload>,
+    fields: Vec<CustomField>,
+) -> Secret {
+    Secret {
+        id,
+        title: title.to_string(),
+        username: username.to_string(),
+        password: password.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        deleted_at: None,
+        project_id: None,
+        kind: payload
+            .as_ref()
+            .map(SecretPayload::kind)
+            .unwrap_or_default(),
+        payload,
+        fields,
+        password_changed_at: None,
+        breached: false,
+        breach_count: None,
+        purge_at: None,
+    }
+}
+
+fn summary_from_row(row: &Row) -> rusqlite::Result<SecretSummary> {
+    Ok(SecretSummary {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        title: row.get(2)?,
+        username: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        created_at: row.get(4)?,
+        deleted_at: row.get(5)?,
+        kind: kind_from_row(row, 6)?,
+        breached: row.get(7)?,
+        purge_at: None,
+    })
+}
+
+fn expect_found(changed: usize, what: &str, id: i64) -> Result<()> {
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("{} id={}", what, id)));
+    }
+
+    Ok(())
+}
+
+/// Troca a senha mestre do cofre `entry`. Se o cofre estiver aberto em
+/// `slot`, ele é fechado durante a troca e reaberto com a nova senha.
+pub fn change_master_password(
+    entry: &VaultEntry,
+    old_password: &str,
+    new_password: &str,
+    slot: &mut Option<Vault>,
+) -> Result<()> {
+    let (db_path, meta_path) = (&entry.db_path, &entry.meta_path);
+    recover_interrupted_rekey(db_path, meta_path)?;
+
+    if !meta_path.exists() {
+        return Err(AppError::VaultNotFound);
+    }
+
+    let was_open = slot.as_ref().is_some_and(|vault| vault.id() == entry.id);
+
+    let (meta, _) = read_vault_meta(meta_path)?;
+    let old_key = meta.derive_key(old_password)?;
+    let conn = initialize_database(db_path, &old_key)?;
+
+    // O meta novo usa um salt novo e os parâmetros de KDF atuais
+    let new_meta = VaultMeta::generate();
+    let new_key = new_meta.derive_key(new_password)?;
+
+    let pending_db = get_pending_path(db_path);
+    let pending_meta = get_pending_path(meta_path);
+
+    // Passo 1: Copiar o banco para um arquivo novo, cifrado com a nova chave
+    export_rekeyed_copy(&conn, &pending_db, &new_key)?;
+    drop(conn);
+
+    // Passo 2: Garantir que a cópia abre com a nova chave antes de qualquer troca
+    // e recifrar as colunas protegidas, cuja chave também deriva da chave mestra
+    let mut pending_conn = initialize_database(&pending_db, &new_key)?;
+    reencrypt_protected_columns(
+        &mut pending_conn,
+        &derive_field_key(&old_key)?,
+        &derive_field_key(&new_key)?,
+    )?;
+    drop(pending_conn);
+
+    // Passo 3: Gravar o novo meta ao lado do antigo
+    write_synced(&pending_meta, new_meta.to_json()?.as_bytes())?;
+
+    // Passo 4: Fechar a conexão atual e trocar os arquivos. A troca do banco é o
+    // ponto de commit; se algo falhar depois dela, recover_interrupted_rekey
+    // promove o meta pendente na próxima abertura.
+    if was_open {
+        *slot = None;
+    }
+    replace_file(&pending_db, db_path)?;
+    replace_file(&pending_meta, meta_path)?;
+
+    if was_open {
+        *slot = Some(Vault::open_with_key(entry, &new_key)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_entry(name: &str) -> VaultEntry {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+
+        VaultEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            db_path: dir.join("vault.db"),
+            meta_path: dir.join("vault.meta"),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            external: false,
+        }
+    }
+
+    #[test]
+    fn test_vault_secret_lifecycle() {
+        let entry = temp_entry("test_vault_secret_lifecycle");
+
+        {
+            let mut vault = Vault::create(&entry, "senha").unwrap();
+
+            let secret = vault.create_secret("GitHub", "dev", "ghp_123").unwrap();
+            vault
+                .update_secret(secret.id, "GitHub (pessoal)", "dev", None, None)
+                .unwrap();
+
+            assert_eq!(
+                vault
+                    .reveal_secret_field(secret.id, SecretField::Password)
+                    .unwrap(),
+                "ghp_123"
+            );
+            assert_eq!(
+                vault.secret_summary(secret.id).unwrap().title,
+                "GitHub (pessoal)"
+            );
+
+            vault.soft_delete_secret(secret.id).unwrap();
+            assert!(vault.secret_summaries(false).unwrap().is_empty());
+            assert_eq!(vault.secret_summaries(true).unwrap()[0].id, secret.id);
+
+            vault.restore_secret(secret.id).unwrap();
+            assert_eq!(vault.secret_summaries(false).unwrap().len(), 1);
+
+            assert!(matches!(
+                vault.delete_secret(secret.id + 1),
+                Err(AppError::NotFound(_))
+            ));
+        }
+
+        // O mesmo cofre, aberto de novo pelo caminho do CLI
+        assert!(matches!(
+            Vault::open(&entry, "errada"),
+            Err(AppError::WrongPassword)
+        ));
+        let mut vault = Vault::open(&entry, "senha").unwrap();
+        let id = vault.secret_summaries(false).unwrap()[0].id;
+        assert_eq!(
+            vault
+                .reveal_secret_field(id, SecretField::Password)
+                .unwrap(),
+            "ghp_123"
+        );
+
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_secret_pages_follow_cursor() {
+        let entry = temp_entry("test_vault_secret_pages");
+        let vault = Vault::create(&entry, "senha").unwrap();
+
+        let project = vault.create_project("Infra", None).unwrap();
+        for title in ["delta", "Alfa", "charlie", "Bravo", "eco"] {
+            let secret = vault.create_secret(title, "", "x").unwrap();
+            if title != "eco" {
+                vault.set_secret_project(secret.id, Some(project)).unwrap();
+            }
+        }
+
+        let mut options = QueryOptions {
+            limit: Some(2),
+            sort: Some(SortField::Title),
+            direction: Some(SortDirection::Desc),
+            project_id: Some(project),
+            ..Default::default()
+        };
+        let mut titles = Vec::new();
+        loop {
+            let page = vault.secret_summaries_page(false, &options).unwrap();
+            assert!(page.items.len() <= 2);
+            titles.extend(page.items.into_iter().map(|s| s.title));
+            match page.next_cursor {
+                Some(cursor) => options.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(titles, ["delta", "charlie", "Bravo", "Alfa"]);
+
+        // Segredos sem usuário (NULL) também aparecem ao paginar por usuário
+        vault
+            .conn()
+            .execute(
+                "UPDATE secrets SET username = NULL WHERE title IN ('eco', 'Alfa')",
+                [],
+            )
+            .unwrap();
+        let mut options = QueryOptions {
+            limit: Some(2),
+            sort: Some(SortField::Username),
+            ..Default::default()
+        };
+        let mut count = 0;
+        loop {
+            let page = vault.secret_summaries_page(false, &options).unwrap();
+            count += page.items.len();
+            match page.next_cursor {
+                Some(cursor) => options.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(count, 5);
+
+        let future = QueryOptions {
+            created_from: Some("2999-01-01".into()),
+            ..Default::default()
+        };
+        assert!(vault
+            .secret_summaries_page(false, &future)
+            .unwrap()
+            .items
+            .is_empty());
+
+        // Ordenações e filtros que a listagem não tem são recusados
+        let by_username = QueryOptions {
+            sort: Some(SortField::Username),
+            ..Default::default()
+        };
+        assert!(matches!(
+            vault.projects_page(false, &by_username),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_change_master_password_reopens_slot() {
+        let entry = temp_entry("test_vault_change_password");
+
+        let vault = Vault::create(&entry, "antiga").unwrap();
+        vault.create_secret("AWS", "root", "aws_123").unwrap();
+
+        let mut slot = Some(vault);
+        assert!(matches!(
+            change_master_password(&entry, "errada", "nova", &mut slot),
+            Err(AppError::WrongPassword)
+        ));
+        assert!(slot.is_some(), "Senha errada não deveria fechar o cofre");
+
+        change_master_password(&entry, "antiga", "nova", &mut slot).unwrap();
+        let mut vault = slot.unwrap();
+        let id = vault.secret_summaries(false).unwrap()[0].id;
+        assert_eq!(
+            vault
+                .reveal_secret_field(id, SecretField::Password)
+                .unwrap(),
+            "aws_123"
+        );
+        drop(vault);
+
+        assert!(Vault::open(&entry, "antiga").is_err());
+        assert!(Vault::open(&entry, "nova").is_ok());
+
+        let _ = fs::remove_dir_all(entry.db_path.parent().unwrap());
+    }
+}
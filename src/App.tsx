@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { Eye, EyeOff, Lock, Plus } from 'lucide-react';
 import { Suspense, use, useActionState, useEffect, useState } from 'react';
 import { toast } from 'sonner';
@@ -26,6 +27,8 @@ import { vaultSchema } from './lib/schemas';
 import { Dashboard } from './routes/Dashboard';
 
 import './assets/css/global.css';
+import { getErrorMessage } from '@/lib/utils';
+import type { VaultInfo } from '@/types';
 
 interface FormState {
   status: string;
@@ -37,7 +40,9 @@ let vaultStatusCache: Promise<boolean> | null = null;
 
 function getVaultStatusPromise(): Promise<boolean> {
   if (!vaultStatusCache) {
-    vaultStatusCache = invoke<boolean>('check_vault_status');
+    vaultStatusCache = invoke<VaultInfo[]>('check_vault_status').then(
+      (vaults) => vaults.some((vault) => vault.initialized),
+    );
   }
   return vaultStatusCache;
 }
@@ -88,6 +93,24 @@ function ZoomKeyboardShortcuts() {
   return null;
 }
 
+// Avisos das limpezas automáticas da lixeira (ao destrancar e periódicas)
+function TrashPurgeNotifications() {
+  useEffect(() => {
+    const unlisteners = Promise.all([
+      listen<string>('trash-purged', (event) => toast.info(event.payload)),
+      listen<string>('trash-purge-failed', (event) =>
+        toast.error(event.payload),
+      ),
+    ]);
+
+    return () => {
+      unlisteners.then((unlisten) => unlisten.forEach((fn) => fn()));
+    };
+  }, []);
+
+  return null;
+}
+
 function App() {
   const [password, setPassword] = useState('');
   const [showPassword, setShowPassword] = useState(false);
@@ -135,7 +158,7 @@ function App() {
       return { status: '', success: true };
     } catch (error) {
       console.error(error);
-      toast.error(`Erro: ${error}`);
+      toast.error(`Erro: ${getErrorMessage(error)}`);
       return { status: '', success: false };
     }
   }
@@ -252,6 +275,7 @@ function App() {
         </div>
 
         <Toaster />
+        <TrashPurgeNotifications />
       </ZoomProvider>
     </ThemeProvider>
   );
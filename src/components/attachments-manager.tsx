@@ -20,7 +20,8 @@ import {
   AlertDialogTitle,
 } from '@/components/ui/alert-dialog';
 import { Button } from '@/components/ui/button';
-import type { AttachmentMetadata } from '@/types';
+import type { AttachmentMetadata, Page } from '@/types';
+import { getErrorMessage } from '@/lib/utils';
 
 interface AttachmentsManagerProps {
   secretId: number | null;
@@ -45,9 +46,10 @@ function getAttachmentsPromise(
   secretId: number,
 ): Promise<AttachmentMetadata[]> {
   if (!attachmentsCache.has(secretId)) {
-    const promise = invoke<AttachmentMetadata[]>('get_attachments_metadata', {
-      secretId,
-    });
+    const promise = invoke<Page<AttachmentMetadata>>(
+      'get_attachments_metadata',
+      { secretId },
+    ).then(({ items }) => items);
     attachmentsCache.set(secretId, promise);
   }
   return attachmentsCache.get(secretId) as Promise<AttachmentMetadata[]>;
@@ -308,7 +310,7 @@ export function AttachmentsManager({
       invalidateAttachmentsCache(secretId);
       setRefreshKey((prev) => prev + 1);
     } catch (error) {
-      toast.error(`Erro ao remover arquivo: ${error}`);
+      toast.error(`Erro ao remover arquivo: ${getErrorMessage(error)}`);
     } finally {
       setPendingDeleteId(null);
     }
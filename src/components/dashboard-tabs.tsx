@@ -1,5 +1,5 @@
 import { Suspense } from 'react';
-import type { Project, Secret } from '../types';
+import type { Project, SecretSummary } from '../types';
 import { ProjectList } from './project-list';
 import { ProjectsListSkeleton } from './projects-list-skeleton';
 import { SecretList } from './secret-list';
@@ -7,10 +7,11 @@ import { SecretsListSkeleton } from './secrets-list-skeleton';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from './ui/tabs';
 
 interface DashboardTabsProps {
-  secrets: Secret[];
+  secrets: SecretSummary[];
   projects: Project[];
   refreshKey: number;
   onCopy: (text: string) => Promise<void>;
+  onCopyPassword: (id: number) => Promise<void>;
   onEditSecret: (id: number) => void;
   onDeleteSecret: (id: number) => void;
   onEditProject: (id: number) => void;
@@ -22,6 +23,7 @@ export function DashboardTabs({
   projects,
   refreshKey,
   onCopy,
+  onCopyPassword,
   onEditSecret,
   onDeleteSecret,
   onEditProject,
@@ -39,6 +41,7 @@ export function DashboardTabs({
             <SecretList
               secrets={secrets}
               onCopy={onCopy}
+              onCopyPassword={onCopyPassword}
               onEdit={onEditSecret}
               onDeleteClick={onDeleteSecret}
             />
@@ -1,13 +1,14 @@
 import { Copy, Edit, Trash2 } from 'lucide-react';
-import type { Secret } from '../types';
+import type { SecretSummary } from '../types';
 import { Button } from './ui/button';
 import { Card, CardContent } from './ui/card';
 import { Separator } from './ui/separator';
 import { Spinner } from './ui/spinner';
 
 interface SecretCardProps {
-  secret: Secret;
+  secret: SecretSummary;
   onCopy: (text: string) => void;
+  onCopyPassword: (id: number) => void;
   onEdit: (id: number) => void;
   onDelete: (id: number) => void;
   isDeleting: boolean;
@@ -16,6 +17,7 @@ interface SecretCardProps {
 export function SecretCard({
   secret,
   onCopy,
+  onCopyPassword,
   onEdit,
   onDelete,
   isDeleting,
@@ -85,20 +87,13 @@ export function SecretCard({
               <span className='min-w-16 text-muted-foreground text-sm'>
                 Senha:
               </span>
-              <span
-                className='max-w-24 font-mono text-sm'
-                title={secret.password}
-              >
-                {Array(secret.password?.length || 1)
-                  .fill('•')
-                  .toString()
-                  .replace(/,/g, '')}
-              </span>
+              {/* A senha só sai do backend ao copiar */}
+              <span className='max-w-24 font-mono text-sm'>••••••••</span>
               <Button
                 variant='ghost'
                 size='icon'
                 className='h-8 w-8'
-                onClick={() => onCopy(secret.password ?? '')}
+                onClick={() => onCopyPassword(secret.id)}
                 title='Copiar senha'
               >
                 <Copy className='h-4 w-4' />
@@ -1,5 +1,5 @@
 import { Key, Plus } from 'lucide-react';
-import type { Secret } from '../types';
+import type { SecretSummary } from '../types';
 import { SecretCard } from './secret-card';
 import { Button } from './ui/button';
 import {
@@ -12,8 +12,9 @@ import {
 } from './ui/empty';
 
 interface SecretListProps {
-  secrets: Secret[];
+  secrets: SecretSummary[];
   onCopy: (text: string) => Promise<void>;
+  onCopyPassword: (id: number) => Promise<void>;
   onEdit: (id: number) => void;
   onDeleteClick: (id: number) => void;
   onCreateClick?: () => void;
@@ -22,6 +23,7 @@ interface SecretListProps {
 export function SecretList({
   secrets,
   onCopy,
+  onCopyPassword,
   onEdit,
   onDeleteClick,
   onCreateClick,
@@ -51,11 +53,12 @@ export function SecretList({
 
   return (
     <div className='flex flex-col gap-3'>
-      {secrets.map((s: Secret) => (
+      {secrets.map((s: SecretSummary) => (
         <SecretCard
           key={s.id}
           secret={s}
           onCopy={onCopy}
+          onCopyPassword={onCopyPassword}
           onEdit={onEdit}
           onDelete={onDeleteClick}
           isDeleting={false}
@@ -1,6 +1,6 @@
 import { Folder, Key, RefreshCcw, Trash2 } from 'lucide-react';
 import { useState } from 'react';
-import type { Project, Secret } from '../types';
+import type { Project, SecretSummary } from '../types';
 import {
   AlertDialog,
   AlertDialogAction,
@@ -20,7 +20,9 @@ import {
   EmptyTitle,
 } from './ui/empty';
 
-export type TrashItem = (Secret | Project) & { type: 'secret' | 'project' };
+export type TrashItem = (SecretSummary | Project) & {
+  type: 'secret' | 'project';
+};
 
 interface TrashListProps {
   items: TrashItem[];
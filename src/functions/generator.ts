@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { GeneratedSecret, GeneratorOptions } from '../types';
+
+// Gera no backend com o CSPRNG do sistema. Sem opções e com projectId, usa a
+// política do projeto
+export async function generateSecret(
+  options?: GeneratorOptions,
+  projectId?: number,
+): Promise<GeneratedSecret> {
+  return await invoke<GeneratedSecret>('generate_secret', {
+    options,
+    projectId,
+  });
+}
+
+export async function getGeneratorPolicy(
+  projectId: number,
+): Promise<GeneratorOptions | null> {
+  return await invoke<GeneratorOptions | null>('get_generator_policy', {
+    projectId,
+  });
+}
+
+// null remove a política e o projeto volta ao padrão
+export async function setGeneratorPolicy(
+  projectId: number,
+  options: GeneratorOptions | null,
+): Promise<void> {
+  await invoke('set_generator_policy', { projectId, options });
+}
@@ -0,0 +1,24 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BreachCheckSummary, HealthReport } from '../types';
+
+// Relatório de saúde do cofre; nenhuma senha vem no resultado
+export async function getVaultHealthReport(): Promise<HealthReport> {
+  return await invoke<HealthReport>('vault_health_report');
+}
+
+export async function getPasswordMaxAge(): Promise<number> {
+  return await invoke<number>('get_password_max_age');
+}
+
+// 0 desliga a verificação de senhas antigas
+export async function setPasswordMaxAge(days: number): Promise<string> {
+  return await invoke<string>('set_password_max_age', { days });
+}
+
+// Confere as senhas numa cópia local do HIBP (arquivo ordenado ou diretório de
+// faixas); nada vai para a rede
+export async function checkBreachedPasswords(
+  path: string,
+): Promise<BreachCheckSummary> {
+  return await invoke<BreachCheckSummary>('check_breached_passwords', { path });
+}
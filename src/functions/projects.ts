@@ -1,5 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Project } from '../types';
+import type {
+  EnvBinding,
+  Page,
+  Project,
+  ProjectEnvVar,
+  QueryOptions,
+} from '../types';
 
 // Cache for Promise management
 let projectsCache: Promise<Project[]> | null = null;
@@ -10,26 +16,40 @@ let deletedProjectsData: Project[] | null = null;
 
 export function loadProjectsPromise(): Promise<Project[]> {
   if (!projectsCache) {
-    projectsCache = invoke<Project[]>('get_all_projects').then((data) => {
-      projectsData = data;
-      return data;
-    });
+    projectsCache = invoke<Page<Project>>('get_all_projects').then(
+      ({ items }) => {
+        projectsData = items;
+        return items;
+      },
+    );
   }
   return projectsCache;
 }
 
 export function loadDeletedProjectsPromise(): Promise<Project[]> {
   if (!deletedProjectsCache) {
-    deletedProjectsCache = invoke<Project[]>('get_deleted_projects').then(
-      (data) => {
-        deletedProjectsData = data;
-        return data;
+    deletedProjectsCache = invoke<Page<Project>>('get_deleted_projects').then(
+      ({ items }) => {
+        deletedProjectsData = items;
+        return items;
       },
     );
   }
   return deletedProjectsCache;
 }
 
+export async function getProjectsPage(
+  options?: QueryOptions,
+): Promise<Page<Project>> {
+  return await invoke<Page<Project>>('get_all_projects', { options });
+}
+
+export async function getDeletedProjectsPage(
+  options?: QueryOptions,
+): Promise<Page<Project>> {
+  return await invoke<Page<Project>>('get_deleted_projects', { options });
+}
+
 export function invalidateProjectsCache() {
   projectsCache = null;
   projectsData = null;
@@ -78,3 +98,48 @@ export async function deleteProject(id: number): Promise<void> {
 export async function restoreProject(id: number): Promise<void> {
   await invoke<void>('restore_project', { id });
 }
+
+export async function getProjectEnvVars(
+  projectId: number,
+): Promise<ProjectEnvVar[]> {
+  return await invoke<ProjectEnvVar[]>('get_project_env_vars', { projectId });
+}
+
+export async function getProjectEnvBindings(
+  projectId: number,
+): Promise<EnvBinding[]> {
+  return await invoke<EnvBinding[]>('get_project_env_bindings', { projectId });
+}
+
+export async function setProjectEnvVar(
+  projectId: number,
+  secretTitle: string,
+  envName: string,
+): Promise<ProjectEnvVar> {
+  return await invoke<ProjectEnvVar>('set_project_env_var', {
+    projectId,
+    secretTitle,
+    envName,
+  });
+}
+
+export async function removeProjectEnvVar(
+  projectId: number,
+  secretTitle: string,
+): Promise<void> {
+  await invoke<void>('remove_project_env_var', { projectId, secretTitle });
+}
+
+export async function importProjectDotenv(
+  projectId: number,
+  filePath: string,
+): Promise<string> {
+  return await invoke<string>('import_project_dotenv', { projectId, filePath });
+}
+
+export async function exportProjectDotenv(
+  projectId: number,
+  filePath: string,
+): Promise<string> {
+  return await invoke<string>('export_project_dotenv', { projectId, filePath });
+}
@@ -0,0 +1,10 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { SearchFilters, SearchHit } from '../types';
+
+// Busca no backend (FTS5); valores protegidos nunca são pesquisados
+export async function search(
+  query: string,
+  filters?: SearchFilters,
+): Promise<SearchHit[]> {
+  return await invoke<SearchHit[]>('search', { query, filters });
+}
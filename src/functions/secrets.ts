@@ -1,32 +1,42 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Secret } from '../types';
+import type {
+  CustomField,
+  Page,
+  QueryOptions,
+  Secret,
+  SecretField,
+  SecretPayload,
+  SecretSummary,
+  SecretVersion,
+  TotpCode,
+  TotpInfo,
+  VersionChange,
+} from '../types';
 
-// Cache para gerenciar o estado da Promise
-let secretsCache: Promise<Secret[]> | null = null;
-let secretsData: Secret[] | null = null;
+// Cache para gerenciar o estado da Promise. As listagens trazem só metadados;
+// valores sensíveis vêm de `revealSecretField`.
+let secretsCache: Promise<SecretSummary[]> | null = null;
+let secretsData: SecretSummary[] | null = null;
 
-let deletedSecretsCache: Promise<Secret[]> | null = null;
-let deletedSecretsData: Secret[] | null = null;
+let deletedSecretsCache: Promise<SecretSummary[]> | null = null;
+let deletedSecretsData: SecretSummary[] | null = null;
 
-export function loadSecretsPromise(): Promise<Secret[]> {
+export function loadSecretsPromise(): Promise<SecretSummary[]> {
   if (!secretsCache) {
-    secretsCache = invoke<Secret[]>('get_all_secrets').then((data) => {
-      console.log(data);
-      secretsData = data;
-      return data;
+    secretsCache = listSecrets().then(({ items }) => {
+      secretsData = items;
+      return items;
     });
   }
   return secretsCache;
 }
 
-export function loadDeletedSecretsPromise(): Promise<Secret[]> {
+export function loadDeletedSecretsPromise(): Promise<SecretSummary[]> {
   if (!deletedSecretsCache) {
-    deletedSecretsCache = invoke<Secret[]>('get_deleted_secrets').then(
-      (data) => {
-        deletedSecretsData = data;
-        return data;
-      },
-    );
+    deletedSecretsCache = listDeletedSecrets().then(({ items }) => {
+      deletedSecretsData = items;
+      return items;
+    });
   }
   return deletedSecretsCache;
 }
@@ -38,11 +48,11 @@ export function invalidateSecretsCache() {
   deletedSecretsData = null;
 }
 
-export function getSecretsFromCache(): Secret[] | null {
+export function getSecretsFromCache(): SecretSummary[] | null {
   return secretsData;
 }
 
-export function getDeletedSecretsFromCache(): Secret[] | null {
+export function getDeletedSecretsFromCache(): SecretSummary[] | null {
   return deletedSecretsData;
 }
 
@@ -51,12 +61,16 @@ export async function createSecret(
   username: string,
   password: string,
   projectId?: number,
+  payload?: SecretPayload,
+  fields?: CustomField[],
 ): Promise<Secret> {
   return await invoke<Secret>('create_secret', {
     title,
     username,
     password,
     projectId,
+    payload,
+    fields,
   });
 }
 
@@ -66,11 +80,26 @@ export async function updateSecret(
   username: string,
   password: string,
   projectId?: number,
+  fields?: CustomField[],
 ): Promise<void> {
-  const updateData = { title, username, password, id, projectId };
+  const updateData = { title, username, password, id, projectId, fields };
   await invoke<void>('update_secret', updateData);
 }
 
+export async function updateSecretPayload(
+  id: number,
+  payload?: SecretPayload,
+): Promise<void> {
+  await invoke<void>('update_secret_payload', { id, payload });
+}
+
+export async function setSecretProject(
+  id: number,
+  projectId?: number,
+): Promise<void> {
+  await invoke<void>('set_secret_project', { id, projectId });
+}
+
 export async function softDeleteSecret(id: number): Promise<void> {
   await invoke<void>('soft_delete_secret', { id });
 }
@@ -82,3 +111,85 @@ export async function deleteSecret(id: number): Promise<void> {
 export async function restoreSecret(id: number): Promise<void> {
   await invoke<void>('restore_secret', { id });
 }
+
+// Com `options.limit`, continue com `options.cursor = page.nextCursor`
+export async function listSecrets(
+  options?: QueryOptions,
+): Promise<Page<SecretSummary>> {
+  return await invoke<Page<SecretSummary>>('list_secrets', { options });
+}
+
+export async function listDeletedSecrets(
+  options?: QueryOptions,
+): Promise<Page<SecretSummary>> {
+  return await invoke<Page<SecretSummary>>('list_deleted_secrets', {
+    options,
+  });
+}
+
+export async function revealSecretField(
+  id: number,
+  field: SecretField,
+): Promise<string> {
+  return await invoke<string>('reveal_secret_field', { id, field });
+}
+
+export async function getSecretFields(id: number): Promise<CustomField[]> {
+  return await invoke<CustomField[]>('get_secret_fields', { id });
+}
+
+export async function revealCustomField(
+  id: number,
+  name: string,
+): Promise<string> {
+  return await invoke<string>('reveal_custom_field', { id, name });
+}
+
+export async function getSecretVersions(id: number): Promise<SecretVersion[]> {
+  return await invoke<SecretVersion[]>('get_secret_versions', { id });
+}
+
+// Sem `from`/`to`, o lado correspondente é o estado atual do segredo
+export async function compareSecretVersions(
+  id: number,
+  from?: number,
+  to?: number,
+): Promise<VersionChange[]> {
+  return await invoke<VersionChange[]>('compare_secret_versions', {
+    id,
+    from,
+    to,
+  });
+}
+
+export async function restoreSecretVersion(
+  id: number,
+  versionId: number,
+): Promise<string> {
+  return await invoke<string>('restore_secret_version', { id, versionId });
+}
+
+export async function getHistoryLimit(): Promise<number> {
+  return await invoke<number>('get_history_limit');
+}
+
+export async function setHistoryLimit(limit: number): Promise<string> {
+  return await invoke<string>('set_history_limit', { limit });
+}
+
+// Aceita a URI otpauth:// de um QR code ou a chave base32; sem uri, remove a
+// semente
+export async function setSecretTotp(
+  id: number,
+  uri?: string,
+): Promise<TotpInfo | null> {
+  return await invoke<TotpInfo | null>('set_secret_totp', { id, uri });
+}
+
+export async function getSecretTotp(id: number): Promise<TotpInfo | null> {
+  return await invoke<TotpInfo | null>('get_secret_totp', { id });
+}
+
+export async function getTotpCode(id: number): Promise<TotpCode> {
+  return await invoke<TotpCode>('get_totp_code', { id });
+}
@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Locale, Settings } from '../types';
+
+export async function getSettings(): Promise<Settings> {
+  return await invoke<Settings>('get_settings');
+}
+
+export async function getLocale(): Promise<Locale> {
+  return await invoke<Locale>('get_locale');
+}
+
+export async function setLocale(locale: Locale): Promise<string> {
+  return await invoke<string>('set_locale', { locale });
+}
+
+export async function setVaultsDir(path: string | null): Promise<string> {
+  return await invoke<string>('set_vaults_dir', { path });
+}
@@ -0,0 +1,16 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TrashPurgeSummary } from '../types';
+
+// Dias que um item fica na lixeira; 0 guarda para sempre
+export async function getTrashRetention(): Promise<number> {
+  return await invoke<number>('get_trash_retention');
+}
+
+// O prazo novo já vale: o que venceu com ele é excluído na hora
+export async function setTrashRetention(days: number): Promise<string> {
+  return await invoke<string>('set_trash_retention', { days });
+}
+
+export async function purgeExpiredTrash(): Promise<TrashPurgeSummary> {
+  return await invoke<TrashPurgeSummary>('purge_expired_trash');
+}
@@ -1,4 +1,12 @@
 import { invoke } from '@tauri-apps/api/core';
+import type {
+  CsvMapping,
+  ExportFormat,
+  ExportReport,
+  ExternalImportSummary,
+  ImportFormat,
+  VaultInfo,
+} from '../types';
 
 export async function exportVault(
   filePath: string,
@@ -19,3 +27,78 @@ export async function importVault(
     password,
   });
 }
+
+export async function exportPlaintext(
+  format: ExportFormat,
+  filePath: string,
+  masterPassword: string,
+): Promise<ExportReport> {
+  return await invoke<ExportReport>('export_plaintext', {
+    format,
+    filePath,
+    masterPassword,
+  });
+}
+
+export async function importExternal(
+  format: ImportFormat,
+  filePath: string,
+  mapping?: CsvMapping,
+): Promise<ExternalImportSummary> {
+  return await invoke<ExternalImportSummary>('import_external', {
+    format,
+    filePath,
+    mapping,
+  });
+}
+
+export async function changeMasterPassword(
+  oldPassword: string,
+  newPassword: string,
+  vaultId?: string,
+): Promise<string> {
+  return await invoke<string>('change_master_password', {
+    oldPassword,
+    newPassword,
+    vaultId,
+  });
+}
+
+export async function listVaults(): Promise<VaultInfo[]> {
+  return await invoke<VaultInfo[]>('list_vaults');
+}
+
+export async function createVault(
+  name: string,
+  password: string,
+): Promise<VaultInfo> {
+  return await invoke<VaultInfo>('create_vault', { name, password });
+}
+
+export async function openVault(
+  vaultId: string,
+  password: string,
+): Promise<string> {
+  return await invoke<string>('open_vault', { vaultId, password });
+}
+
+export async function renameVault(
+  vaultId: string,
+  name: string,
+): Promise<string> {
+  return await invoke<string>('rename_vault', { vaultId, name });
+}
+
+export async function deleteVault(
+  vaultId: string,
+  password: string,
+): Promise<string> {
+  return await invoke<string>('delete_vault', { vaultId, password });
+}
+
+export async function openVaultAt(
+  path: string,
+  password: string,
+): Promise<VaultInfo> {
+  return await invoke<VaultInfo>('open_vault_at', { path, password });
+}
@@ -1,6 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useState } from 'react';
 import { toast } from 'sonner';
+import { getErrorMessage } from '@/lib/utils';
 
 interface UseEmptyTrashProps {
   onRefresh: () => void;
@@ -23,7 +24,7 @@ export function useEmptyTrash({ onRefresh }: UseEmptyTrashProps) {
       toast.success('Lixeira esvaziada com sucesso!');
       onRefresh();
     } catch (e) {
-      toast.error(`Erro ao esvaziar lixeira: ${e}`);
+      toast.error(`Erro ao esvaziar lixeira: ${getErrorMessage(e)}`);
     } finally {
       setIsEmptying(false);
     }
@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { toast } from 'sonner';
 import { softDeleteProject } from '../../functions/projects';
+import { getErrorMessage } from '@/lib/utils';
 
 interface UseProjectDeletionProps {
   onRefresh: () => void;
@@ -26,7 +27,7 @@ export function useProjectDeletion({ onRefresh }: UseProjectDeletionProps) {
       toast.success('Projeto movido para a lixeira!');
       onRefresh();
     } catch (e) {
-      toast.error(`Erro ao deletar: ${e}`);
+      toast.error(`Erro ao deletar: ${getErrorMessage(e)}`);
     } finally {
       setPendingDeleteId(null);
     }
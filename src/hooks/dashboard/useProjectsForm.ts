@@ -6,6 +6,7 @@ import {
   updateProject,
 } from '../../functions/projects';
 import { projectSchema } from '../../lib/schemas';
+import { getErrorMessage } from '@/lib/utils';
 
 interface ProjectFormData {
   name: string;
@@ -58,7 +59,7 @@ export function useProjectsForm({ onRefresh }: UseProjectsFormProps) {
       onRefresh();
       toast.success('Projeto criado com sucesso!');
     } catch (e) {
-      toast.error(`Erro ao salvar: ${e}`);
+      toast.error(`Erro ao salvar: ${getErrorMessage(e)}`);
     }
     return null;
   }
@@ -90,7 +91,7 @@ export function useProjectsForm({ onRefresh }: UseProjectsFormProps) {
       onRefresh();
       toast.success('Projeto atualizado com sucesso!');
     } catch (e) {
-      toast.error(`Erro ao editar: ${e}`);
+      toast.error(`Erro ao editar: ${getErrorMessage(e)}`);
     }
     return null;
   }
@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { toast } from 'sonner';
 import { softDeleteSecret } from '../../functions/secrets';
+import { getErrorMessage } from '@/lib/utils';
 
 interface UseSecretDeletionProps {
   onRefresh: () => void;
@@ -33,7 +34,7 @@ export function useSecretDeletion({
       toast.success('Segredo movido para a lixeira!');
       onRefresh();
     } catch (e) {
-      toast.error(`Erro ao deletar: ${e}`);
+      toast.error(`Erro ao deletar: ${getErrorMessage(e)}`);
       // Em um cenário ideal, faríamos rollback do optimistic update aqui se falhasse,
       // mas como o refresh vai acontecer de qualquer jeito ou o erro vai estourar,
       // a lista vai acabar voltando ao estado real no próximo fetch.
@@ -4,10 +4,12 @@ import type { AttachmentsManagerRef } from '../../components/attachments-manager
 import {
   createSecret,
   getSecretsFromCache,
+  revealSecretField,
   updateSecret,
 } from '../../functions/secrets';
 import { secretSchema } from '../../lib/schemas';
-import type { Secret } from '../../types';
+import type { SecretSummary } from '../../types';
+import { getErrorMessage } from '@/lib/utils';
 
 interface SecretFormData {
   title: string;
@@ -18,8 +20,8 @@ interface SecretFormData {
 
 interface UseSecretsFormProps {
   onRefresh: () => void;
-  onOptimisticCreate?: (secret: Secret) => void;
-  onOptimisticUpdate?: (secret: Secret) => void;
+  onOptimisticCreate?: (secret: SecretSummary) => void;
+  onOptimisticUpdate?: (secret: SecretSummary) => void;
 }
 
 export function useSecretsForm({
@@ -89,13 +91,13 @@ export function useSecretsForm({
     const { title, username, password, projectId } = validation.data;
 
     // Optimistic Create
-    const tempSecret: Secret = {
+    const tempSecret: SecretSummary = {
       id: Date.now(), // ID temporário
       title,
       username: username || '',
-      password,
       createdAt: new Date().toISOString(),
       projectId: projectId,
+      kind: 'login',
     };
     onOptimisticCreate?.(tempSecret);
 
@@ -112,7 +114,7 @@ export function useSecretsForm({
       onRefresh();
       toast.success('Segredo criado com sucesso!');
     } catch (e) {
-      toast.error(`Erro ao salvar: ${e}`);
+      toast.error(`Erro ao salvar: ${getErrorMessage(e)}`);
       onRefresh(); // Reverte optimistic em caso de erro
     }
     return null;
@@ -162,13 +164,13 @@ export function useSecretsForm({
       const original = getSecretsFromCache()?.find(
         (s) => s.id === editSecretId,
       );
-      const updatedSecret: Secret = {
+      const updatedSecret: SecretSummary = {
         id: editSecretId,
         title,
         username: username || '',
-        password,
         createdAt: original?.createdAt || new Date().toISOString(),
         projectId: projectId,
+        kind: original?.kind ?? 'login',
       };
       onOptimisticUpdate?.(updatedSecret);
 
@@ -186,7 +188,7 @@ export function useSecretsForm({
       onRefresh();
       toast.success('Segredo atualizado com sucesso!');
     } catch (e) {
-      toast.error(`Erro ao editar: ${e}`);
+      toast.error(`Erro ao editar: ${getErrorMessage(e)}`);
       onRefresh();
     }
     return null;
@@ -203,7 +205,7 @@ export function useSecretsForm({
     setIsCreateSecret(true);
   }
 
-  function openEdit(id: number) {
+  async function openEdit(id: number) {
     const secrets = getSecretsFromCache();
 
     if (!secrets) {
@@ -214,10 +216,19 @@ export function useSecretsForm({
     const editSecret = secrets.find((s) => s.id === id);
 
     if (editSecret) {
+      // A listagem não traz a senha; ela só é lida ao abrir a edição
+      let password: string;
+      try {
+        password = await revealSecretField(id, 'password');
+      } catch (e) {
+        toast.error(`Erro ao carregar senha: ${getErrorMessage(e)}`);
+        return;
+      }
+
       setForm({
         title: editSecret.title,
         username: editSecret.username,
-        password: editSecret.password ?? '',
+        password,
         projectId: editSecret.projectId?.toString(),
       });
       setIsEditSecret(true);
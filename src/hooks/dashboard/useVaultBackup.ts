@@ -2,6 +2,7 @@ import { open, save } from '@tauri-apps/plugin-dialog';
 import { useState } from 'react';
 import { toast } from 'sonner';
 import { exportVault, importVault } from '../../functions/vault';
+import { getErrorMessage } from '@/lib/utils';
 
 interface UseVaultBackupProps {
   onRefresh: () => void;
@@ -47,7 +48,7 @@ export function useVaultBackup({ onRefresh }: UseVaultBackupProps) {
       setDialogPassword('');
     } catch (e) {
       console.error(e);
-      toast.error(`Erro ao exportar: ${e}`);
+      toast.error(`Erro ao exportar: ${getErrorMessage(e)}`);
     } finally {
       setIsExporting(false);
     }
@@ -75,7 +76,7 @@ export function useVaultBackup({ onRefresh }: UseVaultBackupProps) {
       setShowImportDialog(true);
     } catch (e) {
       console.error(e);
-      toast.error(`Erro ao selecionar arquivo: ${e}`);
+      toast.error(`Erro ao selecionar arquivo: ${getErrorMessage(e)}`);
       setIsImporting(false);
     }
   }
@@ -92,7 +93,7 @@ export function useVaultBackup({ onRefresh }: UseVaultBackupProps) {
       setPendingImportFilePath(null);
     } catch (e) {
       console.error(e);
-      toast.error(`Erro ao importar: ${e}`);
+      toast.error(`Erro ao importar: ${getErrorMessage(e)}`);
     } finally {
       setIsImporting(false);
     }
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useCallback, useEffect, useRef } from 'react';
+import { getErrorMessage } from '@/lib/utils';
 
 const MS_BASE = 1000;
 const TIMEOUT_MS = 2 * 60 * MS_BASE; // 2-minute wait until it auto-locks
@@ -15,7 +16,7 @@ export function useAutoLock(isLoggedIn: boolean, onLock: () => void) {
       await invoke('lock_vault');
       onLock();
     } catch (e) {
-      console.error(`Falha ao trancar cofre: ${e}`);
+      console.error(`Falha ao trancar cofre: ${getErrorMessage(e)}`);
     }
   }, [isLoggedIn, onLock]);
 
@@ -44,6 +44,7 @@ import {
   loadSecretsPromise,
   deleteSecret as permanentlyDeleteSecret,
   restoreSecret,
+  revealSecretField,
 } from '../functions/secrets';
 import { useEmptyTrash } from '../hooks/dashboard/useEmptyTrash';
 import { useProjectDeletion } from '../hooks/dashboard/useProjectDeletion';
@@ -51,14 +52,18 @@ import { useProjectsForm } from '../hooks/dashboard/useProjectsForm';
 import { useSecretDeletion } from '../hooks/dashboard/useSecretDeletion';
 import { useSecretsForm } from '../hooks/dashboard/useSecretsForm';
 import { useVaultBackup } from '../hooks/dashboard/useVaultBackup';
-import type { Project, Secret } from '../types';
+import type { Project, SecretSummary } from '../types';
+import { getErrorMessage } from '@/lib/utils';
 
 type OptimisticAction =
-  | { type: 'create'; secret: Secret }
-  | { type: 'update'; secret: Secret }
+  | { type: 'create'; secret: SecretSummary }
+  | { type: 'update'; secret: SecretSummary }
   | { type: 'delete'; id: number };
 
-function dashboardReducer(state: Secret[], action: OptimisticAction): Secret[] {
+function dashboardReducer(
+  state: SecretSummary[],
+  action: OptimisticAction,
+): SecretSummary[] {
   switch (action.type) {
     case 'create':
       return [action.secret, ...state];
@@ -80,9 +85,9 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
   const [isInitialLoad, setIsInitialLoad] = useState(true);
 
   // Estados para dados carregados
-  const [secrets, setSecrets] = useState<Secret[]>([]);
+  const [secrets, setSecrets] = useState<SecretSummary[]>([]);
   const [projects, setProjects] = useState<Project[]>([]);
-  const [deletedSecrets, setDeletedSecrets] = useState<Secret[]>([]);
+  const [deletedSecrets, setDeletedSecrets] = useState<SecretSummary[]>([]);
   const [deletedProjects, setDeletedProjects] = useState<Project[]>([]);
 
   // Estado Otimista (apenas Segredos por enquanto)
@@ -113,7 +118,7 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
         setDeletedProjects(deletedProjectsData);
         setIsInitialLoad(false);
       } catch (error) {
-        toast.error(`Erro ao carregar dados: ${error}`);
+        toast.error(`Erro ao carregar dados: ${getErrorMessage(error)}`);
         setIsInitialLoad(false);
       }
     }
@@ -144,7 +149,7 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
         setDeletedSecrets(deletedSecretsData);
         setDeletedProjects(deletedProjectsData);
       } catch (error) {
-        toast.error(`Erro ao atualizar dados: ${error}`);
+        toast.error(`Erro ao atualizar dados: ${getErrorMessage(error)}`);
       }
     });
   }
@@ -173,7 +178,15 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
       await copyToClipboard(text);
       toast.success('Copiado para a área de transferência!');
     } catch (e) {
-      toast.error(`Erro ao copiar: ${e}`);
+      toast.error(`Erro ao copiar: ${getErrorMessage(e)}`);
+    }
+  }
+
+  async function handleCopyPassword(id: number) {
+    try {
+      await handleCopy(await revealSecretField(id, 'password'));
+    } catch (e) {
+      toast.error(`Erro ao copiar: ${getErrorMessage(e)}`);
     }
   }
 
@@ -197,7 +210,7 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
       }
       refreshAll();
     } catch (e) {
-      toast.error(`Erro ao restaurar: ${e}`);
+      toast.error(`Erro ao restaurar: ${getErrorMessage(e)}`);
     }
   }
 
@@ -212,7 +225,7 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
       }
       refreshAll();
     } catch (e) {
-      toast.error(`Erro ao excluir: ${e}`);
+      toast.error(`Erro ao excluir: ${getErrorMessage(e)}`);
     }
   }
 
@@ -355,6 +368,7 @@ function DashboardContent({ onLogout }: { onLogout: () => void }) {
                     <SecretList
                       secrets={activeSecrets}
                       onCopy={handleCopy}
+                      onCopyPassword={handleCopyPassword}
                       onEdit={secretForm.openEdit}
                       onDeleteClick={secretDeletion.requestDelete}
                       onCreateClick={secretForm.openCreate}
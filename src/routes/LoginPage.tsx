@@ -18,6 +18,8 @@ import {
 } from '../components/ui/input-group';
 import { Label } from '../components/ui/label';
 import { vaultSchema } from '../lib/schemas';
+import { getErrorMessage } from '@/lib/utils';
+import type { VaultInfo } from '@/types';
 
 interface LoginPageProps {
   onLogin: () => void;
@@ -33,7 +35,9 @@ let vaultStatusCache: Promise<boolean> | null = null;
 
 function getVaultStatusPromise(): Promise<boolean> {
   if (!vaultStatusCache) {
-    vaultStatusCache = invoke<boolean>('check_vault_status');
+    vaultStatusCache = invoke<VaultInfo[]>('check_vault_status').then(
+      (vaults) => vaults.some((vault) => vault.initialized),
+    );
   }
   return vaultStatusCache;
 }
@@ -94,7 +98,7 @@ export function LoginPage({ onLogin }: LoginPageProps) {
       return { status: '', success: true };
     } catch (error) {
       console.error(error);
-      toast.error(`Erro: ${error}`);
+      toast.error(`Erro: ${getErrorMessage(error)}`);
       return { status: '', success: false };
     }
   }
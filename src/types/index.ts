@@ -6,8 +6,92 @@ export interface Secret {
   createdAt: string;
   projectId?: number;
   deletedAt?: string | null;
+  kind: SecretKind;
+  payload?: SecretPayload | null;
+  fields: CustomField[];
+  // Última troca de senha; vazio enquanto for a da criação
+  passwordChangedAt?: string | null;
+  // Senha encontrada na base local do HIBP
+  breached?: boolean;
+  // Vezes que a senha aparece na base; vazio enquanto não verificada
+  breachCount?: number | null;
+  // Quando sai da lixeira; só nos itens excluídos
+  purgeAt?: string | null;
 }
 
+export interface SecretSummary {
+  id: number;
+  title: string;
+  username: string;
+  createdAt: string;
+  projectId?: number;
+  deletedAt?: string | null;
+  kind: SecretKind;
+  breached?: boolean;
+  purgeAt?: string | null;
+}
+
+export type SecretField = 'password' | 'payload';
+
+export type CustomFieldType =
+  | 'text'
+  | 'hidden'
+  | 'url'
+  | 'email'
+  | 'date'
+  | 'number';
+
+export interface CustomField {
+  name: string;
+  /** Ausente em campos ocultos não revelados; ao salvar, mantém o valor atual */
+  value?: string | null;
+  type: CustomFieldType;
+  position: number;
+}
+
+export type SecretKind =
+  | 'login'
+  | 'note'
+  | 'api_key'
+  | 'database'
+  | 'ssh_key'
+  | 'certificate'
+  | 'card';
+
+export type SecretPayload =
+  | { kind: 'note'; content: string }
+  | { kind: 'api_key'; key: string; endpoint?: string | null }
+  | {
+      kind: 'database';
+      engine: string;
+      host: string;
+      port?: number | null;
+      database: string;
+      username: string;
+      password: string;
+      options?: string | null;
+    }
+  | {
+      kind: 'ssh_key';
+      privateKey: string;
+      publicKey?: string | null;
+      passphrase?: string | null;
+    }
+  | {
+      kind: 'certificate';
+      certificate: string;
+      privateKey?: string | null;
+      chain?: string | null;
+    }
+  | {
+      kind: 'card';
+      cardholder: string;
+      number: string;
+      expiryMonth: number;
+      expiryYear: number;
+      cvv?: string | null;
+    };
+
 export interface AttachmentMetadata {
   id: number;
   secretId: number;
@@ -23,4 +107,244 @@ export interface Project {
   description?: string;
   createdAt: string;
   deletedAt?: string | null;
+  purgeAt?: string | null;
+}
+
+export interface ProjectEnvVar {
+  id: number;
+  projectId: number;
+  secretTitle: string;
+  envName: string;
+}
+
+export interface EnvBinding {
+  secretId: number;
+  secretTitle: string;
+  envName: string;
+  configured: boolean;
+}
+
+export interface AppError {
+  code: string;
+  message: string;
+  details?: string | null;
+}
+
+export type Locale = 'pt-BR' | 'en';
+
+export interface VaultInfo {
+  id: string;
+  name: string;
+  createdAt: string;
+  initialized: boolean;
+  unlocked: boolean;
+  external: boolean;
+}
+
+export interface Settings {
+  locale: Locale;
+  vaultsDir?: string | null;
+}
+
+export type ImportFormat =
+  | 'bitwarden-json'
+  | 'keepass-xml'
+  | '1password-1pux'
+  | '1password-csv'
+  | 'csv';
+
+export interface CsvMapping {
+  title: string;
+  username?: string;
+  password: string;
+  project?: string;
+  delimiter?: string;
+  ignore?: string[];
+}
+
+export interface UnmappedItem {
+  title: string;
+  fields: string[];
+  skipped: boolean;
+}
+
+export interface ExternalImportSummary {
+  projectsInserted: number;
+  projectsMerged: number;
+  secretsInserted: number;
+  secretsSkipped: number;
+  unmapped: UnmappedItem[];
+}
+
+export type ExportFormat = 'bitwarden-json' | 'keepass-xml' | 'csv';
+
+export interface LostData {
+  kind: string;
+  count: number;
+}
+
+export interface ExportReport {
+  format: ExportFormat;
+  secretsExported: number;
+  lost: LostData[];
+}
+
+export interface SecretVersion {
+  id: number;
+  secretId: number;
+  title: string;
+  username: string;
+  kind: SecretKind;
+  createdAt: string;
+}
+
+export interface VersionChange {
+  field: string;
+  before?: string;
+  after?: string;
+  masked: boolean;
+}
+
+export type TrashFilter = 'active' | 'deleted' | 'all';
+
+export interface SearchFilters {
+  projectId?: number;
+  kind?: SecretKind;
+  trash?: TrashFilter;
+  limit?: number;
+}
+
+export interface SearchHit {
+  entity: 'secret' | 'project';
+  id: number;
+  title: string;
+  username?: string;
+  kind?: SecretKind;
+  projectId?: number;
+  deletedAt?: string;
+  score: number;
+}
+
+export type SortField =
+  | 'id'
+  | 'title'
+  | 'username'
+  | 'createdAt'
+  | 'deletedAt'
+  | 'size';
+
+export interface QueryOptions {
+  limit?: number;
+  cursor?: string;
+  sort?: SortField;
+  direction?: 'asc' | 'desc';
+  projectId?: number;
+  createdFrom?: string;
+  createdTo?: string;
+  deletedFrom?: string;
+  deletedTo?: string;
+}
+
+export interface Page<T> {
+  items: T[];
+  nextCursor?: string;
+}
+
+export interface PasswordOptions {
+  length?: number;
+  lowercase?: boolean;
+  uppercase?: boolean;
+  digits?: boolean;
+  symbols?: boolean;
+  excludeAmbiguous?: boolean;
+  requireEachClass?: boolean;
+  pronounceable?: boolean;
+}
+
+export interface PassphraseOptions {
+  words?: number;
+  separator?: string;
+  capitalize?: boolean;
+  includeNumber?: boolean;
+  language?: 'en' | 'pt';
+}
+
+export type GeneratorOptions =
+  | ({ mode: 'password' } & PasswordOptions)
+  | ({ mode: 'passphrase' } & PassphraseOptions);
+
+export interface GeneratedSecret {
+  value: string;
+  entropyBits: number;
+}
+
+export type PatternKind =
+  | 'dictionary'
+  | 'sequence'
+  | 'repeat'
+  | 'keyboard'
+  | 'date'
+  | 'bruteforce';
+
+export type HealthIssue =
+  | {
+      type: 'weak';
+      score: number;
+      guessesLog10: number;
+      patterns: PatternKind[];
+    }
+  | { type: 'reused'; sharedWith: number[] }
+  | { type: 'old'; ageDays: number }
+  | { type: 'emptyUsername' };
+
+export interface SecretHealth {
+  secretId: number;
+  title: string;
+  username: string;
+  kind: SecretKind;
+  projectId?: number;
+  strength?: number;
+  passwordAgeDays: number;
+  score: number;
+  issues: HealthIssue[];
+}
+
+export interface HealthReport {
+  score: number;
+  scanned: number;
+  weak: number;
+  reused: number;
+  old: number;
+  emptyUsername: number;
+  passwordMaxAgeDays: number;
+  secrets: SecretHealth[];
+}
+
+export interface BreachCheckSummary {
+  checked: number;
+  breached: number;
+  breachedIds: number[];
+}
+
+export type TotpAlgorithm = 'SHA1' | 'SHA256' | 'SHA512';
+
+// Dados de exibição de uma semente TOTP; a chave nunca sai do cofre
+export interface TotpInfo {
+  issuer: string | null;
+  account: string | null;
+  algorithm: TotpAlgorithm;
+  digits: number;
+  period: number;
+}
+
+export interface TotpCode {
+  code: string;
+  secondsRemaining: number;
+  period: number;
+}
+
+export interface TrashPurgeSummary {
+  secrets: number;
+  projects: number;
+  attachments: number;
 }